@@ -0,0 +1,75 @@
+//! A small growable bitset over non-negative integers.
+//!
+//! Used by [`crate::nimbers`] to represent sets of nimbers,
+//! e.g. the nimbers of all options of a stack, for which the MEX has to be found.
+
+/// A growable set of [u64]s, stored as a bit vector
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Bitset {
+    /// The bits of the set; bit `i % 64` of word `i / 64` is set iff `i` is in the set
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    /// Insert a value into the set, growing the set if necessary
+    pub(crate) fn insert(&mut self, value: u64) {
+        let (word, bit) = Self::position(value);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << bit;
+    }
+
+    /// Check if a value is in the set
+    pub(crate) fn contains(&self, value: u64) -> bool {
+        let (word, bit) = Self::position(value);
+        self.words.get(word).is_some_and(|w| w & (1 << bit) != 0)
+    }
+
+    /// Add all values of `other` to this set
+    pub(crate) fn union_with(&mut self, other: &Bitset) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Check if every value of `other` is also in this set
+    pub(crate) fn is_superset(&self, other: &Bitset) -> bool {
+        other
+            .words
+            .iter()
+            .enumerate()
+            .all(|(i, other)| self.words.get(i).copied().unwrap_or(0) & other == *other)
+    }
+
+    /// Iterate over the values in the set in ascending order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let base = i as u64 * 64;
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| base + bit)
+        })
+    }
+
+    /// The minimum excluded value: the smallest non-negative integer not in the set
+    pub(crate) fn mex(&self) -> u64 {
+        self.words
+            .iter()
+            .position(|&word| word != u64::MAX)
+            .map_or(self.words.len() as u64 * 64, |i| {
+                i as u64 * 64 + u64::from(self.words[i].trailing_ones())
+            })
+    }
+
+    /// The word index and bit index of a value
+    fn position(value: u64) -> (usize, u64) {
+        (
+            usize::try_from(value / 64).expect("Bitset index out of range"),
+            value % 64,
+        )
+    }
+}
@@ -0,0 +1,210 @@
+//! The integer types which count coins: the heights of stacks, the pools of the players, and nimbers.
+//! See [`Coins`] for the supported types.
+
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// An unsigned integer type counting coins (see [`crate::Stack`], [`crate::Nimber`] and [`crate::NimGame`])
+///
+/// Implemented for [u64] (the default everywhere), [u128],
+/// and `num_bigint::BigUint` with the `bigint` feature, whose stacks can be arbitrarily high.
+/// The nimbers of single stacks are calculated in tables indexed by [u64],
+/// so stacks above [`u64::MAX`] can be played on, but their nimbers can't be calculated
+/// (see [`crate::Stack::to_u64`]).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{moves, NimAction, NimGameBuilder, NimRule, NimSplit, Split, Stack, TakeAction, TakeSize};
+///
+/// let mut game = NimGameBuilder::<u128>::new()
+///     .rules([NimRule {
+///         take: TakeSize::Any,
+///         split: Split::Never,
+///     }])
+///     .stacks(vec![Stack(1 << 100)])
+///     .build()
+///     .unwrap();
+///
+/// let take = NimAction::Take(TakeAction {
+///     stack_index: 0,
+///     amount: 1 << 99,
+///     split: NimSplit::No,
+///     from: None,
+///     stack_id: None,
+/// });
+/// moves::apply_move(&mut game, &take).unwrap();
+///
+/// assert_eq!(game.get_stacks(), &vec![Stack(1 << 99)]);
+/// ```
+pub trait Coins:
+    Clone
+    + Debug
+    + Display
+    + Default
+    + PartialEq
+    + Eq
+    + PartialOrd
+    + Ord
+    + Hash
+    + Send
+    + Sync
+    + From<u64>
+    + Serialize
+    + DeserializeOwned
+    + 'static
+{
+    /// Add two numbers of coins, or [`None`] if the result doesn't fit into the type
+    fn try_add(&self, rhs: &Self) -> Option<Self>;
+
+    /// Subtract a number of coins, or [`None`] if there are not enough coins
+    fn try_sub(&self, rhs: &Self) -> Option<Self>;
+
+    /// Convert the number of coins to a [u64], or [`None`] if it is too large
+    fn to_u64(&self) -> Option<u64>;
+
+    /// The number of bits needed to represent the number (0 for 0)
+    fn bit_len(&self) -> u64;
+
+    /// The bitwise XOR of two numbers (nim-addition)
+    #[must_use]
+    fn xor(&self, rhs: &Self) -> Self;
+
+    /// Shift the number to the left by `bits` bits (which must not shift out any set bits)
+    #[must_use]
+    fn shl_bits(&self, bits: u64) -> Self;
+
+    /// Shift the number to the right by `bits` bits
+    #[must_use]
+    fn shr_bits(&self, bits: u64) -> Self;
+
+    /// Keep only the lowest `bits` bits of the number
+    #[must_use]
+    fn low_bits(&self, bits: u64) -> Self;
+
+    /// Append a stable encoding of the number to `bytes` (used for fingerprints)
+    ///
+    /// Numbers which fit into a [u64] are encoded as 8 little-endian bytes by every type,
+    /// so equal numbers of coins have equal encodings.
+    fn encode(&self, bytes: &mut Vec<u8>);
+
+    /// Check if the number is 0
+    fn is_zero(&self) -> bool {
+        *self == Self::from(0)
+    }
+}
+
+/// Implement [`Coins`] for primitive unsigned integers
+macro_rules! impl_coins {
+    ($($int:ty),*) => {$(
+        impl Coins for $int {
+            fn try_add(&self, rhs: &Self) -> Option<Self> {
+                <$int>::checked_add(*self, *rhs)
+            }
+
+            fn try_sub(&self, rhs: &Self) -> Option<Self> {
+                <$int>::checked_sub(*self, *rhs)
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                u64::try_from(*self).ok()
+            }
+
+            fn bit_len(&self) -> u64 {
+                u64::from(<$int>::BITS - self.leading_zeros())
+            }
+
+            fn xor(&self, rhs: &Self) -> Self {
+                self ^ rhs
+            }
+
+            fn shl_bits(&self, bits: u64) -> Self {
+                u32::try_from(bits)
+                    .ok()
+                    .and_then(|bits| self.checked_shl(bits))
+                    .unwrap_or(0)
+            }
+
+            fn shr_bits(&self, bits: u64) -> Self {
+                u32::try_from(bits)
+                    .ok()
+                    .and_then(|bits| self.checked_shr(bits))
+                    .unwrap_or(0)
+            }
+
+            fn low_bits(&self, bits: u64) -> Self {
+                self ^ self.shr_bits(bits).shl_bits(bits)
+            }
+
+            fn encode(&self, bytes: &mut Vec<u8>) {
+                match self.to_u64() {
+                    Some(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+                    None => encode_large(&self.to_le_bytes(), bytes),
+                }
+            }
+        }
+    )*};
+}
+
+impl_coins!(u64, u128);
+
+/// Encode a number above [`u64::MAX`] from its little-endian bytes, see [`Coins::encode`]
+///
+/// The encoding starts with the marker `u64::MAX` (so it differs from every encoded [u64]),
+/// followed by the number of significant bytes and the bytes themselves.
+fn encode_large(le_bytes: &[u8], bytes: &mut Vec<u8>) {
+    let len = le_bytes
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |i| i + 1);
+
+    bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    bytes.extend_from_slice(&(len as u64).to_le_bytes());
+    bytes.extend_from_slice(&le_bytes[..len]);
+}
+
+#[cfg(feature = "bigint")]
+impl Coins for num_bigint::BigUint {
+    fn try_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn try_sub(&self, rhs: &Self) -> Option<Self> {
+        (self >= rhs).then(|| self - rhs)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self).ok()
+    }
+
+    fn bit_len(&self) -> u64 {
+        self.bits()
+    }
+
+    fn xor(&self, rhs: &Self) -> Self {
+        self ^ rhs
+    }
+
+    fn shl_bits(&self, bits: u64) -> Self {
+        self << bits
+    }
+
+    fn shr_bits(&self, bits: u64) -> Self {
+        self >> bits
+    }
+
+    fn low_bits(&self, bits: u64) -> Self {
+        self ^ ((self >> bits) << bits)
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        match self.to_u64() {
+            Some(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            None => encode_large(&self.to_bytes_le(), bytes),
+        }
+    }
+}
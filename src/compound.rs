@@ -0,0 +1,251 @@
+//! Conjunctive and selective sums of stacks, where a move is made in every stack or in any selection of the stacks
+//! (see [`Compound`] and [`crate::NimGame::set_compound`]).
+//! The nimbers of the stacks only describe disjunctive sums, where a move is made in exactly one stack.
+//! The other sums have theories of their own, which again reduce a position to numbers of its single stacks:
+//! the remoteness of the stacks for conjunctive sums (see [`suspense_numbers`]),
+//! and the outcomes of the stacks for selective sums (see [`selective_outcomes`]).
+//!
+//! In either sum, the stacks created by a split are stacks of the sum,
+//! so a split stack counts like the sum of the two resulting stacks.
+
+use crate::{
+    moves,
+    nimbers::to_index,
+    solver::{check_stackwise, check_two_players, Outcome, SolveError},
+    Compound, NimAction, NimGame, NimSplit, PlayConvention, Player, RuleSet, Stack,
+};
+
+/// Calculate the remoteness of single stacks of all heights up to `max_height` in conjunctive sums
+///
+/// A conjunctive sum ends when none of its stacks allows a move anymore,
+/// so it lasts as long as its longest stack.
+/// The player who wins a stack (on its own) tries to make it last as long as possible,
+/// and the player who loses it tries to end it as soon as possible.
+/// The number of moves a stack lasts under this play is its remoteness in the conjunctive sum,
+/// known as its _suspense number_ after Conway.
+/// (The remoteness of Steinhaus and Smith, where the winner hurries and the loser delays,
+/// describes sums which end as soon as their first stack ends.)
+///
+/// A stack without moves has a suspense number of 0.
+/// A stack which the player to move wins has an odd suspense number under normal play (even under misère play),
+/// one more than the largest suspense number of the options which the opponent loses;
+/// a stack which the player to move loses has one more than the smallest suspense number of its options.
+/// The player to move wins a conjunctive sum if and only if the largest suspense number of its stacks
+/// is odd under normal play (even under misère play), see [`solve`].
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{compound, NimRule, PlayConvention, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// assert_eq!(
+///     compound::suspense_numbers(&rules, PlayConvention::Normal, 6),
+///     Ok(vec![0, 1, 1, 2, 3, 3, 4])
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::Partizan`] if the rules are partizan,
+/// [`SolveError::PlaceRule`] if the rules allow placing coins,
+/// or [`SolveError::NotStackwise`] if the rules allow moves which involve several stacks or depend on the previous move.
+///
+/// # Panics
+///
+/// Panics if the numbers don't fit into memory.
+pub fn suspense_numbers(
+    rules: &RuleSet,
+    convention: PlayConvention,
+    max_height: u64,
+) -> Result<Vec<u64>, SolveError> {
+    check_impartial(rules)?;
+
+    let mut numbers: Vec<u64> = Vec::with_capacity(to_index(max_height) + 1);
+    for height in 0..=max_height {
+        let options: Vec<u64> = stack_options(rules, height)
+            .into_iter()
+            .map(|stacks| {
+                stacks
+                    .into_iter()
+                    .map(|stack| numbers[to_index(stack.0)])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        // The options which the opponent loses
+        let losing = options
+            .iter()
+            .filter(|&&number| is_lost(number, convention));
+        numbers.push(match losing.max() {
+            Some(number) => number + 1,
+            None => options.iter().min().map_or(0, |number| number + 1),
+        });
+    }
+
+    Ok(numbers)
+}
+
+/// Calculate the outcomes of single stacks of all heights up to `max_height` in selective sums
+///
+/// The player to move loses a selective sum under normal play if and only if they lose every stack on its own:
+/// if they win some stacks, they move in exactly these stacks to lost positions,
+/// and whichever stacks the opponent moves in next are won again.
+/// So a stack is lost if all its options are won, where a split stack is only lost if both resulting stacks are lost.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{compound, solver::Outcome, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// let outcomes = compound::selective_outcomes(&rules, 6).unwrap();
+/// assert_eq!(outcomes[3], Outcome::Loss);
+/// assert_eq!(outcomes[4], Outcome::Win);
+/// ```
+///
+/// # Errors
+///
+/// Returns the errors of [`suspense_numbers`].
+///
+/// # Panics
+///
+/// Panics if the outcomes don't fit into memory.
+pub fn selective_outcomes(rules: &RuleSet, max_height: u64) -> Result<Vec<Outcome>, SolveError> {
+    check_impartial(rules)?;
+
+    let mut outcomes: Vec<Outcome> = Vec::with_capacity(to_index(max_height) + 1);
+    for height in 0..=max_height {
+        let wins = stack_options(rules, height).into_iter().any(|stacks| {
+            stacks
+                .iter()
+                .all(|stack| outcomes[to_index(stack.0)] == Outcome::Loss)
+        });
+        outcomes.push(if wins { Outcome::Win } else { Outcome::Loss });
+    }
+
+    Ok(outcomes)
+}
+
+/// Find the outcome of a conjunctive or selective sum for the player whose turn it is
+///
+/// Conjunctive sums are solved with the suspense numbers of their stacks (see [`suspense_numbers`]),
+/// under normal or misère play.
+/// Selective sums are solved with the outcomes of their stacks (see [`selective_outcomes`]), under normal play.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{compound, solver::Outcome, Compound, NimGame, NimRule, Split, Stack, TakeSize};
+///
+/// let game = |compound, stacks| {
+///     NimGame::builder()
+///         .rules([NimRule {
+///             take: TakeSize::List(vec![1, 2]),
+///             split: Split::Never,
+///         }])
+///         .stacks(stacks)
+///         .compound(compound)
+///         .build()
+///         .unwrap()
+/// };
+///
+/// // The stack of 3 coins lasts longest, and the player to move loses it
+/// let conjunctive = game(Compound::Conjunctive, vec![Stack(1), Stack(2), Stack(3)]);
+/// assert_eq!(compound::solve(&conjunctive), Ok(Outcome::Loss));
+///
+/// // The player to move wins the stacks of 1 and 2 coins, and takes them both
+/// let selective = game(Compound::Selective, vec![Stack(1), Stack(2), Stack(3)]);
+/// assert_eq!(compound::solve(&selective), Ok(Outcome::Win));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::Disjunctive`] if the stacks are a disjunctive sum,
+/// [`SolveError::NotTwoPlayers`] if the game doesn't have two players,
+/// [`SolveError::MisereSelective`] for selective sums under the misère play convention,
+/// or the errors of [`suspense_numbers`].
+pub fn solve(game: &NimGame) -> Result<Outcome, SolveError> {
+    check_two_players(game)?;
+
+    let max_height = game.get_stacks().iter().map(|stack| stack.0).max();
+    let convention = game.get_convention();
+
+    match game.get_compound() {
+        Compound::Disjunctive => Err(SolveError::Disjunctive),
+        Compound::Conjunctive => {
+            let numbers = suspense_numbers(game.get_rules(), convention, max_height.unwrap_or(0))?;
+            let longest = game
+                .get_stacks()
+                .iter()
+                .map(|stack| numbers[to_index(stack.0)])
+                .max()
+                .unwrap_or(0);
+
+            Ok(if is_lost(longest, convention) {
+                Outcome::Loss
+            } else {
+                Outcome::Win
+            })
+        }
+        Compound::Selective => {
+            if convention == PlayConvention::Misere {
+                return Err(SolveError::MisereSelective);
+            }
+
+            let outcomes = selective_outcomes(game.get_rules(), max_height.unwrap_or(0))?;
+            Ok(
+                if game
+                    .get_stacks()
+                    .iter()
+                    .all(|stack| outcomes[to_index(stack.0)] == Outcome::Loss)
+                {
+                    Outcome::Loss
+                } else {
+                    Outcome::Win
+                },
+            )
+        }
+    }
+}
+
+/// Check if the rules are impartial, and only involve a single stack
+fn check_impartial(rules: &RuleSet) -> Result<(), SolveError> {
+    if rules.is_partizan() {
+        return Err(SolveError::Partizan);
+    }
+    check_stackwise(rules)
+}
+
+/// Check if the player to move loses a stack (or sum) with the given suspense number
+fn is_lost(number: u64, convention: PlayConvention) -> bool {
+    match convention {
+        PlayConvention::Normal => number.is_multiple_of(2),
+        PlayConvention::Misere => !number.is_multiple_of(2),
+    }
+}
+
+/// Get the stacks of each option of a single stack (two stacks after a split, one otherwise)
+fn stack_options(rules: &RuleSet, height: u64) -> Vec<Vec<Stack>> {
+    moves::legal_moves(&[Stack(height)], rules.rules(), &[], Player::A, None)
+        .into_iter()
+        .filter_map(|mov| match mov {
+            NimAction::Take(take) => Some(match take.split {
+                NimSplit::Yes(a, b) => vec![a, b],
+                NimSplit::No => vec![Stack(height - take.amount)],
+            }),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! Fibonacci Nim, where the first move may take any number of coins but not the whole stack,
+//! and every later move may take up to twice the coins taken by the previous move.
+//! The moves depend on the previous move (see [`crate::NimGame::get_last_take`]),
+//! so the outcomes are found with the Zeckendorf representation of the height instead of nimbers (see [`solve`]).
+
+use crate::{
+    moves::take_limit,
+    solver::{check_two_players, Outcome, SolveError},
+    Coins, NimAction, NimGame, NimRule, NimSplit, PlayConvention, RuleSet, Split, TakeAction,
+    TakeSize,
+};
+
+/// Check if the rules are those of Fibonacci Nim
+///
+/// The rules of Fibonacci Nim are a single [`TakeSize::UpToMultipleOfLast`] rule with a multiple of 2,
+/// without splitting.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{fibonacci, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::UpToMultipleOfLast(2),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// assert!(fibonacci::is_fibonacci_nim(&rules));
+/// ```
+#[must_use]
+pub fn is_fibonacci_nim(rules: &RuleSet) -> bool {
+    !rules.is_partizan()
+        && rules.rules()
+            == [NimRule {
+                take: TakeSize::UpToMultipleOfLast(2),
+                split: Split::Never,
+            }]
+}
+
+/// Calculate the Zeckendorf representation of a number
+///
+/// Every number is the sum of a unique set of non-consecutive Fibonacci numbers (1, 2, 3, 5, 8, ...),
+/// which are found greedily. The terms are returned from the largest to the smallest;
+/// the representation of 0 is empty.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::fibonacci;
+///
+/// assert_eq!(fibonacci::zeckendorf(&20_u64), vec![13, 5, 2]);
+/// assert_eq!(fibonacci::zeckendorf(&21_u64), vec![21]);
+/// assert!(fibonacci::zeckendorf(&0_u64).is_empty());
+/// ```
+pub fn zeckendorf<C: Coins>(n: &C) -> Vec<C> {
+    // The Fibonacci numbers up to `n` (as far as they can be counted)
+    let mut fibonacci = vec![C::from(1), C::from(2)];
+    while fibonacci[fibonacci.len() - 1] <= *n {
+        let len = fibonacci.len();
+        match fibonacci[len - 2].try_add(&fibonacci[len - 1]) {
+            Some(next) => fibonacci.push(next),
+            None => break,
+        }
+    }
+
+    let mut rest = n.clone();
+    let mut terms = Vec::new();
+    for number in fibonacci.into_iter().rev() {
+        if let Some(remainder) = rest.try_sub(&number) {
+            rest = remainder;
+            terms.push(number);
+        }
+    }
+    terms
+}
+
+/// Find the outcome of a position of Fibonacci Nim for the player whose turn it is
+///
+/// Under normal play, the player to move wins if and only if the smallest term of the
+/// Zeckendorf representation of the height (see [`zeckendorf`]) may be taken:
+/// taking it leaves a height whose smallest term is more than twice as large, which the opponent can't take.
+/// In particular, the first player loses if and only if the height is a Fibonacci number.
+///
+/// Under misère play, the player taking the last coin loses, so the height is one coin less in effect:
+/// a position with a limit (after the first move) has the outcome of the position with one coin less under normal play,
+/// and on the first move, the player to move wins by leaving a single coin (if there are at least 2 coins).
+/// As usual, the player who can't move wins under misère play.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{fibonacci, solver::Outcome, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::UpToMultipleOfLast(2),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// // 13 is a Fibonacci number
+/// let game = NimGame::new(rules.clone(), vec![Stack(13)]);
+/// assert_eq!(fibonacci::solve(&game), Ok(Outcome::Loss));
+///
+/// // 20 = 13 + 5 + 2, so the player to move takes 2 coins
+/// let game = NimGame::new(rules.clone(), vec![Stack(20)]);
+/// assert_eq!(fibonacci::solve(&game), Ok(Outcome::Win));
+///
+/// // After a move which took 10 coins, up to 20 of the 21 coins may be taken
+/// let mut game = NimGame::new(rules, vec![Stack(21)]);
+/// game.set_last_take(Some(10));
+/// assert_eq!(fibonacci::solve(&game), Ok(Outcome::Loss));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotFibonacciNim`] if the rules of the game are not those of Fibonacci Nim,
+/// [`SolveError::TooManyStacks`] if more than one stack is non-empty,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn solve<C: Coins>(game: &NimGame<C>) -> Result<Outcome, SolveError> {
+    let take = winning_take(game)?;
+
+    // Only empty stacks, or a single coin which can't be taken on the first move
+    let stuck = game
+        .get_stacks()
+        .iter()
+        .all(|stack| stack.0 <= C::from(u64::from(game.get_last_take().is_none())));
+    if stuck && game.get_convention() == PlayConvention::Misere {
+        return Ok(Outcome::Win);
+    }
+
+    Ok(match take {
+        Some(_) => Outcome::Win,
+        None => Outcome::Loss,
+    })
+}
+
+/// Find a winning move of a position of Fibonacci Nim for the player whose turn it is, if there is one
+///
+/// See [`solve`] for the strategy. The move refers to the non-empty stack.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{fibonacci, NimAction, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::UpToMultipleOfLast(2),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// let game = NimGame::new(rules, vec![Stack(20)]);
+/// let Some(NimAction::Take(take)) = fibonacci::winning_move(&game).unwrap() else {
+///     panic!("Expected a winning take");
+/// };
+/// assert_eq!(take.amount, 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotFibonacciNim`] if the rules of the game are not those of Fibonacci Nim,
+/// [`SolveError::TooManyStacks`] if more than one stack is non-empty,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn winning_move<C: Coins>(game: &NimGame<C>) -> Result<Option<NimAction<C>>, SolveError> {
+    Ok(winning_take(game)?.map(|(stack_index, amount)| {
+        NimAction::Take(TakeAction {
+            stack_index,
+            amount,
+            split: NimSplit::No,
+            from: None,
+            stack_id: game
+                .get_stack_ids()
+                .and_then(|ids| ids.get(stack_index))
+                .copied(),
+        })
+    }))
+}
+
+/// Find the index of the non-empty stack and the number of coins to take from it to win, if possible
+fn winning_take<C: Coins>(game: &NimGame<C>) -> Result<Option<(usize, C)>, SolveError> {
+    if !is_fibonacci_nim(game.get_rules()) {
+        return Err(SolveError::NotFibonacciNim);
+    }
+    check_two_players(game)?;
+
+    let stacks: Vec<(usize, &C)> = game
+        .get_stacks()
+        .iter()
+        .map(|stack| &stack.0)
+        .enumerate()
+        .filter(|(_, height)| !height.is_zero())
+        .collect();
+    let (stack_index, height) = match stacks.as_slice() {
+        [] => return Ok(None),
+        [stack] => *stack,
+        _ => {
+            return Err(SolveError::TooManyStacks {
+                stack_count: stacks.len(),
+                max_stacks: 1,
+            })
+        }
+    };
+
+    let last_take = game.get_last_take();
+    let amount = match (game.get_convention(), last_take) {
+        (PlayConvention::Normal, last_take) => {
+            let limit = take_limit(2, last_take.as_ref(), height);
+            normal_take(height, limit.as_ref())
+        }
+        // Leave a single coin, which the opponent has to take
+        (PlayConvention::Misere, None) => height
+            .try_sub(&C::from(1))
+            .filter(|amount| !amount.is_zero()),
+        // Play normally on all coins but one, which the opponent has to take in the end
+        (PlayConvention::Misere, Some(last_take)) => {
+            let rest = height.try_sub(&C::from(1)).unwrap_or_else(|| C::from(0));
+            let limit = take_limit(2, Some(&last_take), &rest);
+            normal_take(&rest, limit.as_ref())
+        }
+    };
+
+    Ok(amount.map(|amount| (stack_index, amount)))
+}
+
+/// Find the number of coins to take from a stack to win under normal play, given the limit of the take
+///
+/// A limit of [`None`] allows taking any number of coins.
+fn normal_take<C: Coins>(height: &C, limit: Option<&C>) -> Option<C> {
+    let smallest = zeckendorf(height).pop()?;
+    limit
+        .is_none_or(|limit| smallest <= *limit)
+        .then_some(smallest)
+}
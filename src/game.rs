@@ -2,137 +2,1565 @@
 //! For game logic, see [`crate::nimbers`].
 
 use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    error::Error,
     fmt::{Debug, Display},
+    hash::{DefaultHasher, Hash, Hasher},
     ops::BitXor,
 };
 
 use clap::ValueEnum;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
-use crate::nimbers;
+use crate::{
+    moves,
+    nimbers::{self, NimberError},
+    CanonicalPosition, Coins, RuleSet, RuleSetError,
+};
 
 /// # A Nim game
 ///
-/// This struct uses [`NimRule`]s to calculate the nimber of the position.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// This struct uses [`NimRule`]s to calculate the nimber of the position.  
+/// The rules are kept in canonical form (see [`RuleSet`]),
+/// so games with equivalent rules and the same stacks are equal.  
+/// Games are compared (and hashed) by their positions: the rules, the stacks, the pools,
+/// the player whose turn it is, the play convention, how the stacks are combined, and the coins taken by the last move.
+/// Bookkeeping like stack IDs, tracked nimbers, or normalizing after every move doesn't make games differ.
+///
+/// Create a game with [`NimGame::new`], or with [`NimGame::builder`] to set every part of it.  
+/// The heights of the stacks and the pools are [u64]s by default,
+/// use [`NimGameBuilder::new`] for other types of [`Coins`] (e.g. `NimGame<u128>`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "GameData<C>", bound = "C: Coins")]
+#[allow(clippy::module_name_repetitions)]
+pub struct NimGame<C = u64> {
+    /// The rules of the game (e.g. which numbers of coins can be taken)
+    pub(crate) rules: RuleSet,
+
+    /// The stacks of the game, represented as their current heights
+    pub(crate) stacks: Vec<Stack<C>>,
+
+    /// The number of coins in the pool of each player, which makes for the number of players  
+    /// (ignored by the nimber calculation for now)
+    pub(crate) pools: Vec<C>,
+
+    /// The player whose turn it is
+    #[serde(default)]
+    pub(crate) to_move: Player,
+
+    /// Whether the player making the last move wins or loses
+    #[serde(default)]
+    pub(crate) convention: PlayConvention,
+
+    /// How the stacks are combined, i.e. in how many stacks a move is made (see [`NimGame::get_compound`])
+    #[serde(default, skip_serializing_if = "is_disjunctive")]
+    pub(crate) compound: Compound,
+
+    /// The largest number of coins taken from a stack by the last move which took coins,
+    /// if any and if the rules depend on it (see [`NimGame::get_last_take`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_take: Option<C>,
+
+    /// The stable IDs of the stacks, if enabled (see [`NimGame::set_stack_ids`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) stack_ids: Option<StackIds>,
+
+    /// Whether the stacks are normalized after every move (see [`NimGame::normalize`])
+    #[serde(default)]
+    pub(crate) auto_normalize: bool,
+
+    /// The nimbers of the stacks and the position, if tracked (see [`NimGame::set_track_nimber`])  
+    /// (only serialized as a flag, the nimbers are restored when deserializing)
+    #[serde(
+        rename = "track_nimber",
+        serialize_with = "serialize_is_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) nimbers: Option<NimberTracker<C>>,
+}
+
+/// The serialized form of a [`NimGame`], from which the state derived from the stacks is restored
+#[derive(Deserialize)]
+#[serde(bound = "C: Coins")]
+struct GameData<C> {
+    /// See [`NimGame::rules`]
+    rules: RuleSet,
+
+    /// See [`NimGame::stacks`]
+    stacks: Vec<Stack<C>>,
+
+    /// See [`NimGame::pools`]
+    #[serde(default)]
+    pools: Option<Vec<C>>,
+
+    /// The number of coins in the pool of player A, for games of two players serialized without `pools`
+    #[serde(default)]
+    coins_a: Option<C>,
+
+    /// The number of coins in the pool of player B, for games of two players serialized without `pools`
+    #[serde(default)]
+    coins_b: Option<C>,
+
+    /// See [`NimGame::to_move`]
+    #[serde(default)]
+    to_move: Player,
+
+    /// See [`NimGame::convention`]
+    #[serde(default)]
+    convention: PlayConvention,
+
+    /// See [`NimGame::compound`]
+    #[serde(default)]
+    compound: Compound,
+
+    /// See [`NimGame::last_take`]
+    #[serde(default)]
+    last_take: Option<C>,
+
+    /// See [`NimGame::stack_ids`]
+    #[serde(default)]
+    stack_ids: Option<StackIds>,
+
+    /// See [`NimGame::auto_normalize`]
+    #[serde(default)]
+    auto_normalize: bool,
+
+    /// Whether the nimbers are tracked, see [`NimGame::nimbers`]
+    #[serde(default)]
+    track_nimber: bool,
+}
+
+impl<C: Coins> TryFrom<GameData<C>> for NimGame<C> {
+    type Error = GameError<C>;
+
+    fn try_from(data: GameData<C>) -> Result<Self, Self::Error> {
+        let pools = data.pools.unwrap_or_else(|| {
+            vec![
+                data.coins_a.unwrap_or_else(|| C::from(0)),
+                data.coins_b.unwrap_or_else(|| C::from(0)),
+            ]
+        });
+        check_game(&data.rules, &pools, data.to_move, data.compound)?;
+
+        let mut game = NimGame {
+            rules: data.rules,
+            stacks: data.stacks,
+            pools,
+            to_move: data.to_move,
+            convention: data.convention,
+            compound: data.compound,
+            last_take: None,
+            stack_ids: data.stack_ids,
+            auto_normalize: data.auto_normalize,
+            nimbers: None,
+        };
+
+        game.set_last_take(data.last_take);
+
+        // Every stack needs an ID of its own, and new stacks need unused IDs
+        if let Some(ids) = &mut game.stack_ids {
+            let mut seen = HashSet::new();
+            if let Some(&id) = ids.ids.iter().find(|&&id| !seen.insert(id)) {
+                return Err(GameError::DuplicateStackId { id });
+            }
+
+            if ids.ids.len() != game.stacks.len() {
+                return Err(GameError::StackIdCount {
+                    id_count: ids.ids.len(),
+                    stack_count: game.stacks.len(),
+                });
+            }
+
+            let unused = ids.ids.iter().map(|id| id.0.saturating_add(1)).max();
+            ids.next = ids.next.max(unused.unwrap_or(0));
+        }
+
+        if game.auto_normalize {
+            game.normalize();
+        }
+        game.set_track_nimber(data.track_nimber)?;
+
+        Ok(game)
+    }
+}
+
+/// The parts of a [`NimGame`] which make up its position, by which games are compared and hashed
+type PositionKey<'a, C> = (
+    &'a RuleSet,
+    &'a [Stack<C>],
+    &'a [C],
+    Player,
+    PlayConvention,
+    Compound,
+    Option<&'a C>,
+);
+
+impl<C> NimGame<C> {
+    /// Get the parts of the game which make up its position (see [`NimGame`])
+    fn position_key(&self) -> PositionKey<'_, C> {
+        (
+            &self.rules,
+            &self.stacks,
+            &self.pools,
+            self.to_move,
+            self.convention,
+            self.compound,
+            self.last_take.as_ref(),
+        )
+    }
+}
+
+impl<C: Hash> NimGame<C> {
+    /// Hash the position of the game together with its stack IDs
+    ///
+    /// Unlike [`NimGame::canonical_position`], this tells apart games whose stacks are merely in a different order,
+    /// so it identifies the exact state a move was applied to (or resulted in).
+    pub(crate) fn state_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.position_key().hash(&mut hasher);
+        self.stack_ids.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<C: PartialEq> PartialEq for NimGame<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_key() == other.position_key()
+    }
+}
+
+impl<C: Eq> Eq for NimGame<C> {}
+
+impl<C: PartialOrd> PartialOrd for NimGame<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.position_key().partial_cmp(&other.position_key())
+    }
+}
+
+impl<C: Ord> Ord for NimGame<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position_key().cmp(&other.position_key())
+    }
+}
+
+impl<C: Hash> Hash for NimGame<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position_key().hash(state);
+    }
+}
+
+/// Serialize whether the nimbers of a game are tracked
+#[allow(clippy::ref_option)]
+fn serialize_is_some<C, S: Serializer>(
+    nimbers: &Option<NimberTracker<C>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(nimbers.is_some())
+}
+
+/// The nimbers of the stacks of a [`NimGame`], and their XOR (the nimber of the position)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct NimberTracker<C> {
+    /// The nimber of each stack, in the order of the stacks
+    stack_nimbers: Vec<Nimber<C>>,
+
+    /// The XOR of the nimbers of the stacks
+    nimber: Nimber<C>,
+}
+
+impl<C: Coins> NimberTracker<C> {
+    /// Look up the nimbers of the given stacks
+    ///
+    /// Returns [`GameError::StackTooHigh`] if a stack is higher than [`u64::MAX`].
+    fn new(stacks: &[Stack<C>], rules: &RuleSet) -> Result<Self, GameError<C>> {
+        let stack_nimbers = stacks
+            .iter()
+            .map(|stack| stack_nimber(stack, rules))
+            .collect::<Result<Vec<Nimber<C>>, _>>()?;
+        let nimber = stack_nimbers.iter().sum();
+
+        Ok(Self {
+            stack_nimbers,
+            nimber,
+        })
+    }
+}
+
+/// Look up the nimber of a stack given a (validated) rule set
+///
+/// Returns [`GameError::StackTooHigh`] if the stack is higher than [`u64::MAX`],
+/// as the nimber tables are indexed by [u64].
+fn stack_nimber<C: Coins>(stack: &Stack<C>, rules: &RuleSet) -> Result<Nimber<C>, GameError<C>> {
+    let height = stack.0.to_u64().ok_or_else(|| GameError::StackTooHigh {
+        height: stack.0.clone(),
+    })?;
+    Ok(Nimber(C::from(nimbers::nimber_for_height(height, rules).0)))
+}
+
+/// A stack together with its ID and nimber (if the game keeps track of them)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct StackEntry<C> {
+    /// The stack itself
+    pub(crate) stack: Stack<C>,
+
+    /// The ID of the stack, or [`None`] for a new stack
+    pub(crate) id: Option<StackId>,
+
+    /// The nimber of the stack, or [`None`] if it has to be looked up
+    pub(crate) nimber: Option<Nimber<C>>,
+}
+
+/// The stable IDs of the stacks of a [`NimGame`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub(crate) struct StackIds {
+    /// The ID of each stack, in the order of the stacks
+    pub(crate) ids: Vec<StackId>,
+
+    /// The ID given to the next new stack
+    pub(crate) next: u64,
+}
+
+impl StackIds {
+    /// Give new IDs to the given number of stacks
+    fn new(count: usize) -> Self {
+        let mut ids = StackIds {
+            ids: Vec::with_capacity(count),
+            next: 0,
+        };
+        ids.ids = (0..count).map(|_| ids.create()).collect();
+        ids
+    }
+
+    /// Create an ID for a new stack
+    pub(crate) fn create(&mut self) -> StackId {
+        let id = StackId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+impl<C: Coins> NimGame<C> {
+    /// Get the stacks currently in the game
+    ///
+    /// Retrieves the position as a shared reference to vector of [Stack]s.
+    #[must_use]
+    pub fn get_stacks(&self) -> &Vec<Stack<C>> {
+        &self.stacks
+    }
+
+    /// Get the rules of the game (in canonical form)
+    #[must_use]
+    pub fn get_rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Get the player whose turn it is
+    ///
+    /// Player A moves first (unless set otherwise);
+    /// the turn passes to the next player whenever a move is applied (see [`NimGame::next_player`]).
+    #[must_use]
+    pub fn get_to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Get the number of players of the game
+    ///
+    /// Games have two players (A and B) unless set otherwise (see [`NimGameBuilder::players`]).
+    #[must_use]
+    pub fn get_player_count(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Get the player whose turn comes after the given player's turn
+    ///
+    /// The players take turns in the order A, B, C, ..., and the first player follows the last one.
+    #[must_use]
+    pub fn next_player(&self, player: Player) -> Player {
+        player.next(self.get_player_count())
+    }
+
+    /// Get the number of coins in the pool of a player
+    ///
+    /// Players who don't take part in the game have no coins.
+    #[must_use]
+    pub fn get_pool_coins(&self, player: Player) -> C {
+        self.pools
+            .get(player.0)
+            .cloned()
+            .unwrap_or_else(|| C::from(0))
+    }
+
+    /// Get the number of coins in the pool of each player, in the order of the players
+    #[must_use]
+    pub fn get_pools(&self) -> &[C] {
+        &self.pools
+    }
+
+    /// Get the play convention of the game
+    #[must_use]
+    pub fn get_convention(&self) -> PlayConvention {
+        self.convention
+    }
+
+    /// Get how the stacks of the game are combined, i.e. in how many stacks a move is made
+    ///
+    /// Games are disjunctive sums of their stacks (a move is made in exactly one stack) unless set otherwise.
+    #[must_use]
+    pub fn get_compound(&self) -> Compound {
+        self.compound
+    }
+
+    /// Get the largest number of coins taken from a single stack by the last move which took coins
+    ///
+    /// This is `None` before the first such move, and if the rules don't depend on it
+    /// (see [`RuleSet::depends_on_last_take`]).
+    /// Rules like [`TakeSize::UpToMultipleOfLast`] limit the next take by it.  
+    /// Moves which place coins don't change it.
+    #[must_use]
+    pub fn get_last_take(&self) -> Option<C> {
+        self.last_take.clone()
+    }
+
+    /// Replace the stacks of the game
+    ///
+    /// If stack IDs are enabled, the new stacks get new IDs.  
+    /// The new stacks are normalized and their nimbers are looked up, if enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::StackTooHigh`] if the nimbers are tracked and a stack is higher than [`u64::MAX`]
+    /// (see [`NimGame::set_track_nimber`]).
+    /// The game is not changed if an error is returned.
+    pub fn set_stacks(&mut self, stacks: Vec<Stack<C>>) -> Result<(), GameError<C>> {
+        if self.nimbers.is_some() {
+            self.nimbers = Some(NimberTracker::new(&stacks, &self.rules)?);
+        }
+        if let Some(ids) = &mut self.stack_ids {
+            ids.ids = stacks.iter().map(|_| ids.create()).collect();
+        }
+        self.stacks = stacks;
+
+        if self.auto_normalize {
+            self.normalize();
+        }
+
+        Ok(())
+    }
+
+    /// Replace `count` stacks at `index` with the given stacks, keeping their IDs and nimbers in line
+    ///
+    /// Stacks without an ID get a new ID, and stacks without a nimber get their nimber looked up
+    /// (if the game keeps track of them, in which case the caller has to make sure that
+    /// the stacks without a nimber are at most [`u64::MAX`] coins high).
+    /// Returns the replaced stacks.
+    pub(crate) fn splice_stacks(
+        &mut self,
+        index: usize,
+        count: usize,
+        entries: &[StackEntry<C>],
+    ) -> Vec<StackEntry<C>> {
+        let range = index..index + count;
+
+        let old_ids: Vec<Option<StackId>> = match &mut self.stack_ids {
+            Some(ids) => {
+                let new_ids: Vec<StackId> = entries
+                    .iter()
+                    .map(|entry| entry.id.unwrap_or_else(|| ids.create()))
+                    .collect();
+                ids.ids.splice(range.clone(), new_ids).map(Some).collect()
+            }
+            None => vec![None; count],
+        };
+
+        let old_nimbers: Vec<Option<Nimber<C>>> = match &mut self.nimbers {
+            Some(tracker) => {
+                let new_nimbers: Vec<Nimber<C>> = entries
+                    .iter()
+                    .map(|entry| {
+                        entry.nimber.clone().unwrap_or_else(|| {
+                            stack_nimber(&entry.stack, &self.rules)
+                                .expect("The heights of new tracked stacks are checked before")
+                        })
+                    })
+                    .collect();
+
+                // Update the nimber of the position with the nimbers of the replaced and new stacks
+                let old_nimbers: Vec<Nimber<C>> = tracker
+                    .stack_nimbers
+                    .splice(range.clone(), new_nimbers.iter().cloned())
+                    .collect();
+                tracker.nimber = old_nimbers
+                    .iter()
+                    .chain(&new_nimbers)
+                    .fold(tracker.nimber.clone(), |nimber, stack_nimber| {
+                        nimber ^ stack_nimber.clone()
+                    });
+
+                old_nimbers.into_iter().map(Some).collect()
+            }
+            None => vec![None; count],
+        };
+
+        self.stacks
+            .splice(range, entries.iter().map(|entry| entry.stack.clone()))
+            .zip(old_ids)
+            .zip(old_nimbers)
+            .map(|((stack, id), nimber)| StackEntry { stack, id, nimber })
+            .collect()
+    }
+
+    /// Get the stable IDs of the stacks (in the order of [`NimGame::get_stacks`]), if enabled
+    ///
+    /// Unlike the index of a stack, its ID doesn't change when other stacks are split.
+    #[must_use]
+    pub fn get_stack_ids(&self) -> Option<&[StackId]> {
+        self.stack_ids.as_ref().map(|ids| ids.ids.as_slice())
+    }
+
+    /// Find the current index of the stack with the given ID
+    ///
+    /// Returns [`None`] if stack IDs are disabled, or if there is no such stack (anymore).
+    #[must_use]
+    pub fn find_stack(&self, id: StackId) -> Option<usize> {
+        self.get_stack_ids()?
+            .iter()
+            .position(|&stack_id| stack_id == id)
+    }
+
+    /// Enable or disable stable stack IDs
+    ///
+    /// When enabled, every stack gets an ID ([`StackId`]), which stays the same while the stack exists.  
+    /// Stacks created by a split get new IDs, and moves may refer to a stack by its ID
+    /// (see [`TakeAction::stack_id`] and [`PlaceAction::stack_id`]).  
+    /// Enabling IDs when they are already enabled keeps the current IDs.
+    pub fn set_stack_ids(&mut self, enabled: bool) {
+        match (enabled, &self.stack_ids) {
+            (true, None) => self.stack_ids = Some(StackIds::new(self.stacks.len())),
+            (false, _) => self.stack_ids = None,
+            (true, Some(_)) => {}
+        }
+    }
+
+    /// Normalize the stacks: drop empty stacks, and sort the stacks by their heights
+    ///
+    /// Neither changes the value of the position, but it makes equivalent positions look the same.  
+    /// If the order of the stacks matters (see [`RuleSet::depends_on_stack_order`]),
+    /// only the empty stacks after the last non-empty stack are dropped.  
+    /// If stack IDs are enabled, the IDs move along with their stacks,
+    /// and the IDs of the dropped stacks are returned as [`moves::StackChanges::removed`].
+    pub fn normalize(&mut self) -> moves::StackChanges<C> {
+        let mut changes = moves::StackChanges::default();
+
+        // The indices of the non-empty stacks, sorted by their heights
+        // (keeping the order of stacks with the same height)
+        let (empty, order): (Vec<usize>, Vec<usize>) = if self.rules.depends_on_stack_order() {
+            let end = self
+                .stacks
+                .iter()
+                .rposition(|stack| !stack.0.is_zero())
+                .map_or(0, |last| last + 1);
+            ((end..self.stacks.len()).collect(), (0..end).collect())
+        } else {
+            let (empty, mut order): (Vec<usize>, Vec<usize>) =
+                (0..self.stacks.len()).partition(|&index| self.stacks[index].0.is_zero());
+            order.sort_by(|&a, &b| self.stacks[a].cmp(&self.stacks[b]));
+            (empty, order)
+        };
+
+        if let Some(ids) = &mut self.stack_ids {
+            changes.removed = empty.iter().map(|&index| ids.ids[index]).collect();
+            ids.ids = order.iter().map(|&index| ids.ids[index]).collect();
+        }
+
+        if let Some(tracker) = &mut self.nimbers {
+            tracker.nimber = empty.iter().fold(tracker.nimber.clone(), |nimber, &index| {
+                nimber ^ tracker.stack_nimbers[index].clone()
+            });
+            tracker.stack_nimbers = order
+                .iter()
+                .map(|&index| tracker.stack_nimbers[index].clone())
+                .collect();
+        }
+
+        self.stacks = order
+            .iter()
+            .map(|&index| self.stacks[index].clone())
+            .collect();
+
+        changes
+    }
+
+    /// Get the nimber of the position, if the game keeps track of it
+    ///
+    /// Unlike [`NimGame::calculate_nimber`], this never calculates anything:
+    /// the nimber is kept up to date with every change of the stacks (see [`NimGame::set_track_nimber`]).  
+    /// Returns [`None`] if the nimber is not tracked.
+    #[must_use]
+    pub fn get_nimber(&self) -> Option<Nimber<C>> {
+        self.nimbers.as_ref().map(|tracker| tracker.nimber.clone())
+    }
+
+    /// Get the nimbers of the stacks (in the order of [`NimGame::get_stacks`]), if the game keeps track of them
+    #[must_use]
+    pub fn get_stack_nimbers(&self) -> Option<&[Nimber<C>]> {
+        self.nimbers
+            .as_ref()
+            .map(|tracker| tracker.stack_nimbers.as_slice())
+    }
+
+    /// Enable or disable keeping track of the nimbers of the stacks and the position
+    ///
+    /// When enabled, the nimbers of the stacks are looked up once,
+    /// and afterwards only the nimbers of changed stacks are looked up.
+    /// The nimber of the position is updated in constant time with every applied (or undone) move,
+    /// and can be read with [`NimGame::get_nimber`].  
+    /// Enabling it calculates the nimbers of all stacks, which may take a long time for high stacks.
+    /// This is the nimber under the normal play convention, just like [`NimGame::calculate_nimber`].  
+    /// While the nimbers are tracked, no stack may be higher than [`u64::MAX`],
+    /// as the nimbers of such stacks can't be calculated (see [`Stack::to_u64`]):
+    /// moves making a stack higher fail with [`moves::MoveError::StackTooHigh`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::StackTooHigh`] if the tracking is enabled and a stack is higher than [`u64::MAX`].
+    /// The game is not changed if an error is returned.
+    pub fn set_track_nimber(&mut self, enabled: bool) -> Result<(), GameError<C>> {
+        match (enabled, &self.nimbers) {
+            (true, None) => self.nimbers = Some(NimberTracker::new(&self.stacks, &self.rules)?),
+            (false, _) => self.nimbers = None,
+            (true, Some(_)) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Check if the stacks are normalized after every move
+    #[must_use]
+    pub fn get_auto_normalize(&self) -> bool {
+        self.auto_normalize
+    }
+
+    /// Enable or disable normalizing the stacks after every move (see [`NimGame::normalize`])
+    ///
+    /// Enabling it normalizes the current stacks right away, returning the changes.
+    pub fn set_auto_normalize(&mut self, enabled: bool) -> moves::StackChanges<C> {
+        self.auto_normalize = enabled;
+
+        if enabled {
+            self.normalize()
+        } else {
+            moves::StackChanges::default()
+        }
+    }
+
+    /// Get the canonical form of the position (see [`CanonicalPosition`])
+    ///
+    /// Equivalent positions, e.g. with the stacks in a different order or with empty stacks,
+    /// have equal canonical forms with equal fingerprints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimGame, RuleSet, NimRule, Split, Stack, TakeSize};
+    ///
+    /// let rules = RuleSet::new([NimRule {
+    ///     take: TakeSize::Any,
+    ///     split: Split::Never,
+    /// }])
+    /// .unwrap();
+    ///
+    /// let a = NimGame::new(rules.clone(), vec![Stack(3), Stack(0), Stack(1)]);
+    /// let b = NimGame::new(rules, vec![Stack(1), Stack(3)]);
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.canonical_position(), b.canonical_position());
+    /// assert_eq!(a.canonical_position().stacks(), &[Stack(1), Stack(3)]);
+    /// ```
+    #[must_use]
+    pub fn canonical_position(&self) -> CanonicalPosition<C> {
+        CanonicalPosition::new(self)
+    }
+
+    /// Replace the rules of the game
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::PoolsWithoutPlaceRule`] if the pools of the players hold coins,
+    /// but the new rules don't allow placing coins,
+    /// or [`GameError::CompoundRules`] if the stacks are not a disjunctive sum,
+    /// but the new rules allow moves which can't be combined (see [`NimGame::set_compound`]).
+    /// The game is not changed if an error is returned.
+    pub fn set_rules(&mut self, rules: RuleSet) -> Result<(), GameError<C>> {
+        check_pools(&rules, &self.pools)?;
+        check_compound(&rules, self.compound)?;
+
+        if self.nimbers.is_some() {
+            self.nimbers = Some(NimberTracker::new(&self.stacks, &rules)?);
+        }
+        self.rules = rules;
+        if !self.rules.depends_on_last_take() {
+            self.last_take = None;
+        }
+
+        Ok(())
+    }
+
+    /// Set the number of coins in the pool of a player
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::NoSuchPlayer`] if the player doesn't take part in the game,
+    /// or [`GameError::PoolsWithoutPlaceRule`] if the pool would hold coins,
+    /// but the rules don't allow placing coins.
+    /// The game is not changed if an error is returned.
+    pub fn set_pool_coins(&mut self, player: Player, coins: C) -> Result<(), GameError<C>> {
+        check_players(self.get_player_count(), player)?;
+        let mut pools = self.pools.clone();
+        pools[player.0] = coins;
+        check_pools(&self.rules, &pools)?;
+
+        self.pools = pools;
+        Ok(())
+    }
+
+    /// Set the player whose turn it is
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::NoSuchPlayer`] if the player doesn't take part in the game.
+    /// The game is not changed if an error is returned.
+    pub fn set_to_move(&mut self, player: Player) -> Result<(), GameError<C>> {
+        check_players(self.get_player_count(), player)?;
+        self.to_move = player;
+        Ok(())
+    }
+
+    /// Set the play convention of the game
+    pub fn set_convention(&mut self, convention: PlayConvention) {
+        self.convention = convention;
+    }
+
+    /// Set how the stacks of the game are combined, i.e. in how many stacks a move is made
+    ///
+    /// The moves of the stacks are generated and checked as in a disjunctive sum,
+    /// and combined into moves of several stacks ([`NimAction::Compound`]).
+    /// So only rules which take coins from a single stack ([`TakeSize::List`] and [`TakeSize::Any`])
+    /// can be played as conjunctive or selective sums.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{moves, Compound, NimAction, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+    ///
+    /// let rules = RuleSet::new([NimRule {
+    ///     take: TakeSize::List(vec![1, 2]),
+    ///     split: Split::Never,
+    /// }])
+    /// .unwrap();
+    ///
+    /// let mut game = NimGame::new(rules, vec![Stack(1), Stack(3)]);
+    /// game.set_compound(Compound::Conjunctive).unwrap();
+    ///
+    /// // A coin is taken from the first stack, and one or two coins from the second stack
+    /// let moves = game.calculate_legal_moves();
+    /// assert_eq!(moves.len(), 2);
+    ///
+    /// moves::apply_move(&mut game, &moves[1]).unwrap();
+    /// assert_eq!(game.get_stacks(), &vec![Stack(0), Stack(1)]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::CompoundRules`] if the compound is not the disjunctive sum,
+    /// but the rules allow moves which involve several stacks, depend on the previous move, or place coins.
+    /// The game is not changed if an error is returned.
+    pub fn set_compound(&mut self, compound: Compound) -> Result<(), GameError<C>> {
+        check_compound(&self.rules, compound)?;
+        self.compound = compound;
+        Ok(())
+    }
+
+    /// Set the largest number of coins taken from a single stack by the last move (see [`NimGame::get_last_take`])
+    ///
+    /// The coins are ignored if the rules don't depend on them.
+    pub fn set_last_take(&mut self, coins: Option<C>) {
+        self.last_take = coins.filter(|_| self.rules.depends_on_last_take());
+    }
+
+    /// Generate all legal moves of the player whose turn it is
+    ///
+    /// See [`moves::calculate_legal_moves`] for details.  
+    /// If the stacks are a conjunctive or selective sum (see [`NimGame::set_compound`]),
+    /// the moves of the single stacks are combined: moves in a single stack are [`NimAction::Take`] moves,
+    /// and moves in several stacks are [`NimAction::Compound`] moves.  
+    /// If stack IDs are enabled, the moves refer to the stacks by their IDs as well.
+    #[must_use]
+    pub fn calculate_legal_moves(&self) -> Vec<NimAction<C>> {
+        let rules = self.rules.rules_for(self.to_move);
+        let mut moves = match self.compound {
+            Compound::Disjunctive => moves::legal_moves(
+                &self.stacks,
+                rules,
+                &self.pools,
+                self.to_move,
+                self.last_take.as_ref(),
+            ),
+            Compound::Conjunctive | Compound::Selective => {
+                moves::compound_moves(&self.stacks, rules, self.compound)
+            }
+        };
+
+        if let Some(ids) = self.get_stack_ids() {
+            for mov in &mut moves {
+                match mov {
+                    NimAction::Take(take) => take.stack_id = ids.get(take.stack_index).copied(),
+                    NimAction::Place(place) => {
+                        place.stack_id = ids.get(place.stack_index).copied();
+                    }
+                    NimAction::TakeMany(take_many) => {
+                        for take in &mut take_many.takes {
+                            take.stack_id = ids.get(take.stack_index).copied();
+                        }
+                    }
+                    NimAction::Transfer(transfer) => {
+                        transfer.stack_id = ids.get(transfer.stack_index).copied();
+                        transfer.target_id = ids.get(transfer.target_index).copied();
+                    }
+                    NimAction::Compound(compound) => {
+                        for take in &mut compound.takes {
+                            take.stack_id = ids.get(take.stack_index).copied();
+                        }
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Calculate the nimber of the position using the MEX & XOR rules
+    ///
+    /// This is the nimber under the normal play convention, regardless of [`NimGame::get_convention`].  
+    /// If the game keeps track of its nimber, the tracked nimber is returned (see [`NimGame::get_nimber`]).
+    ///
+    /// The nimber is the XOR of the nimbers of the single stacks, which assumes that every move involves a single stack.
+    /// Moves across several stacks ([`TakeSize::AnyFromStacks`] and [`TakeSize::SameFromTwoStacks`] rules)
+    /// are not taken into account, see [`crate::moore`] and [`crate::wythoff`] for such games.
+    /// Neither are rules depending on the previous move ([`TakeSize::UpToMultipleOfLast`]),
+    /// see [`crate::fibonacci`], nor rules moving coins between stacks (see [`crate::staircase`]).  
+    /// Neither is the compound of the stacks: the nimber is that of the disjunctive sum,
+    /// see [`crate::compound`] for conjunctive and selective sums.
+    ///
+    /// Games counting coins with [u64]s can use [`NimGame::calculate_nimber`], which can't fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::StackTooHigh`] if a stack is higher than [`u64::MAX`],
+    /// as the nimbers of such stacks can't be calculated (see [`Stack::to_u64`]).
+    pub fn try_calculate_nimber(&self) -> Result<Nimber<C>, GameError<C>> {
+        // FIXME handle pool coins
+
+        if let Some(nimber) = self.get_nimber() {
+            return Ok(nimber);
+        }
+
+        self.stacks
+            .iter()
+            .map(|stack| stack_nimber(stack, &self.rules))
+            .sum()
+    }
+}
+
+impl Default for NimGame {
+    fn default() -> Self {
+        Self {
+            rules: RuleSet::new([NimRule {
+                take: TakeSize::List(vec![1, 2, 3]),
+                split: Split::Never,
+            }])
+            .expect("The default rules are valid"),
+            stacks: vec![Stack(10)],
+            pools: vec![0, 0],
+            to_move: Player::A,
+            convention: PlayConvention::Normal,
+            compound: Compound::Disjunctive,
+            last_take: None,
+            stack_ids: None,
+            auto_normalize: false,
+            nimbers: None,
+        }
+    }
+}
+
+impl NimGame {
+    /// Create a new Nim game with the given rules and stacks
+    ///
+    /// The rules are validated and brought into canonical form by [`RuleSet::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+    ///
+    /// let simple_rules: Vec<NimRule> = vec![NimRule {
+    ///     take: TakeSize::List(vec![1, 2, 3]),
+    ///     split: Split::Never,
+    /// }];
+    ///
+    /// let stacks: Vec<Stack> = vec![Stack(10)];
+    ///
+    /// let game = NimGame::new(RuleSet::new(simple_rules).unwrap(), stacks);
+    /// ```
+    #[must_use]
+    pub fn new(rules: RuleSet, stacks: Vec<Stack>) -> Self {
+        Self {
+            rules,
+            stacks,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the nimber of the position using the MEX & XOR rules
+    ///
+    /// See [`NimGame::try_calculate_nimber`] for details,
+    /// which can't fail for stacks counted with [u64]s.
+    #[must_use]
+    pub fn calculate_nimber(&self) -> Nimber {
+        self.try_calculate_nimber()
+            .unwrap_or_else(|_| unreachable!("The nimbers of u64 stacks can always be calculated"))
+    }
+
+    /// Create a builder to set every part of a game (see [`NimGameBuilder`])
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimGame, NimRule, PlayConvention, Player, Split, Stack, TakeSize};
+    ///
+    /// let game = NimGame::builder()
+    ///     .rules(vec![
+    ///         NimRule {
+    ///             take: TakeSize::List(vec![1, 2]),
+    ///             split: Split::Never,
+    ///         },
+    ///         NimRule {
+    ///             take: TakeSize::Place,
+    ///             split: Split::Never,
+    ///         },
+    ///     ])
+    ///     .stacks(vec![Stack(3), Stack(5)])
+    ///     .pool_coins(Player::B, 2)
+    ///     .to_move(Player::B)
+    ///     .convention(PlayConvention::Misere)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(game.get_pool_coins(Player::B), 2);
+    /// assert_eq!(game.get_to_move(), Player::B);
+    /// ```
+    #[must_use]
+    pub fn builder() -> NimGameBuilder {
+        NimGameBuilder::default()
+    }
+}
+
+/// A builder for [`NimGame`]s, see [`NimGame::builder`]
+///
+/// The rules have to be set; all other parts default to those of a new game
+/// (no stacks, two players with empty pools, player A to move, normal play).
+#[derive(Clone, Debug, Default)]
 #[allow(clippy::module_name_repetitions)]
-pub struct NimGame {
-    /// The rules of the game (e.g. which numbers of coins can be taken)
-    pub(crate) rules: Vec<NimRule>,
+pub struct NimGameBuilder<C = u64> {
+    /// The rules for all players, if set
+    rules: Option<Vec<NimRule>>,
 
-    /// The stacks of the game, represented as their current heights
-    pub(crate) stacks: Vec<Stack>,
+    /// The rules of single players
+    player_rules: Vec<(Player, NimRule)>,
+
+    /// The stacks of the game
+    stacks: Vec<Stack<C>>,
+
+    /// The number of players
+    players: Option<usize>,
+
+    /// The players whose pools hold coins, and their coins
+    pools: Vec<(Player, C)>,
+
+    /// The player whose turn it is
+    to_move: Player,
+
+    /// The play convention
+    convention: PlayConvention,
 
-    /// The number of coins in the pool of player A  
-    /// (ignored for now)
-    pub(crate) coins_a: u64,
+    /// How the stacks are combined
+    compound: Compound,
 
-    /// The number of coins in the pool of player B  
-    /// (ignored for now)
-    pub(crate) coins_b: u64,
+    /// The coins taken by the previous move
+    last_take: Option<C>,
+
+    /// Whether the stacks have stable IDs
+    stack_ids: bool,
+
+    /// Whether the stacks are normalized after every move
+    auto_normalize: bool,
+
+    /// Whether the nimbers are tracked
+    track_nimber: bool,
 }
 
-impl NimGame {
-    /// Get the stacks currently in the game
+impl<C: Coins> NimGameBuilder<C> {
+    /// Create a builder for games counting coins with any type of [`Coins`]
     ///
-    /// Retrieves the position as a shared reference to vector of [Stack]s.
+    /// [`NimGame::builder`] creates a builder for the default [`NimGame`], counting coins with [u64]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimGameBuilder, NimRule, Player, Split, Stack, TakeSize};
+    ///
+    /// let game = NimGameBuilder::<u128>::new()
+    ///     .rules([NimRule {
+    ///         take: TakeSize::Place,
+    ///         split: Split::Never,
+    ///     }])
+    ///     .stacks(vec![Stack(u128::MAX - 1)])
+    ///     .pool_coins(Player::A, 1 << 64)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(game.get_pool_coins(Player::A), 1 << 64);
+    /// ```
     #[must_use]
-    pub fn get_stacks(&self) -> &Vec<Stack> {
-        &self.stacks
+    pub fn new() -> Self {
+        Self::default()
     }
-}
 
-impl Default for NimGame {
-    fn default() -> Self {
-        Self {
-            rules: vec![NimRule {
-                take: TakeSize::List(vec![1, 2, 3]),
-                split: Split::Never,
-            }],
-            stacks: vec![Stack(10)],
-            coins_a: 0,
-            coins_b: 0,
-        }
+    /// Set the rules of the game for all players (validated by [`NimGameBuilder::build`])
+    #[must_use]
+    pub fn rules(mut self, rules: impl IntoIterator<Item = NimRule>) -> Self {
+        self.rules = Some(rules.into_iter().collect());
+        self
     }
-}
 
-impl NimGame {
-    /// Create a new Nim game with the given rules and stacks
+    /// Add rules which only the given player may use, which makes the game partizan (see [`RuleSet::partizan`])
+    ///
+    /// Rules for single players are enough to build a game, the rules for all players default to none then.
     ///
     /// # Examples
     ///
     /// ```
-    /// use nimlib::{NimGame, NimRule, Split, Stack, TakeSize};
+    /// use nimlib::{NimGame, NimRule, Player, Split, Stack, TakeSize};
     ///
-    /// let simple_rules: Vec<NimRule> = vec![NimRule {
-    ///     take: TakeSize::List(vec![1, 2, 3]),
-    ///     split: Split::Never,
-    /// }];
+    /// let game = NimGame::builder()
+    ///     .player_rules(
+    ///         Player::A,
+    ///         [NimRule {
+    ///             take: TakeSize::List(vec![1]),
+    ///             split: Split::Never,
+    ///         }],
+    ///     )
+    ///     .player_rules(
+    ///         Player::B,
+    ///         [NimRule {
+    ///             take: TakeSize::List(vec![2]),
+    ///             split: Split::Never,
+    ///         }],
+    ///     )
+    ///     .stacks(vec![Stack(3)])
+    ///     .build()
+    ///     .unwrap();
     ///
-    /// let stacks: Vec<Stack> = vec![Stack(10)];
+    /// // Player A takes a single coin, after which player B takes the other two
+    /// assert_eq!(game.calculate_legal_moves().len(), 1);
+    /// assert!(game.get_rules().is_partizan());
+    /// ```
+    #[must_use]
+    pub fn player_rules(
+        mut self,
+        player: Player,
+        rules: impl IntoIterator<Item = NimRule>,
+    ) -> Self {
+        self.player_rules
+            .extend(rules.into_iter().map(|rule| (player, rule)));
+        self
+    }
+
+    /// Set the stacks of the game
+    #[must_use]
+    pub fn stacks(mut self, stacks: Vec<Stack<C>>) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    /// Set the number of players (two by default)
+    ///
+    /// The players take turns in the order A, B, C, ... (see [`NimGame::next_player`]).
     ///
-    /// let game = NimGame::new(simple_rules, stacks);
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{moves, NimGame, NimRule, Player, Split, Stack, TakeSize};
+    ///
+    /// let mut game = NimGame::builder()
+    ///     .rules([NimRule {
+    ///         take: TakeSize::Any,
+    ///         split: Split::Never,
+    ///     }])
+    ///     .stacks(vec![Stack(5)])
+    ///     .players(3)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mov = game.calculate_legal_moves().remove(0);
+    /// moves::apply_move(&mut game, &mov).unwrap();
+    /// assert_eq!(game.get_to_move(), Player::B);
+    /// assert_eq!(game.next_player(Player::B), Player(2));
+    /// assert_eq!(game.next_player(Player(2)), Player::A);
     /// ```
     #[must_use]
-    pub fn new(rules: Vec<NimRule>, stacks: Vec<Stack>) -> Self {
-        // TODO allow pool coins to be set
+    pub fn players(mut self, count: usize) -> Self {
+        self.players = Some(count);
+        self
+    }
 
-        Self {
+    /// Set the number of coins in the pool of a player
+    #[must_use]
+    pub fn pool_coins(mut self, player: Player, coins: C) -> Self {
+        self.pools.retain(|(other, _)| *other != player);
+        self.pools.push((player, coins));
+        self
+    }
+
+    /// Set the player whose turn it is
+    #[must_use]
+    pub fn to_move(mut self, player: Player) -> Self {
+        self.to_move = player;
+        self
+    }
+
+    /// Set the play convention
+    #[must_use]
+    pub fn convention(mut self, convention: PlayConvention) -> Self {
+        self.convention = convention;
+        self
+    }
+
+    /// Set how the stacks are combined (see [`NimGame::set_compound`])
+    #[must_use]
+    pub fn compound(mut self, compound: Compound) -> Self {
+        self.compound = compound;
+        self
+    }
+
+    /// Set the coins taken by the previous move (see [`NimGame::get_last_take`])
+    #[must_use]
+    pub fn last_take(mut self, coins: Option<C>) -> Self {
+        self.last_take = coins;
+        self
+    }
+
+    /// Enable or disable stable stack IDs (see [`NimGame::set_stack_ids`])
+    #[must_use]
+    pub fn stack_ids(mut self, enabled: bool) -> Self {
+        self.stack_ids = enabled;
+        self
+    }
+
+    /// Enable or disable normalizing the stacks after every move (see [`NimGame::set_auto_normalize`])
+    ///
+    /// If enabled, the given stacks are normalized by [`NimGameBuilder::build`] as well.
+    #[must_use]
+    pub fn auto_normalize(mut self, enabled: bool) -> Self {
+        self.auto_normalize = enabled;
+        self
+    }
+
+    /// Enable or disable keeping track of the nimbers (see [`NimGame::set_track_nimber`])
+    #[must_use]
+    pub fn track_nimber(mut self, enabled: bool) -> Self {
+        self.track_nimber = enabled;
+        self
+    }
+
+    /// Validate the parts of the game and build it
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GameError`] if no rules were set, if the rules are invalid,
+    /// if there are fewer than two players, if a pool, the turn, or rules belong to a player who doesn't take part,
+    /// if the pools hold coins but the rules don't allow placing coins,
+    /// if the moves of the rules can't be combined as set (see [`NimGame::set_compound`]),
+    /// or if the nimbers are tracked and a stack is higher than [`u64::MAX`] (see [`NimGame::set_track_nimber`]).
+    pub fn build(self) -> Result<NimGame<C>, GameError<C>> {
+        if self.rules.is_none() && self.player_rules.is_empty() {
+            return Err(GameError::MissingRules);
+        }
+
+        let player_count = self.players.unwrap_or(2);
+
+        let rules = RuleSet::partizan(
+            self.rules
+                .unwrap_or_default()
+                .into_iter()
+                .map(|rule| (None, rule))
+                .chain(
+                    self.player_rules
+                        .into_iter()
+                        .map(|(player, rule)| (Some(player), rule)),
+                ),
+        )?;
+
+        let mut pools = vec![C::from(0); player_count];
+        for (player, coins) in self.pools {
+            check_players(player_count, player)?;
+            pools[player.0] = coins;
+        }
+        check_game(&rules, &pools, self.to_move, self.compound)?;
+
+        let mut game = NimGame {
             rules,
-            stacks,
-            ..Default::default()
+            stack_ids: self.stack_ids.then(|| StackIds::new(self.stacks.len())),
+            stacks: self.stacks,
+            pools,
+            to_move: self.to_move,
+            convention: self.convention,
+            compound: self.compound,
+            last_take: None,
+            auto_normalize: self.auto_normalize,
+            nimbers: None,
+        };
+        game.set_last_take(self.last_take);
+
+        if game.auto_normalize {
+            game.normalize();
         }
+        game.set_track_nimber(self.track_nimber)?;
+
+        Ok(game)
     }
+}
 
-    /// Calculate the nimber of the position using the MEX & XOR rules
-    #[must_use]
-    pub fn calculate_nimber(&self) -> Nimber {
-        // FIXME handle pool coins
+/// Check if the parts of a game fit together, see [`NimGameBuilder::build`]
+///
+/// The number of players is given by the pools.
+fn check_game<C: Coins>(
+    rules: &RuleSet,
+    pools: &[C],
+    to_move: Player,
+    compound: Compound,
+) -> Result<(), GameError<C>> {
+    check_players(pools.len(), to_move)?;
+    for (player, _) in rules.player_rules() {
+        check_players(pools.len(), *player)?;
+    }
+    check_pools(rules, pools)?;
+    check_compound(rules, compound)
+}
+
+/// Check if there are enough players, and if the given player takes part in a game of `player_count` players
+fn check_players<C>(player_count: usize, player: Player) -> Result<(), GameError<C>> {
+    if player_count < 2 {
+        Err(GameError::TooFewPlayers { player_count })
+    } else if player.0 >= player_count {
+        Err(GameError::NoSuchPlayer {
+            player,
+            player_count,
+        })
+    } else {
+        Ok(())
+    }
+}
 
-        self.stacks.iter().fold(Nimber(0), |nimber, stack| {
-            nimber ^ stack.calculate_nimber(&self.rules, 0)
+/// Check if the pools may hold the given coins under the given rules
+fn check_pools<C: Coins>(rules: &RuleSet, pools: &[C]) -> Result<(), GameError<C>> {
+    if pools.iter().any(|coins| !coins.is_zero())
+        && rules.all_rules().all(|rule| rule.take != TakeSize::Place)
+    {
+        Err(GameError::PoolsWithoutPlaceRule {
+            pools: pools.to_vec(),
         })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check if the moves of the rules can be combined into moves of several stacks as given by the compound
+fn check_compound<C>(rules: &RuleSet, compound: Compound) -> Result<(), GameError<C>> {
+    let stackwise = rules
+        .all_rules()
+        .all(|rule| matches!(rule.take, TakeSize::List(_) | TakeSize::Any));
+
+    if compound == Compound::Disjunctive || stackwise {
+        Ok(())
+    } else {
+        Err(GameError::CompoundRules { compound })
+    }
+}
+
+/// Errors which may occur when building or changing a [`NimGame`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum GameError<C = u64> {
+    /// No rules were set
+    MissingRules,
+
+    /// The rules are invalid (see [`RuleSet::new`])
+    InvalidRules(RuleSetError),
+
+    /// The pools hold coins, but the rules don't allow placing coins
+    PoolsWithoutPlaceRule {
+        /// The number of coins in the pool of each player
+        pools: Vec<C>,
+    },
+
+    /// A game needs at least two players
+    TooFewPlayers {
+        /// The number of players
+        player_count: usize,
+    },
+
+    /// The player doesn't take part in the game
+    NoSuchPlayer {
+        /// The player
+        player: Player,
+
+        /// The number of players of the game
+        player_count: usize,
+    },
+
+    /// The rules allow moves which can't be combined into a conjunctive or selective sum of the stacks
+    /// (see [`NimGame::set_compound`])
+    CompoundRules {
+        /// How the stacks were to be combined
+        compound: Compound,
+    },
+
+    /// Several stacks have the same ID (see [`NimGame::set_stack_ids`])
+    DuplicateStackId {
+        /// The ID
+        id: StackId,
+    },
+
+    /// The number of stack IDs doesn't match the number of stacks (see [`NimGame::set_stack_ids`])
+    StackIdCount {
+        /// The number of IDs
+        id_count: usize,
+
+        /// The number of stacks
+        stack_count: usize,
+    },
+
+    /// The nimber of a stack higher than [`u64::MAX`] can't be calculated
+    /// (see [`NimGame::try_calculate_nimber`] and [`NimGame::set_track_nimber`])
+    StackTooHigh {
+        /// The height of the stack
+        height: C,
+    },
+}
+
+impl<C: Coins> Display for GameError<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::MissingRules => write!(f, "The rules of the game are missing"),
+            GameError::InvalidRules(error) => write!(f, "{error}"),
+            GameError::PoolsWithoutPlaceRule { pools } => {
+                let pools: Vec<String> = pools
+                    .iter()
+                    .enumerate()
+                    .map(|(player, coins)| format!("{}: {coins}", Player(player)))
+                    .collect();
+                write!(
+                    f,
+                    "The pools hold coins ({}), but the rules don't allow placing coins",
+                    pools.join(", ")
+                )
+            }
+            GameError::TooFewPlayers { player_count } => write!(
+                f,
+                "A game needs at least 2 players, but it has {player_count}"
+            ),
+            GameError::NoSuchPlayer {
+                player,
+                player_count,
+            } => write!(
+                f,
+                "There is no player {player}, as the game has {player_count} players"
+            ),
+            GameError::CompoundRules { compound } => write!(
+                f,
+                "Only rules which take coins from a single stack can be played as a {compound} sum"
+            ),
+            GameError::DuplicateStackId { id } => {
+                write!(f, "Several stacks have the same ID {id}")
+            }
+            GameError::StackIdCount {
+                id_count,
+                stack_count,
+            } => write!(
+                f,
+                "There are {id_count} stack IDs for {stack_count} stack(s)"
+            ),
+            GameError::StackTooHigh { height } => write!(
+                f,
+                "The nimber of a stack of {height} coins can't be calculated, as it is higher than {}",
+                u64::MAX
+            ),
+        }
+    }
+}
+
+impl<C: Coins> Error for GameError<C> {}
+
+impl<C> From<RuleSetError> for GameError<C> {
+    fn from(error: RuleSetError) -> Self {
+        GameError::InvalidRules(error)
+    }
+}
+
+/// The play convention of a game, deciding whether the player making the last move wins or loses
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+pub enum PlayConvention {
+    /// The player making the last move wins
+    #[default]
+    Normal,
+
+    /// The player making the last move loses
+    Misere,
+}
+
+/// How the stacks of a game are combined into a position, i.e. in how many stacks a move is made
+///
+/// Every stack is a game of its own, and a position is a compound of these games.
+/// Only the moves of single stacks can be combined like this (see [`NimGame::set_compound`]),
+/// and the stacks created by a split are stacks of the compound as well.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+pub enum Compound {
+    /// A move is made in exactly one stack (the sum of the Sprague-Grundy theory, see [`crate::nimbers`])
+    #[default]
+    Disjunctive,
+
+    /// A move is made in every stack which allows a move (in every non-empty stack, if a single coin may be taken),
+    /// see [`crate::compound::suspense_numbers`]
+    Conjunctive,
+
+    /// A move is made in any (non-empty) selection of the stacks which allow a move,
+    /// see [`crate::compound::selective_outcomes`]
+    Selective,
+}
+
+impl Display for Compound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compound::Disjunctive => write!(f, "disjunctive"),
+            Compound::Conjunctive => write!(f, "conjunctive"),
+            Compound::Selective => write!(f, "selective"),
+        }
     }
 }
 
+/// Check if a compound is the (default) disjunctive sum, which isn't serialized
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_disjunctive(compound: &Compound) -> bool {
+    *compound == Compound::Disjunctive
+}
+
 /// Represents a stack of coins; specifically its height.  
-/// Simply wraps a [u64].
+/// Simply wraps a [u64] by default, or any other type of [`Coins`].
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct Stack(pub u64);
+pub struct Stack<C = u64>(pub C);
+
+impl<C: Coins> Stack<C> {
+    /// Convert the height of the stack to a [u64], or [`None`] if the stack is too high
+    ///
+    /// The nimbers of single stacks are calculated for [u64] heights, see [`Stack::calculate_nimber`].
+    #[must_use]
+    pub fn to_u64(&self) -> Option<Stack> {
+        self.0.to_u64().map(Stack)
+    }
+}
 
 impl Stack {
     /// Calculate the nimber of the stack using the MEX & XOR rules
     ///
-    /// For now, `pool_coins` must be 0.
-    pub fn calculate_nimber(&self, rules: impl AsRef<Vec<NimRule>>, pool_coins: u64) -> Nimber {
+    /// For now, `pool_coins` must be 0.  
+    /// Stacks of other types of [`Coins`] can be converted with [`Stack::to_u64`] first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NimberError::InvalidRules`] if the rules are invalid (see [`RuleSet::new`]),
+    /// or [`NimberError::PoolCoinsUnsupported`] if `pool_coins` is not 0.
+    pub fn calculate_nimber(
+        &self,
+        rules: impl AsRef<Vec<NimRule>>,
+        pool_coins: u64,
+    ) -> Result<Nimber, NimberError> {
         nimbers::calculate_nimber_for_height(self.0, rules.as_ref(), pool_coins)
     }
 }
 
-/// A nimber.  
+/// A stable ID of a stack, see [`NimGame::set_stack_ids`].  
 /// Simply wraps a [u64].
 #[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StackId(pub u64);
+
+impl Display for StackId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A nimber.  
+/// Simply wraps a [u64] by default, or any other type of [`Coins`].
+#[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct Nimber(pub u64);
+pub struct Nimber<C = u64>(pub C);
 
-impl BitXor for Nimber {
-    type Output = Nimber;
+impl<C: Coins> BitXor for Nimber<C> {
+    type Output = Nimber<C>;
 
-    fn bitxor(self, rhs: Nimber) -> Nimber {
-        Nimber(self.0 ^ rhs.0)
+    fn bitxor(self, rhs: Nimber<C>) -> Nimber<C> {
+        Nimber(self.0.xor(&rhs.0))
     }
 }
 
-impl Display for Nimber {
+impl<C: Display> Display for Nimber<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "*{}", self.0)
     }
 }
 
-impl Debug for Nimber {
+impl<C: Debug> Debug for Nimber<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "*{}", self.0)
+        write!(f, "*{:?}", self.0)
     }
 }
 
@@ -178,6 +1606,42 @@ pub enum TakeSize {
     /// The player may place coins into the stack from their pool (none are taken),  
     /// For use with Poker-Nim
     Place,
+
+    /// Any number of coins may be taken from each of up to the given number of stacks in a single move
+    /// (at least one coin from every stack involved), as in Moore's Nim.
+    ///
+    /// Moves involving a single stack are [`NimAction::Take`] moves, moves involving several stacks
+    /// are [`NimAction::TakeMany`] moves. Stacks can't be split with these moves.
+    #[serde(rename = "TakeAnyFromStacks")]
+    AnyFromStacks(usize),
+
+    /// The same number of coins (at least one) may be taken from two stacks in a single move,
+    /// as in Wythoff's game (together with [`TakeSize::Any`]).
+    ///
+    /// These moves are [`NimAction::TakeMany`] moves (see [`TakeManyAction::joint`]).
+    /// Stacks can't be split with these moves.
+    #[serde(rename = "TakeSameFromTwoStacks")]
+    SameFromTwoStacks,
+
+    /// At least one coin, and at most the given multiple of the coins taken by the previous move
+    /// (see [`NimGame::get_last_take`]) may be taken from a stack, as in Fibonacci Nim (with a multiple of 2).
+    ///
+    /// The first move of the game may take any number of coins, but not the whole stack.
+    #[serde(rename = "TakeUpToMultipleOfLast")]
+    UpToMultipleOfLast(u64),
+
+    /// Any number of coins (at least one) may be moved from a stack onto the stack before it,
+    /// as in Staircase Nim (where the stacks are the steps of a staircase, and the first stack is the ground).
+    ///
+    /// These moves are [`NimAction::Transfer`] moves, which neither take nor place coins,
+    /// and the order of the stacks matters (see [`RuleSet::depends_on_stack_order`]).
+    /// Stacks can't be split with these moves.
+    TransferToNeighbour,
+
+    /// Any number of coins (at least one) may be moved from a stack onto any stack before it.
+    ///
+    /// Like [`TakeSize::TransferToNeighbour`], but the coins may skip stacks.
+    TransferToLower,
 }
 
 /// A rule for a Nim game.  
@@ -199,70 +1663,273 @@ pub struct NimRule {
 /// This does not include information about the current game state,
 /// or if a non-empty first
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub enum NimAction {
+pub enum NimAction<C = u64> {
     /// A move which takes coins from a stack, possibly splitting it
-    Take(TakeAction),
+    Take(TakeAction<C>),
 
     /// A move which places coins onto a stack from the player's pool
     ///
     /// For use with Poker-Nim
-    Place(PlaceAction),
+    Place(PlaceAction<C>),
+
+    /// A move which takes coins from several stacks at once, without splitting them
+    ///
+    /// For use with [`TakeSize::AnyFromStacks`] and [`TakeSize::SameFromTwoStacks`] rules
+    TakeMany(TakeManyAction<C>),
+
+    /// A move which moves coins from a stack onto another stack
+    ///
+    /// For use with [`TakeSize::TransferToNeighbour`] and [`TakeSize::TransferToLower`] rules
+    Transfer(TransferAction<C>),
+
+    /// A move which takes coins from several stacks at once, possibly splitting them
+    ///
+    /// For use with conjunctive and selective sums of the stacks (see [`NimGame::set_compound`])
+    Compound(CompoundAction<C>),
 }
 
 /// A move which takes coins from a stack
 ///
 /// (placing them into the player's pool, when used with Poker-Nim)
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct TakeAction {
+pub struct TakeAction<C = u64> {
     /// The index of the stack to take coins from
     pub stack_index: usize,
 
     /// The number of coins to take from the stack
-    pub amount: u64,
+    pub amount: C,
 
     /// If (and possibly how) the stack should be split after taking coins
-    pub split: NimSplit,
+    pub split: NimSplit<C>,
+
+    /// If you play with `Place` rule(s), this is the player who obtained the coins they are taking
+    /// (i.e. the player whose turn it is; filled in by [`crate::moves::check_move`] if [`None`]).  
+    /// Without `Place` rules, the taken coins leave the game, and [`crate::moves::check_move`] clears it.
+    pub from: Option<Player>,
+
+    /// The ID of the stack to take coins from, if the game has stack IDs  
+    /// (takes precedence over `stack_index`, which is filled in by [`crate::moves::check_move`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_id: Option<StackId>,
+}
+
+/// A move which takes coins from several stacks at once
+///
+/// (placing them into the player's pool, when used with Poker-Nim)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TakeManyAction<C = u64> {
+    /// The stacks to take coins from, and the number of coins to take from each of them
+    /// (every stack may only appear once)
+    pub takes: Vec<StackTake<C>>,
 
     /// If you play with `Place` rule(s), this is the player who obtained the coins they are taking
+    /// (i.e. the player whose turn it is; filled in by [`crate::moves::check_move`] if [`None`]).  
+    /// Without `Place` rules, the taken coins leave the game, and [`crate::moves::check_move`] clears it.
     pub from: Option<Player>,
 }
 
+impl<C: Clone> TakeManyAction<C> {
+    /// A joint take of the same number of coins from two stacks, as in Wythoff's game
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{moves, NimAction, NimGame, NimRule, RuleSet, Split, Stack, TakeManyAction, TakeSize};
+    ///
+    /// let wythoff = RuleSet::new([
+    ///     NimRule {
+    ///         take: TakeSize::Any,
+    ///         split: Split::Never,
+    ///     },
+    ///     NimRule {
+    ///         take: TakeSize::SameFromTwoStacks,
+    ///         split: Split::Never,
+    ///     },
+    /// ])
+    /// .unwrap();
+    ///
+    /// let mut game = NimGame::new(wythoff, vec![Stack(3), Stack(5)]);
+    /// moves::apply_move(&mut game, &NimAction::TakeMany(TakeManyAction::joint(0, 1, 2))).unwrap();
+    ///
+    /// assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(3)]);
+    /// ```
+    #[must_use]
+    pub fn joint(first: usize, second: usize, amount: C) -> Self {
+        Self {
+            takes: vec![
+                StackTake {
+                    stack_index: first,
+                    amount: amount.clone(),
+                    stack_id: None,
+                },
+                StackTake {
+                    stack_index: second,
+                    amount,
+                    stack_id: None,
+                },
+            ],
+            from: None,
+        }
+    }
+}
+
+/// The coins taken from a single stack in a [`TakeManyAction`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StackTake<C = u64> {
+    /// The index of the stack to take coins from
+    pub stack_index: usize,
+
+    /// The number of coins to take from the stack
+    pub amount: C,
+
+    /// The ID of the stack to take coins from, if the game has stack IDs  
+    /// (takes precedence over `stack_index`, which is filled in by [`crate::moves::check_move`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_id: Option<StackId>,
+}
+
+/// A move which makes a move in each of several stacks, in a conjunctive or selective sum of the stacks
+///
+/// Every take is checked like a [`NimAction::Take`] move of its own (see [`crate::moves::check_move`]).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CompoundAction<C = u64> {
+    /// The moves in the single stacks
+    /// (every stack may only appear once)
+    pub takes: Vec<TakeAction<C>>,
+}
+
+/// A move which moves coins from a stack onto another stack, as in Staircase Nim
+///
+/// The coins stay in the game, so no pool is involved.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TransferAction<C = u64> {
+    /// The index of the stack to move coins from
+    pub stack_index: usize,
+
+    /// The index of the stack to move coins onto
+    pub target_index: usize,
+
+    /// The number of coins to move
+    pub amount: C,
+
+    /// The ID of the stack to move coins from, if the game has stack IDs  
+    /// (takes precedence over `stack_index`, which is filled in by [`crate::moves::check_move`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_id: Option<StackId>,
+
+    /// The ID of the stack to move coins onto, if the game has stack IDs  
+    /// (takes precedence over `target_index`, which is filled in by [`crate::moves::check_move`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<StackId>,
+}
+
 /// A move which places coins onto a stack from the player's pool
 ///
 /// For use with Poker-Nim.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub struct PlaceAction {
+pub struct PlaceAction<C = u64> {
     /// The index of the stack to place coins onto
     pub stack_index: usize,
 
     /// The number of coins to place onto the stack,  
     /// taken from the player's pool
-    pub amount: u64,
+    pub amount: C,
 
     /// The player from whose pool the coins are taken from
+    /// (only the player whose turn it is may place coins)
     pub from: Player,
+
+    /// The ID of the stack to place coins onto, if the game has stack IDs  
+    /// (takes precedence over `stack_index`, which is filled in by [`crate::moves::check_move`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_id: Option<StackId>,
 }
 
 /// Represents a possible split of a stack into two non-empty stacks in a [`NimAction::Take`] move
 ///
 /// This struct represents the resulting split (if any) of a stack after a [`TakeAction`] is applied.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub enum NimSplit {
+pub enum NimSplit<C = u64> {
     /// The resulting stacks after a split
-    Yes(Stack, Stack),
+    Yes(Stack<C>, Stack<C>),
 
     /// The stack was not split
     No,
 }
 
-/// A player in a game of Nim, either A or B
+/// A player in a game of Nim, numbered from 0 in the order of their turns
 ///
-/// Useful for specifying from which player's pool coins are taken/placed
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub enum Player {
+/// Useful for specifying from which player's pool coins are taken/placed.  
+/// The players are called A, B, C, ... (see [`Player::A`] and [`Player::B`]);
+/// they are displayed and serialized by these letters, and by their numbers after Z.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "PlayerName")]
+pub struct Player(pub usize);
+
+impl Player {
     /// The first player
-    A,
+    pub const A: Player = Player(0);
 
     /// The second player
-    B,
+    pub const B: Player = Player(1);
+
+    /// The other player of a game of two players
+    #[must_use]
+    pub fn opponent(self) -> Player {
+        self.next(2)
+    }
+
+    /// The player whose turn comes next in a game of `player_count` players
+    ///
+    /// # Panics
+    ///
+    /// Panics if `player_count` is 0.
+    #[must_use]
+    pub fn next(self, player_count: usize) -> Player {
+        Player((self.0 + 1) % player_count)
+    }
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match u8::try_from(self.0) {
+            Ok(index) if index < 26 => write!(f, "{}", char::from(b'A' + index)),
+            _ => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl Serialize for Player {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0 < 26 {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serializer.serialize_u64(self.0 as u64)
+        }
+    }
+}
+
+/// The serialized form of a [`Player`]: a letter, or a number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PlayerName {
+    /// The letter of one of the first 26 players
+    Letter(String),
+
+    /// The number of any player
+    Number(usize),
+}
+
+impl TryFrom<PlayerName> for Player {
+    type Error = String;
+
+    fn try_from(name: PlayerName) -> Result<Self, Self::Error> {
+        match name {
+            PlayerName::Number(number) => Ok(Player(number)),
+            PlayerName::Letter(letter) => match letter.as_bytes() {
+                [letter @ b'A'..=b'Z'] => Ok(Player(usize::from(letter - b'A'))),
+                _ => Err(format!("{letter:?} is not the letter of a player")),
+            },
+        }
+    }
 }
@@ -11,9 +11,31 @@
 
 #![deny(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items, clippy::pedantic)]
+#![allow(
+    clippy::doc_comment_double_space_linebreaks,
+    clippy::non_std_lazy_statics
+)]
 
+mod bitset;
+mod coins;
+pub mod compound;
+pub mod fibonacci;
 mod game;
+pub mod moore;
 pub mod moves;
+pub mod multiplayer;
+mod nimber_arithmetic;
 pub mod nimbers;
+pub mod partizan;
+mod position;
+mod rule_set;
+pub mod solver;
+pub mod staircase;
+mod sum;
+pub mod wythoff;
 
+pub use coins::*;
 pub use game::*;
+pub use position::*;
+pub use rule_set::*;
+pub use sum::*;
@@ -5,7 +5,9 @@
 //! ```bash
 //! nimlib --help
 //! nimlib nimber --help
+//! nimlib table --help
 //! nimlib splits --help
+//! nimlib verify --help
 //! nimlib make-rule-set --help
 //! ```
 
@@ -14,7 +16,16 @@
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use clap_verbosity_flag::Verbosity;
-use nimlib::{nimbers, NimRule, Nimber, Split, Stack, TakeSize};
+use std::{
+    io::IsTerminal,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use nimlib::{
+    nimbers::{self, CalculationOptions, Progress},
+    solver::{self, Discrepancy},
+    NimRule, Nimber, RuleSet, Split, Stack, TakeSize,
+};
 use serde::Serialize;
 
 #[derive(clap::Parser)]
@@ -59,6 +70,33 @@ enum Action {
         #[arg(long, short = 'J')]
         /// Pretty-print the JSON output
         json_pretty: bool,
+
+        /// Number of threads to use (defaults to one per CPU core)
+        #[cfg(feature = "parallel")]
+        #[arg(long)]
+        jobs: Option<usize>,
+    },
+    #[command(
+        about = "Calculate the nimbers of all heights up to a given height for one or more rule sets"
+    )]
+    Table {
+        /// The greatest height to calculate the nimber for
+        #[arg()]
+        max_height: u64,
+
+        /// A JSON string containing the rules to use for the calculation (see `nimlib make-rule-set`);
+        /// may be given multiple times to calculate the tables of several rule sets
+        #[arg(long, short, required = true)]
+        rules: Vec<String>,
+
+        /// Print the table as a CSV (with commas, newlines, and a header)
+        #[arg(short, long)]
+        csv: bool,
+
+        /// Number of threads to use (defaults to one per CPU core)
+        #[cfg(feature = "parallel")]
+        #[arg(long)]
+        jobs: Option<usize>,
     },
     #[command(about = "Calculate all possible splits for a given height")]
     Splits {
@@ -72,6 +110,26 @@ enum Action {
     },
     #[command(about = "Create a JSON rule set using CLI parameters")]
     MakeRuleSet(MakeRuleSet),
+    #[command(
+        about = "Cross-check the nimbers of all positions up to given bounds with a brute-force solver"
+    )]
+    Verify {
+        /// The greatest height of the stacks of the positions to check
+        #[arg()]
+        max_height: u64,
+
+        /// The greatest number of stacks of the positions to check
+        #[arg(long, short = 's', default_value_t = 3)]
+        max_stacks: usize,
+
+        /// A JSON string containing the rules to use for the calculation (see `nimlib make-rule-set`)
+        #[arg(long, short)]
+        rules: String,
+
+        #[arg(long, short)]
+        /// Print the result as JSON
+        json: bool,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -83,6 +141,7 @@ enum PrintNimbers {
 }
 
 #[derive(Args, Debug, Serialize)]
+#[allow(clippy::struct_excessive_bools)]
 struct MakeRuleSet {
     /// A list of heights which remainder cannot be split
     #[arg(long, short = 'n')]
@@ -104,6 +163,26 @@ struct MakeRuleSet {
     #[arg(long, short = 'p')]
     allow_place: bool,
 
+    /// Allow for taking arbitrary amounts of coins from up to this many stacks at once (Moore's Nim)
+    #[arg(long, short = 'm')]
+    take_from_stacks: Option<usize>,
+
+    /// Allow for taking the same amount of coins from two stacks at once (Wythoff's game)
+    #[arg(long, short = 'w')]
+    take_same_from_two_stacks: bool,
+
+    /// Allow for taking up to this multiple of the coins taken by the previous move (Fibonacci Nim with 2)
+    #[arg(long, short = 'f')]
+    take_up_to_multiple_of_last: Option<u64>,
+
+    /// Allow for moving coins from a stack onto the stack before it (Staircase Nim)
+    #[arg(long, short = 't')]
+    transfer_to_neighbour: bool,
+
+    /// Allow for moving coins from a stack onto any stack before it
+    #[arg(long, short = 'l')]
+    transfer_to_lower: bool,
+
     /// Pretty-print the JSON output
     #[arg(long, short = 'P')]
     pretty_print: bool,
@@ -129,6 +208,9 @@ pub fn main() {
     log::trace!("CLI arguments: {:#?}", &args);
     log::trace!("Log level: {:#?}", &args.verbose.log_level());
 
+    // Show a progress bar for long calculations, unless the output is quieted
+    let show_progress = args.verbose.log_level().is_some() && std::io::stderr().is_terminal();
+
     match args.action {
         Action::Nimber {
             heights,
@@ -136,9 +218,40 @@ pub fn main() {
             print: print_style,
             json,
             json_pretty,
-        } => calculate_nimbers(print_style, &rules, heights, json, json_pretty),
+            #[cfg(feature = "parallel")]
+            jobs,
+        } => calculate_nimbers(
+            print_style,
+            &rules,
+            heights,
+            json,
+            json_pretty,
+            show_progress,
+            #[cfg(feature = "parallel")]
+            jobs,
+        ),
+        Action::Table {
+            max_height,
+            rules,
+            csv,
+            #[cfg(feature = "parallel")]
+            jobs,
+        } => calculate_tables(
+            max_height,
+            &rules,
+            csv,
+            show_progress,
+            #[cfg(feature = "parallel")]
+            jobs,
+        ),
         Action::Splits { height, csv } => calculate_splits(height, csv),
         Action::MakeRuleSet(options) => make_rule_set(options),
+        Action::Verify {
+            max_height,
+            max_stacks,
+            rules,
+            json,
+        } => verify(max_height, max_stacks, &rules, json),
     }
 }
 
@@ -149,6 +262,11 @@ fn make_rule_set(
         take_split_always,
         allow_any_take,
         allow_place,
+        take_from_stacks,
+        take_same_from_two_stacks,
+        take_up_to_multiple_of_last,
+        transfer_to_neighbour,
+        transfer_to_lower,
         pretty_print,
     }: MakeRuleSet,
 ) {
@@ -198,6 +316,40 @@ fn make_rule_set(
             split: Split::Never,
         });
     }
+    if let Some(stacks) = take_from_stacks {
+        rule_set.push(NimRule {
+            take: TakeSize::AnyFromStacks(stacks),
+            split: Split::Never,
+        });
+    }
+    if take_same_from_two_stacks {
+        rule_set.push(NimRule {
+            take: TakeSize::SameFromTwoStacks,
+            split: Split::Never,
+        });
+    }
+    if let Some(multiple) = take_up_to_multiple_of_last {
+        rule_set.push(NimRule {
+            take: TakeSize::UpToMultipleOfLast(multiple),
+            split: Split::Never,
+        });
+    }
+    if transfer_to_neighbour {
+        rule_set.push(NimRule {
+            take: TakeSize::TransferToNeighbour,
+            split: Split::Never,
+        });
+    }
+    if transfer_to_lower {
+        rule_set.push(NimRule {
+            take: TakeSize::TransferToLower,
+            split: Split::Never,
+        });
+    }
+    let rule_set = RuleSet::new(rule_set).unwrap_or_else(|error| {
+        log::error!("{error}");
+        std::process::exit(1);
+    });
     let rules = if pretty_print {
         serde_json::to_string_pretty(&rule_set).unwrap()
     } else {
@@ -227,12 +379,61 @@ fn calculate_splits(height: u64, csv: bool) {
     }
 }
 
+fn calculate_tables(
+    max_height: u64,
+    rules: &[String],
+    csv: bool,
+    show_progress: bool,
+    #[cfg(feature = "parallel")] jobs: Option<usize>,
+) {
+    let rule_sets = rules
+        .iter()
+        .map(|rules| parse_rules(rules).into())
+        .collect::<Vec<Vec<NimRule>>>();
+
+    let progress = progress_bar("Calculating tables");
+    let options = CalculationOptions {
+        progress: show_progress.then_some(&progress),
+        ..Default::default()
+    };
+
+    #[cfg(feature = "parallel")]
+    let tables =
+        nimbers::parallel::calculate_nimber_tables(&rule_sets, max_height, jobs, &options).unwrap();
+
+    #[cfg(not(feature = "parallel"))]
+    let tables = rule_sets
+        .iter()
+        .map(|rules| nimbers::calculate_nimbers_up_to_with(max_height, rules, &options).unwrap())
+        .collect::<Vec<_>>();
+
+    if csv {
+        let header = (1..=tables.len())
+            .map(|i| format!("nimber_{i}"))
+            .collect::<Vec<_>>();
+        println!("height,{}", header.join(","));
+    }
+
+    for height in 0..tables[0].len() {
+        let row = tables.iter().map(|table| table[height]);
+        if csv {
+            let row = row.map(|Nimber(n)| n.to_string()).collect::<Vec<_>>();
+            println!("{height},{}", row.join(","));
+        } else {
+            let row = row.map(|n| n.to_string()).collect::<Vec<_>>();
+            println!("Nimbers for stack of height {height}: {}", row.join(" "));
+        }
+    }
+}
+
 fn calculate_nimbers(
     print_style: Option<PrintNimbers>,
     rules: &str,
     heights: Vec<u64>,
     json: bool,
     json_pretty: bool,
+    show_progress: bool,
+    #[cfg(feature = "parallel")] jobs: Option<usize>,
 ) {
     /// For JSON output
     #[derive(Serialize)]
@@ -242,16 +443,33 @@ fn calculate_nimbers(
     }
 
     let print_style = print_style.unwrap_or_default();
-    let rules: Vec<NimRule> = serde_json::from_str(rules).unwrap();
+    let rules = parse_rules(rules);
+
+    // Calculate the nimbers of all heights at once (possibly in parallel), showing the progress
+    if let Some(&max_height) = heights.iter().max() {
+        let progress = progress_bar("Calculating nimbers");
+        let options = CalculationOptions {
+            progress: show_progress.then_some(&progress),
+            ..Default::default()
+        };
+
+        #[cfg(feature = "parallel")]
+        nimbers::parallel::calculate_nimbers_up_to(max_height, &rules, jobs, &options).unwrap();
+
+        #[cfg(not(feature = "parallel"))]
+        nimbers::calculate_nimbers_up_to_with(max_height, &rules, &options).unwrap();
+    }
+
     let mut nimbers = Vec::new();
     for height in heights {
-        let nimber = nimbers::calculate_nimber_for_height(height, &rules, 0);
+        let nimber = nimbers::calculate_nimber_for_height(height, &rules, 0)
+            .expect("The rules have been validated");
         if print_style != PrintNimbers::Position && !json && !json_pretty {
             println!("Nimber for stack of height {height}: {nimber}");
         }
         nimbers.push(nimber);
     }
-    let nimber = Nimber(nimbers.iter().fold(0, |acc, x| acc ^ x.0));
+    let nimber: Nimber = nimbers.iter().sum();
     if nimbers.len() > 1 && print_style != PrintNimbers::Stacks && !json && !json_pretty {
         println!("Nimber for the position: {nimber}");
     }
@@ -286,3 +504,77 @@ fn calculate_nimbers(
         log::warn!("--json and --json-pretty are mutually exclusive. Ignoring --json.");
     }
 }
+
+/// Cross-check the nimbers of all positions up to the given bounds, exiting with an error code on discrepancies
+fn verify(max_height: u64, max_stacks: usize, rules: &str, json: bool) {
+    let rules = parse_rules(rules);
+
+    let verification = solver::verify(&rules, max_height, max_stacks).unwrap_or_else(|error| {
+        log::error!("{error}");
+        std::process::exit(1);
+    });
+
+    if json {
+        println!("{}", serde_json::to_string(&verification).unwrap());
+    } else {
+        for Discrepancy {
+            stacks,
+            nimber,
+            outcome,
+        } in &verification.discrepancies
+        {
+            let stacks = stacks
+                .iter()
+                .map(|Stack(height)| height.to_string())
+                .collect::<Vec<_>>();
+            println!(
+                "Discrepancy for stacks [{}]: nimber {nimber}, but the player to move would {outcome}",
+                stacks.join(", ")
+            );
+        }
+
+        println!(
+            "Checked {} position(s), found {} discrepancy(ies)",
+            verification.positions,
+            verification.discrepancies.len()
+        );
+    }
+
+    if !verification.discrepancies.is_empty() {
+        std::process::exit(2);
+    }
+}
+
+/// Parse and validate a rule set given as a JSON string, exiting with an error message if it is invalid
+fn parse_rules(rules: &str) -> RuleSet {
+    serde_json::from_str(rules).unwrap_or_else(|error| {
+        log::error!("Could not parse the rules {rules}: {error}");
+        std::process::exit(1);
+    })
+}
+
+/// Create a progress bar for [`CalculationOptions::progress`], which is drawn on stderr
+fn progress_bar(message: &'static str) -> impl Fn(Progress) + Sync {
+    /// The width of the bar in characters
+    const WIDTH: usize = 40;
+
+    // Only redraw the bar if it changed
+    let drawn = AtomicU64::new(u64::MAX);
+
+    move |Progress { done, total }| {
+        let percent = (done * 100).checked_div(total).unwrap_or(100).min(100);
+
+        if drawn.swap(percent, Ordering::Relaxed) == percent {
+            return;
+        }
+
+        let filled = usize::try_from(percent).unwrap_or(100) * WIDTH / 100;
+        let bar = "#".repeat(filled) + &" ".repeat(WIDTH - filled);
+        eprint!("\r{message} [{bar}] {percent:3}% ({done}/{total})");
+
+        if done >= total {
+            // Clear the line once done
+            eprint!("\r{}\r", " ".repeat(message.len() + 60));
+        }
+    }
+}
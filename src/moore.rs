@@ -0,0 +1,118 @@
+//! Moore's Nim, where coins may be taken from up to k stacks in a single move.
+//! Moves across several stacks break the Sprague-Grundy decomposition into single stacks,
+//! so the outcomes of such games are found with Moore's theorem instead (see [`solve`]).
+
+use crate::{
+    solver::{check_two_players, Outcome, SolveError},
+    Coins, NimGame, PlayConvention, RuleSet, Split, TakeSize,
+};
+
+/// Get the number of stacks coins may be taken from in a single move, if the rules are those of Moore's Nim
+///
+/// The rules of Moore's Nim with `k` stacks consist of a single [`TakeSize::AnyFromStacks`] rule
+/// (in canonical form, see [`crate::RuleSet`]); for `k = 1`, this is ordinary Nim
+/// ([`TakeSize::Any`] without splitting).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{moore, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::AnyFromStacks(3),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+/// assert_eq!(moore::max_stacks(&rules), Some(3));
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+/// assert_eq!(moore::max_stacks(&rules), None);
+/// ```
+#[must_use]
+pub fn max_stacks(rules: &RuleSet) -> Option<usize> {
+    match rules.rules() {
+        [rule] if rule.split == Split::Never && !rules.is_partizan() => match rule.take {
+            TakeSize::Any => Some(1),
+            TakeSize::AnyFromStacks(stacks) => Some(stacks),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Find the outcome of a position of Moore's Nim for the player whose turn it is
+///
+/// By Moore's theorem, a position with up to `k` stacks per move (see [`max_stacks`]) is lost
+/// for the player to move (a _P-position_) if and only if,
+/// when writing the heights of the stacks in binary, the number of stacks with a 1 in each position
+/// is divisible by `k + 1` (i.e. the digits of the "sum" of the heights, added in base `k + 1` without carrying, are all 0).
+/// For `k = 1`, this is the XOR of the heights of ordinary Nim.
+///
+/// Under misère play, positions in which every stack holds at most 1 coin are lost
+/// if and only if the number of such stacks leaves a remainder of 1 when divided by `k + 1`;
+/// all other positions have the same outcome as under normal play.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{moore, solver::Outcome, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::AnyFromStacks(2),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// // Both binary digits occur 3 times
+/// let game = NimGame::new(rules.clone(), vec![Stack(1), Stack(2), Stack(3), Stack(3)]);
+/// assert_eq!(moore::solve(&game), Ok(Outcome::Loss));
+///
+/// // The player to move takes all coins from both stacks
+/// let game = NimGame::new(rules, vec![Stack(3), Stack(5)]);
+/// assert_eq!(moore::solve(&game), Ok(Outcome::Win));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotMooreNim`] if the rules of the game are not those of Moore's Nim,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn solve<C: Coins>(game: &NimGame<C>) -> Result<Outcome, SolveError> {
+    let modulus = max_stacks(game.get_rules()).ok_or(SolveError::NotMooreNim)? as u64 + 1;
+    check_two_players(game)?;
+    let stacks = game.get_stacks();
+
+    let misere_ending = game.get_convention() == PlayConvention::Misere
+        && stacks.iter().all(|stack| stack.0.bit_len() <= 1);
+    if misere_ending {
+        let ones = stacks.iter().filter(|stack| !stack.0.is_zero()).count() as u64;
+        return Ok(outcome(ones % modulus == 1));
+    }
+
+    let bits = stacks
+        .iter()
+        .map(|stack| stack.0.bit_len())
+        .max()
+        .unwrap_or(0);
+    let lost = (0..bits).all(|bit| {
+        let ones = stacks
+            .iter()
+            .filter(|stack| !stack.0.shr_bits(bit).low_bits(1).is_zero())
+            .count() as u64;
+        ones.is_multiple_of(modulus)
+    });
+
+    Ok(outcome(lost))
+}
+
+/// The outcome of a position for the player whose turn it is, given whether it is lost
+fn outcome(lost: bool) -> Outcome {
+    if lost {
+        Outcome::Loss
+    } else {
+        Outcome::Win
+    }
+}
@@ -9,250 +9,947 @@ use std::{error::Error, fmt::Display};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    nimbers::calculate_splits, NimAction, NimGame, NimRule, NimSplit, PlaceAction, Player, Split,
-    Stack, TakeAction, TakeSize,
+    Coins, Compound, CompoundAction, NimAction, NimGame, NimRule, NimSplit, PlaceAction, Player,
+    RuleSet, RuleSetError, Split, Stack, StackEntry, StackId, StackTake, TakeAction,
+    TakeManyAction, TakeSize, TransferAction,
 };
 
-/// Errors which may occur when applying a move
+/// Errors which may occur when checking or applying a move
+///
+/// Every error carries the details needed to explain the problem to a player,
+/// see the [`Display`] implementation for the messages.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-pub enum MoveError {
-    // TODO remove `InvalidMove` and replace it with more specific errors
-    /// The move is invalid for the given position
-    ///
-    /// This error is very generic and is subject to be replaced with more specific errors
-    InvalidMove,
-
+pub enum MoveError<C = u64> {
     /// The stack index is out of bounds
-    NoSuchStack,
+    NoSuchStack {
+        /// The index of the stack the move refers to
+        stack_index: usize,
+
+        /// The number of stacks in the position
+        stack_count: usize,
+    },
+
+    /// There is no stack with the ID the move refers to (or the game has no stack IDs)
+    NoSuchStackId {
+        /// The ID of the stack the move refers to
+        stack_id: StackId,
+    },
 
     /// The stack does not have enough coins to take (before a possible split)
-    NotEnoughCoinsOnStack,
+    NotEnoughCoinsOnStack {
+        /// The index of the stack
+        stack_index: usize,
+
+        /// The number of coins to take
+        amount: C,
+
+        /// The number of coins on the stack
+        available: C,
+    },
+
+    /// No rule allows taking the requested number of coins
+    NoSuchRule {
+        /// The number of coins to take
+        amount: C,
+    },
+
+    /// No rule allows placing coins (for [`NimAction::Place`] moves)
+    NoPlaceRule,
+
+    /// The resulting stacks of a split are empty,
+    /// or don't hold exactly the coins left on the stack after taking coins
+    InvalidSplit {
+        /// The index of the stack
+        stack_index: usize,
+
+        /// The number of coins left on the stack after taking coins
+        remainder: C,
+
+        /// The requested split
+        split: (Stack<C>, Stack<C>),
+    },
+
+    /// The rules allow taking the requested number of coins, but not splitting the stack afterwards
+    SplitNotAllowed {
+        /// The number of coins to take
+        amount: C,
+    },
+
+    /// The rules allow taking the requested number of coins, but only when splitting the stack afterwards
+    SplitRequired {
+        /// The number of coins to take
+        amount: C,
+    },
+
+    /// The player does not have enough coins to place (for [`NimAction::Place`] moves)
+    NotEnoughCoinsOnPlayer {
+        /// The player placing the coins
+        player: Player,
+
+        /// The number of coins to place
+        amount: C,
+
+        /// The number of coins in the player's pool
+        available: C,
+    },
+
+    /// The stack would hold more coins than the type of [`Coins`] can count after placing coins onto it
+    StackOverflow {
+        /// The index of the stack
+        stack_index: usize,
+    },
+
+    /// The pool of the player would hold more coins than the type of [`Coins`] can count after taking coins
+    PoolOverflow {
+        /// The player whose pool would overflow
+        player: Player,
+    },
+
+    /// A [`ValidatedMove`] was applied to a position it was not checked for
+    StaleMove,
+
+    /// The component index of a [`crate::SumAction`] is out of bounds
+    NoSuchComponent {
+        /// The index of the component the move refers to
+        component: usize,
+
+        /// The number of components in the sum
+        component_count: usize,
+    },
+
+    /// No rule allows taking coins from the requested number of stacks in a single move
+    /// (for [`NimAction::TakeMany`] moves)
+    NoSuchStackRule {
+        /// The number of stacks to take coins from
+        stack_count: usize,
+    },
+
+    /// The rules only allow taking the same number of coins from two stacks,
+    /// but a [`NimAction::TakeMany`] move takes different numbers of coins
+    DifferentAmounts {
+        /// The numbers of coins to take from the two stacks
+        amounts: (C, C),
+    },
+
+    /// A stack appears more than once in a [`NimAction::TakeMany`] move
+    DuplicateStack {
+        /// The index of the stack
+        stack_index: usize,
+    },
+
+    /// The rules only allow taking up to a limit, which depends on the previous move
+    /// (see [`TakeSize::UpToMultipleOfLast`])
+    TakeLimitExceeded {
+        /// The number of coins to take
+        amount: C,
 
-    /// No rule exists which supports the desired move
-    NoSuchRule,
+        /// The largest number of coins which may be taken
+        limit: C,
+    },
 
-    /// The split is invalid for the given move under ever rule in the specified game
-    InvalidSplit,
+    /// No rule allows moving coins from a stack onto the other stack (for [`NimAction::Transfer`] moves)
+    NoSuchTransferRule {
+        /// The index of the stack to move coins from
+        stack_index: usize,
 
-    /// Player does not have enough coins to place (for [`NimAction::Place`] moves)
-    NotEnoughCoinsOnPlayer,
+        /// The index of the stack to move coins onto
+        target_index: usize,
+    },
+
+    /// The move uses the pool of a player whose turn it is not
+    NotYourTurn {
+        /// The player whose pool the move uses
+        player: Player,
+
+        /// The player whose turn it is
+        to_move: Player,
+    },
+
+    /// The stacks are a conjunctive sum, but the move doesn't make a move in a stack which allows one
+    /// (see [`crate::Compound::Conjunctive`])
+    MissingStack {
+        /// The index of the stack
+        stack_index: usize,
+    },
+
+    /// The game keeps track of its nimber, but the stack would be higher than [`u64::MAX`],
+    /// so its nimber couldn't be calculated (see [`NimGame::set_track_nimber`])
+    StackTooHigh {
+        /// The index of the stack
+        stack_index: usize,
+    },
 }
 
-impl Display for MoveError {
+impl<C: Coins> Display for MoveError<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MoveError::InvalidMove => write!(f, "The move is invalid for the given position"),
-            MoveError::NoSuchStack => write!(f, "The stack index is out of bounds"),
-            MoveError::NotEnoughCoinsOnStack => {
-                write!(f, "The stack does not have enough coins to take")
+            MoveError::NoSuchStack {
+                stack_index,
+                stack_count,
+            } => write!(
+                f,
+                "There is no stack {stack_index}; the position has {stack_count} stack(s)"
+            ),
+            MoveError::NoSuchStackId { stack_id } => {
+                write!(f, "There is no stack {stack_id} (anymore)")
+            }
+            MoveError::NotEnoughCoinsOnStack {
+                stack_index,
+                amount,
+                available,
+            } => write!(
+                f,
+                "Can't take {amount} coin(s) from stack {stack_index}, which only has {available} coin(s)"
+            ),
+            MoveError::NoSuchRule { amount } => {
+                write!(f, "The rules don't allow taking {amount} coin(s)")
+            }
+            MoveError::NoPlaceRule => write!(f, "The rules don't allow placing coins"),
+            MoveError::InvalidSplit {
+                stack_index,
+                remainder,
+                split: (a, b),
+            } => write!(
+                f,
+                "Can't split the {remainder} remaining coin(s) of stack {stack_index} into stacks of {} and {} coin(s)",
+                a.0, b.0
+            ),
+            MoveError::SplitNotAllowed { amount } => write!(
+                f,
+                "The rules don't allow splitting the stack after taking {amount} coin(s)"
+            ),
+            MoveError::SplitRequired { amount } => write!(
+                f,
+                "The rules require splitting the stack after taking {amount} coin(s)"
+            ),
+            MoveError::NotEnoughCoinsOnPlayer {
+                player,
+                amount,
+                available,
+            } => write!(
+                f,
+                "Player {player} can't place {amount} coin(s), as they only have {available} coin(s)"
+            ),
+            MoveError::StackOverflow { stack_index } => {
+                write!(f, "Stack {stack_index} can't hold any more coins")
+            }
+            MoveError::PoolOverflow { player } => {
+                write!(f, "The pool of player {player} can't hold any more coins")
             }
-            MoveError::NoSuchRule => write!(f, "No rule exists which supports the desired move"),
-            MoveError::InvalidSplit => write!(f, "The split is invalid for the given move"),
-            MoveError::NotEnoughCoinsOnPlayer => {
-                write!(f, "Player does not have enough coins to place")
+            MoveError::StaleMove => write!(
+                f,
+                "The move was checked for a different position and has to be checked again"
+            ),
+            MoveError::NoSuchComponent {
+                component,
+                component_count,
+            } => write!(
+                f,
+                "There is no component {component}; the sum has {component_count} component(s)"
+            ),
+            MoveError::NoSuchStackRule { stack_count } => write!(
+                f,
+                "The rules don't allow taking coins from {stack_count} stack(s) in a single move"
+            ),
+            MoveError::DifferentAmounts { amounts: (a, b) } => write!(
+                f,
+                "The rules only allow taking the same number of coins from two stacks, not {a} and {b} coin(s)"
+            ),
+            MoveError::DuplicateStack { stack_index } => {
+                write!(f, "Can't take coins from stack {stack_index} more than once")
             }
+            MoveError::TakeLimitExceeded { amount, limit } => write!(
+                f,
+                "Can't take {amount} coin(s), the previous move only allows taking up to {limit} coin(s)"
+            ),
+            MoveError::NoSuchTransferRule {
+                stack_index,
+                target_index,
+            } => write!(
+                f,
+                "The rules don't allow moving coins from stack {stack_index} onto stack {target_index}"
+            ),
+            MoveError::NotYourTurn { player, to_move } => write!(
+                f,
+                "It is the turn of player {to_move}, so the pool of player {player} can't be used"
+            ),
+            MoveError::MissingStack { stack_index } => write!(
+                f,
+                "The stacks are a conjunctive sum, so the move has to take coins from stack {stack_index} as well"
+            ),
+            MoveError::StackTooHigh { stack_index } => write!(
+                f,
+                "Stack {stack_index} would be too high to keep track of the nimber of the game"
+            ),
         }
     }
 }
 
-impl Error for MoveError {}
+impl<C: Coins> Error for MoveError<C> {}
 
 /// Determine if a move is valid for a given position
 ///
+/// A [`NimAction::Take`] move is valid if a single rule allows both
+/// taking `amount` coins and the split (or the lack thereof),
+/// and the coins are conserved: the resulting stacks of a split are non-empty
+/// and hold exactly the coins left on the stack after taking `amount` coins.
+/// [`TakeSize::UpToMultipleOfLast`] rules only allow `amount` up to the limit set by the previous move
+/// (see [`take_limit`]).  
+/// A [`NimAction::Place`] move is valid if there is a [`TakeSize::Place`] rule
+/// and the player has enough coins in their pool.  
+/// A [`NimAction::TakeMany`] move is valid if a [`TakeSize::AnyFromStacks`] rule allows taking coins
+/// from (at least) that many stacks, or a [`TakeSize::SameFromTwoStacks`] rule allows taking the same number
+/// of coins from two stacks, and at least one coin is taken from every stack, which has enough coins.  
+/// A [`NimAction::Transfer`] move is valid if a [`TakeSize::TransferToNeighbour`] or [`TakeSize::TransferToLower`]
+/// rule allows moving coins onto the target stack, and at least one coin is moved from a stack which has enough coins.  
+/// A [`NimAction::Compound`] move is valid if the stacks are a conjunctive or selective sum
+/// (see [`NimGame::set_compound`]), and every take is valid on its own and involves a different stack.
+/// In conjunctive sums, every stack which allows a move has to be involved
+/// (by a [`NimAction::Compound`] move, or by a [`NimAction::Take`] move if it is the only such stack).
+///
+/// # Returns
+///
+/// A [`ValidatedMove`], which can be applied with [`apply_validated_move`] without checking it again.
+/// It includes the rule which justifies the move (the first one, if several rules allow it;
+/// for [`NimAction::Compound`] moves, the rule which justifies the first take).  
+/// As the rules of a game are kept in canonical form (see [`crate::RuleSet`]),
+/// this is a canonical rule, which is not necessarily one of the rules the game was created from.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{moves::check_move, NimAction, NimGame, NimRule, NimSplit, RuleSet, Split, Stack, TakeAction, TakeSize};
+///
+/// let rules = RuleSet::new(vec![
+///     NimRule {
+///         take: TakeSize::List(vec![1]),
+///         split: Split::Never,
+///     },
+///     NimRule {
+///         take: TakeSize::List(vec![2]),
+///         split: Split::Always,
+///     },
+/// ])
+/// .unwrap();
+///
+/// let game = NimGame::new(rules, vec![Stack(7)]);
+///
+/// let split = NimAction::Take(TakeAction {
+///     stack_index: 0,
+///     amount: 2,
+///     split: NimSplit::Yes(Stack(2), Stack(3)),
+///     from: None,
+///     stack_id: None,
+/// });
+///
+/// assert_eq!(
+///     check_move(&game, &split).unwrap().rule(),
+///     &NimRule {
+///         take: TakeSize::List(vec![2]),
+///         split: Split::Always,
+///     }
+/// );
+/// ```
+///
 /// # Errors
 ///
-/// Returns `Ok(())` if the move is valid, or an error if the move is invalid
+/// Returns an error if the move is invalid
 /// (see [`MoveError`] for possible errors).
-pub fn check_move(game: &NimGame, mov: &NimAction) -> Result<(), MoveError> {
+pub fn check_move<C: Coins>(
+    game: &NimGame<C>,
+    mov: &NimAction<C>,
+) -> Result<ValidatedMove<C>, MoveError<C>> {
+    // When playing with pools, the taken coins go to the pool of the player whose turn it is;
+    // otherwise the taken coins leave the game, whatever the move says
+    let uses_pools = uses_pools(game.rules.rules_for(game.to_move));
+    let mut mov = mov.clone();
+    let froms = match &mut mov {
+        NimAction::Take(take) => vec![&mut take.from],
+        NimAction::TakeMany(take_many) => vec![&mut take_many.from],
+        NimAction::Compound(compound) => compound
+            .takes
+            .iter_mut()
+            .map(|take| &mut take.from)
+            .collect(),
+        NimAction::Place(_) | NimAction::Transfer(_) => Vec::new(),
+    };
+    for from in froms {
+        if !uses_pools {
+            *from = None;
+        } else if from.is_none() {
+            *from = Some(game.to_move);
+        }
+    }
+
+    // Resolve the stack IDs of the move, or fill them in if the game has stack IDs
+    let stack_refs: Vec<(&mut usize, &mut Option<StackId>)> = match &mut mov {
+        NimAction::Take(take) => vec![(&mut take.stack_index, &mut take.stack_id)],
+        NimAction::Place(place) => vec![(&mut place.stack_index, &mut place.stack_id)],
+        NimAction::TakeMany(take_many) => take_many
+            .takes
+            .iter_mut()
+            .map(|take| (&mut take.stack_index, &mut take.stack_id))
+            .collect(),
+        NimAction::Transfer(transfer) => vec![
+            (&mut transfer.stack_index, &mut transfer.stack_id),
+            (&mut transfer.target_index, &mut transfer.target_id),
+        ],
+        NimAction::Compound(compound) => compound
+            .takes
+            .iter_mut()
+            .map(|take| (&mut take.stack_index, &mut take.stack_id))
+            .collect(),
+    };
+    for (stack_index, stack_id) in stack_refs {
+        match stack_id {
+            Some(id) => {
+                *stack_index = game
+                    .find_stack(*id)
+                    .ok_or(MoveError::NoSuchStackId { stack_id: *id })?;
+            }
+            None => {
+                *stack_id = game
+                    .get_stack_ids()
+                    .and_then(|ids| ids.get(*stack_index))
+                    .copied();
+            }
+        }
+    }
+
+    let rule = justify_move(game, &mov)?.clone();
+    check_involved_stacks(game, &mov)?;
+
+    let pool_coins = match &mov {
+        NimAction::Take(_)
+        | NimAction::TakeMany(_)
+        | NimAction::Transfer(_)
+        | NimAction::Compound(_) => None,
+        NimAction::Place(place) => Some(pool_coins(game, place.from)),
+    };
+
+    Ok(ValidatedMove {
+        stack_heights: stack_indices(&mov)
+            .into_iter()
+            .map(|stack_index| game.stacks[stack_index].0.clone())
+            .collect(),
+        action: mov,
+        rule,
+        rules: game.rules.fingerprint(),
+        to_move: game.to_move,
+        compound: game.compound,
+        all_stacks: (game.compound == Compound::Conjunctive).then(|| game.stacks.clone()),
+        last_take: game.last_take.clone(),
+        pool_coins,
+    })
+}
+
+/// Get the indices of the stacks a move refers to
+fn stack_indices<C>(mov: &NimAction<C>) -> Vec<usize> {
+    match mov {
+        NimAction::Take(take) => vec![take.stack_index],
+        NimAction::Place(place) => vec![place.stack_index],
+        NimAction::TakeMany(take_many) => take_many
+            .takes
+            .iter()
+            .map(|take| take.stack_index)
+            .collect(),
+        NimAction::Transfer(transfer) => vec![transfer.stack_index, transfer.target_index],
+        NimAction::Compound(compound) => {
+            compound.takes.iter().map(|take| take.stack_index).collect()
+        }
+    }
+}
+
+/// Get the IDs of the stacks a move refers to (if any), in the order of [`stack_indices`]
+fn stack_ids<C>(mov: &NimAction<C>) -> Vec<Option<StackId>> {
+    match mov {
+        NimAction::Take(take) => vec![take.stack_id],
+        NimAction::Place(place) => vec![place.stack_id],
+        NimAction::TakeMany(take_many) => {
+            take_many.takes.iter().map(|take| take.stack_id).collect()
+        }
+        NimAction::Transfer(transfer) => vec![transfer.stack_id, transfer.target_id],
+        NimAction::Compound(compound) => compound.takes.iter().map(|take| take.stack_id).collect(),
+    }
+}
+
+/// A move which was checked by [`check_move`] and can be applied with [`apply_validated_move`]
+///
+/// The validity of a move only depends on the rules of the game, the player whose turn it is,
+/// the heights of the stacks the move refers to, the pool of the player placing coins,
+/// the coins taken by the previous move, and how the stacks are combined
+/// (in conjunctive sums, on the heights of all stacks).
+/// These are remembered, so the move can be applied to the position it was checked for
+/// (or any other position which agrees in these details) without checking it again.
+///
+/// Only [`check_move`] creates validated moves: they can be serialized (e.g. for logging),
+/// but not deserialized, as a deserialized move could have been changed after it was checked.
+///
+/// ```compile_fail
+/// use nimlib::moves::ValidatedMove;
+///
+/// let json = r#"{"action":{"Take":{"stack_index":0,"amount":5,"split":"No","from":null}}}"#;
+/// let forged: ValidatedMove = serde_json::from_str(json).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct ValidatedMove<C = u64> {
+    /// The move itself
+    action: NimAction<C>,
+
+    /// The rule which justifies the move
+    rule: NimRule,
+
+    /// The fingerprint of the rules the move was checked against
+    rules: u64,
+
+    /// The player whose turn it was when the move was checked
+    to_move: Player,
+
+    /// How the stacks were combined when the move was checked
+    compound: Compound,
+
+    /// The stacks when the move was checked, if the move had to involve every stack which allows a move
+    all_stacks: Option<Vec<Stack<C>>>,
+
+    /// The heights of the stacks the move refers to, when the move was checked
+    stack_heights: Vec<C>,
+
+    /// The coins taken by the previous move, when the move was checked
+    last_take: Option<C>,
+
+    /// The coins in the pool of the player placing coins, when the move was checked
+    pool_coins: Option<C>,
+}
+
+impl<C: Coins> ValidatedMove<C> {
+    /// Get the move itself
+    #[must_use]
+    pub fn action(&self) -> &NimAction<C> {
+        &self.action
+    }
+
+    /// Get the (canonical) rule which justifies the move
+    #[must_use]
+    pub fn rule(&self) -> &NimRule {
+        &self.rule
+    }
+
+    /// Get the move itself, discarding the validation
+    #[must_use]
+    pub fn into_action(self) -> NimAction<C> {
+        self.action
+    }
+
+    /// Check if the move was validated for the given game (or an equivalent one)
+    fn is_valid_for(&self, game: &NimGame<C>) -> bool {
+        let pool_coins = match &self.action {
+            NimAction::Take(_)
+            | NimAction::TakeMany(_)
+            | NimAction::Transfer(_)
+            | NimAction::Compound(_) => None,
+            NimAction::Place(place) => Some(pool_coins(game, place.from)),
+        };
+
+        let stacks_match = stack_indices(&self.action)
+            .into_iter()
+            .zip(stack_ids(&self.action))
+            .zip(&self.stack_heights)
+            .all(|((stack_index, stack_id), height)| {
+                game.stacks.get(stack_index).map(|stack| &stack.0) == Some(height)
+                    && stack_id
+                        == game
+                            .get_stack_ids()
+                            .and_then(|ids| ids.get(stack_index))
+                            .copied()
+            });
+
+        self.rules == game.rules.fingerprint()
+            && self.to_move == game.to_move
+            && pool_coins == self.pool_coins
+            && self.last_take == game.last_take
+            && self.compound == game.compound
+            && self
+                .all_stacks
+                .as_ref()
+                .is_none_or(|stacks| *stacks == game.stacks)
+            && stacks_match
+    }
+}
+
+/// Check if the rules of a player involve the pools of the players (i.e. allow placing coins)
+fn uses_pools(rules: &[NimRule]) -> bool {
+    rules.iter().any(|rule| rule.take == TakeSize::Place)
+}
+
+/// Check if it is the turn of the given player, whose pool a move uses
+fn check_turn<C>(game: &NimGame<C>, player: Player) -> Result<(), MoveError<C>> {
+    if player == game.to_move {
+        Ok(())
+    } else {
+        Err(MoveError::NotYourTurn {
+            player,
+            to_move: game.to_move,
+        })
+    }
+}
+
+/// Get the number of coins in the pool of a player
+fn pool_coins<C: Coins>(game: &NimGame<C>, player: Player) -> C {
+    game.get_pool_coins(player)
+}
+
+/// Find the rule which justifies a move, see [`check_move`]
+fn justify_move<'a, C: Coins>(
+    game: &'a NimGame<C>,
+    mov: &NimAction<C>,
+) -> Result<&'a NimRule, MoveError<C>> {
     match mov {
         NimAction::Take(TakeAction {
             stack_index,
             amount,
             split,
-            from: _, // Don't need to check this field for `Take` moves
+            from,
+            ..
         }) => {
+            // Only the player whose turn it is may receive the taken coins
+            if let Some(player) = from {
+                check_turn(game, *player)?;
+            }
+
             // Get the stack to take coins from
-            let stack = game
-                .stacks
-                .get(*stack_index)
-                .ok_or(MoveError::NoSuchStack)?;
-
-            // Check if a rule can support the desired move (taking)
-            let mut supporting_rules = Vec::new();
-
-            for rule in &game.rules {
-                let supports = match &rule.take {
-                    TakeSize::List(list) => list.contains(amount),
-                    TakeSize::Any => true,
-                    TakeSize::Place => false,
-                };
-
-                if supports {
-                    supporting_rules.push(rule);
-                    break;
+            let stack = get_stack(game, *stack_index)?;
+
+            // Find the rules which allow taking `amount` coins
+            let allows_amount = |rule: &&NimRule| match &rule.take {
+                TakeSize::List(list) => list.iter().any(|&take| C::from(take) == *amount),
+                TakeSize::Any | TakeSize::AnyFromStacks(_) => !amount.is_zero(),
+                TakeSize::UpToMultipleOfLast(multiple) => {
+                    !amount.is_zero()
+                        && take_limit(*multiple, game.last_take.as_ref(), &stack.0)
+                            .is_none_or(|limit| *amount <= limit)
                 }
-            }
+                TakeSize::Place
+                | TakeSize::SameFromTwoStacks
+                | TakeSize::TransferToNeighbour
+                | TakeSize::TransferToLower => false,
+            };
 
-            if supporting_rules.is_empty() {
-                return Err(MoveError::NoSuchRule);
+            if !game
+                .rules
+                .rules_for(game.to_move)
+                .iter()
+                .any(|rule| allows_amount(&rule))
+            {
+                return Err(no_rule_error(game, amount, &stack.0));
             }
 
             // Check if the stack has enough coins to take
-            if stack.0 < *amount {
-                return Err(MoveError::NotEnoughCoinsOnStack);
-            }
+            let Some(remainder) = stack.0.try_sub(amount) else {
+                return Err(MoveError::NotEnoughCoinsOnStack {
+                    stack_index: *stack_index,
+                    amount: amount.clone(),
+                    available: stack.0.clone(),
+                });
+            };
 
-            // Check if the move is valid for at least one rule (splitting)
-            let mut valid = false;
-            for rule in supporting_rules {
-                match rule.split {
-                    Split::Never => {
-                        // TODO consider replacing this with a regular if statement
-                        if let NimSplit::No = split {
-                            valid = true;
-                            break;
-                        }
-                    }
-                    Split::Optional => {
-                        // TODO consider replacing this with a regular if statement
-                        if let NimSplit::Yes(a, b) = split {
-                            // FIXME replace `true` with a check if the rule allowing for the split allows taking the `amount` of coins
-                            if ((a.0 + b.0 + amount) <= stack.0) && a.0 != 0 && b.0 != 0 && (true) {
-                                valid = true;
-                                break;
-                            }
-                        } else {
-                            // Splitting is optional and no split was specified, so the move is valid
-                            valid = true;
-                            break;
-                        }
-                    }
-                    Split::Always => {
-                        // TODO consider replacing this with a regular if statement
-                        if let NimSplit::Yes(a, b) = split {
-                            // FIXME replace `true` with a check if the rule allowing for the split allows taking the `amount` of coins
-                            if ((a.0 + b.0 + amount) <= stack.0) && a.0 != 0 && b.0 != 0 && (true) {
-                                valid = true;
-                                break;
-                            }
-                        }
-                        // No `else` clause as the move is invalid if no split was specified
-                    }
+            // The resulting stacks must be non-empty and hold exactly the remaining coins
+            if let NimSplit::Yes(a, b) = split {
+                if a.0.is_zero() || b.0.is_zero() || a.0.try_add(&b.0) != Some(remainder.clone()) {
+                    return Err(MoveError::InvalidSplit {
+                        stack_index: *stack_index,
+                        remainder,
+                        split: (a.clone(), b.clone()),
+                    });
                 }
             }
 
-            if !valid {
-                return Err(MoveError::InvalidSplit);
-            }
+            // The same rule has to allow both the amount and the split
+            let allows_split = |rule: &&NimRule| match split {
+                NimSplit::Yes(..) => rule.split != Split::Never,
+                NimSplit::No => rule.split != Split::Always,
+            };
+
+            game.rules
+                .rules_for(game.to_move)
+                .iter()
+                .filter(allows_amount)
+                .find(allows_split)
+                .ok_or_else(|| match split {
+                    NimSplit::Yes(..) => MoveError::SplitNotAllowed {
+                        amount: amount.clone(),
+                    },
+                    NimSplit::No => MoveError::SplitRequired {
+                        amount: amount.clone(),
+                    },
+                })
         }
         NimAction::Place(PlaceAction {
             stack_index,
             amount,
             from,
+            ..
         }) => {
-            if game.rules.iter().all(|rule| rule.take != TakeSize::Place) {
-                return Err(MoveError::NoSuchRule);
-            }
+            let rule = game
+                .rules
+                .rules_for(game.to_move)
+                .iter()
+                .find(|rule| rule.take == TakeSize::Place)
+                .ok_or(MoveError::NoPlaceRule)?;
+
+            // Only the player whose turn it is may place coins from their pool
+            check_turn(game, *from)?;
 
-            // Get the stack to place coins onto
-            let stack = game
-                .stacks
-                .get(*stack_index)
-                .ok_or(MoveError::NoSuchStack)?;
+            // Make sure the stack to place coins onto exists
+            get_stack(game, *stack_index)?;
 
             // Check if the player has sufficient coins to place
-            let player_coins = match from {
-                crate::Player::A => game.coins_a,
-                crate::Player::B => game.coins_b,
-            };
+            let player_coins = pool_coins(game, *from);
 
             if player_coins < *amount {
-                return Err(MoveError::NotEnoughCoinsOnStack);
+                return Err(MoveError::NotEnoughCoinsOnPlayer {
+                    player: *from,
+                    amount: amount.clone(),
+                    available: player_coins,
+                });
             }
 
-            return Ok(());
+            Ok(rule)
         }
+        NimAction::TakeMany(take_many) => justify_take_many(game, take_many),
+        NimAction::Transfer(transfer) => justify_transfer(game, transfer),
+        NimAction::Compound(compound) => justify_compound(game, compound),
     }
+}
 
-    Ok(())
+/// The error for taking `amount` coins from a stack of `height` coins, which no rule allows
+///
+/// Reports the largest limit of the rules depending on the previous move, if any.
+fn no_rule_error<C: Coins>(game: &NimGame<C>, amount: &C, height: &C) -> MoveError<C> {
+    let limit = game
+        .rules
+        .rules_for(game.to_move)
+        .iter()
+        .filter_map(|rule| match rule.take {
+            TakeSize::UpToMultipleOfLast(multiple) => {
+                take_limit(multiple, game.last_take.as_ref(), height)
+            }
+            _ => None,
+        })
+        .max();
+
+    match limit {
+        Some(limit) if !amount.is_zero() => MoveError::TakeLimitExceeded {
+            amount: amount.clone(),
+            limit,
+        },
+        _ => MoveError::NoSuchRule {
+            amount: amount.clone(),
+        },
+    }
 }
 
-/// The implementation of [`apply_move`] and [`apply_move_unchecked`]
-fn apply_move_(game: &mut NimGame, mov: &NimAction, unchecked: bool) -> Result<(), MoveError> {
-    // Assure that the move is valid
-    if !unchecked {
-        check_move(game, mov)?;
+/// Find the rule which justifies a [`NimAction::TakeMany`] move, see [`check_move`]
+fn justify_take_many<'a, C: Coins>(
+    game: &'a NimGame<C>,
+    TakeManyAction { takes, from }: &TakeManyAction<C>,
+) -> Result<&'a NimRule, MoveError<C>> {
+    // Only the player whose turn it is may receive the taken coins
+    if let Some(player) = from {
+        check_turn(game, *player)?;
     }
 
-    match mov {
-        NimAction::Take(TakeAction {
-            stack_index,
-            amount,
-            split,
-            from,
-        }) => {
-            // Get the stack to take coins from
-            let stack = game
-                .stacks
-                .get_mut(*stack_index)
-                .ok_or(MoveError::NoSuchStack)?;
+    // Find a rule which allows taking coins from that many stacks
+    let same_amounts = takes
+        .windows(2)
+        .all(|pair| pair[0].amount == pair[1].amount);
+    let rule = game
+        .rules
+        .rules_for(game.to_move)
+        .iter()
+        .find(|rule| match rule.take {
+            TakeSize::AnyFromStacks(stacks) => (1..=stacks).contains(&takes.len()),
+            TakeSize::SameFromTwoStacks => takes.len() == 2 && same_amounts,
+            _ => false,
+        });
+    let Some(rule) = rule else {
+        let joint = game
+            .rules
+            .rules_for(game.to_move)
+            .iter()
+            .any(|rule| rule.take == TakeSize::SameFromTwoStacks);
+        return Err(match takes.as_slice() {
+            [first, second] if joint => MoveError::DifferentAmounts {
+                amounts: (first.amount.clone(), second.amount.clone()),
+            },
+            _ => MoveError::NoSuchStackRule {
+                stack_count: takes.len(),
+            },
+        });
+    };
 
-            // Take coins from the stack
-            stack.0 -= amount;
+    for (i, take) in takes.iter().enumerate() {
+        let stack = get_stack(game, take.stack_index)?;
 
-            // Split the coins if necessary
-            if let NimSplit::Yes(a, b) = split {
-                // Insert stacks `a` and `b` into `stacks` at position `stack_index`
-                // And remove the original stack at `stack_index`
-                game.stacks
-                    .splice(*stack_index..=*stack_index, [*a, *b].into_iter());
-            }
+        if takes[..i]
+            .iter()
+            .any(|other| other.stack_index == take.stack_index)
+        {
+            return Err(MoveError::DuplicateStack {
+                stack_index: take.stack_index,
+            });
+        }
 
-            if let Some(player) = from {
-                // Remove coins from the player's pool
-                match player {
-                    crate::Player::A => {
-                        game.coins_a = game.coins_a.checked_add(*amount).expect("Coin overflow")
-                    }
-                    crate::Player::B => {
-                        game.coins_b = game.coins_b.checked_add(*amount).expect("Coin overflow")
-                    }
-                };
-            }
+        if take.amount.is_zero() {
+            return Err(MoveError::NoSuchRule {
+                amount: take.amount.clone(),
+            });
         }
-        NimAction::Place(PlaceAction {
-            stack_index,
-            amount,
-            from,
-        }) => {
-            // Get the stack to place coins onto
-            let stack = game
-                .stacks
-                .get_mut(*stack_index)
-                .ok_or(MoveError::NoSuchStack)?;
-
-            // Place coins onto the stack
-            stack.0 += amount;
-
-            // Remove coins from the player's pool
-            match from {
-                crate::Player::A => {
-                    game.coins_a = game.coins_a.checked_sub(*amount).expect(
-                        "Coin underflow; this should not happen, as the move was checked before",
-                    )
-                }
-                crate::Player::B => {
-                    game.coins_b = game.coins_b.checked_sub(*amount).expect(
-                        "Coin underflow; this should not happen, as the move was checked before",
-                    )
-                }
-            };
+
+        if stack.0 < take.amount {
+            return Err(MoveError::NotEnoughCoinsOnStack {
+                stack_index: take.stack_index,
+                amount: take.amount.clone(),
+                available: stack.0.clone(),
+            });
+        }
+    }
+
+    Ok(rule)
+}
+
+/// Find the rule which justifies the first take of a [`NimAction::Compound`] move, see [`check_move`]
+fn justify_compound<'a, C: Coins>(
+    game: &'a NimGame<C>,
+    CompoundAction { takes }: &CompoundAction<C>,
+) -> Result<&'a NimRule, MoveError<C>> {
+    if game.compound == Compound::Disjunctive || takes.is_empty() {
+        return Err(MoveError::NoSuchStackRule {
+            stack_count: takes.len(),
+        });
+    }
+
+    let mut rule = None;
+    for (i, take) in takes.iter().enumerate() {
+        if takes[..i]
+            .iter()
+            .any(|other| other.stack_index == take.stack_index)
+        {
+            return Err(MoveError::DuplicateStack {
+                stack_index: take.stack_index,
+            });
+        }
+
+        let take_rule = justify_move(game, &NimAction::Take(take.clone()))?;
+        rule.get_or_insert(take_rule);
+    }
+
+    rule.ok_or(MoveError::NoSuchStackRule { stack_count: 0 })
+}
+
+/// Check if a move involves every stack which allows a move, if the stacks are a conjunctive sum
+fn check_involved_stacks<C: Coins>(
+    game: &NimGame<C>,
+    mov: &NimAction<C>,
+) -> Result<(), MoveError<C>> {
+    if game.compound != Compound::Conjunctive {
+        return Ok(());
+    }
+
+    let involved = stack_indices(mov);
+    let rules = game.rules.rules_for(game.to_move);
+    for (stack_index, stack) in game.stacks.iter().enumerate() {
+        if !involved.contains(&stack_index) && !stack_moves(stack_index, stack, rules).is_empty() {
+            return Err(MoveError::MissingStack { stack_index });
         }
     }
 
     Ok(())
 }
 
+/// Get the largest number of coins a [`TakeSize::UpToMultipleOfLast`] rule allows taking from a stack
+///
+/// After a move which took `last_take` coins (see [`NimGame::get_last_take`]),
+/// up to `multiple` times as many coins may be taken; on the first move, all coins but one.
+/// Returns [`None`] if the limit can't be counted with the type of [`Coins`], i.e. there is no limit.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::moves::take_limit;
+///
+/// assert_eq!(take_limit(2, Some(&3_u64), &20), Some(6));
+/// assert_eq!(take_limit(2, None, &20_u64), Some(19));
+/// assert_eq!(take_limit(2, Some(&u64::MAX), &20), None);
+/// ```
+#[must_use]
+pub fn take_limit<C: Coins>(multiple: u64, last_take: Option<&C>, height: &C) -> Option<C> {
+    let Some(last_take) = last_take else {
+        return Some(height.try_sub(&C::from(1)).unwrap_or_else(|| C::from(0)));
+    };
+
+    // Double and add, from the highest bit of the multiple
+    let mut limit = C::from(0);
+    for bit in (0..u64::BITS - multiple.leading_zeros()).rev() {
+        limit = limit.try_add(&limit)?;
+        if multiple >> bit & 1 == 1 {
+            limit = limit.try_add(last_take)?;
+        }
+    }
+    Some(limit)
+}
+
+/// Find the rule which justifies a [`NimAction::Transfer`] move, see [`check_move`]
+fn justify_transfer<'a, C: Coins>(
+    game: &'a NimGame<C>,
+    TransferAction {
+        stack_index,
+        target_index,
+        amount,
+        ..
+    }: &TransferAction<C>,
+) -> Result<&'a NimRule, MoveError<C>> {
+    let stack = get_stack(game, *stack_index)?;
+    get_stack(game, *target_index)?;
+
+    let rule = game
+        .rules
+        .rules_for(game.to_move)
+        .iter()
+        .find(|rule| match rule.take {
+            TakeSize::TransferToNeighbour => *target_index + 1 == *stack_index,
+            TakeSize::TransferToLower => target_index < stack_index,
+            _ => false,
+        })
+        .ok_or(MoveError::NoSuchTransferRule {
+            stack_index: *stack_index,
+            target_index: *target_index,
+        })?;
+
+    if amount.is_zero() {
+        return Err(MoveError::NoSuchRule {
+            amount: amount.clone(),
+        });
+    }
+
+    if stack.0 < *amount {
+        return Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: *stack_index,
+            amount: amount.clone(),
+            available: stack.0.clone(),
+        });
+    }
+
+    Ok(rule)
+}
+
+/// Get a stack of a game, or a [`MoveError::NoSuchStack`] error if it doesn't exist
+fn get_stack<C>(game: &NimGame<C>, stack_index: usize) -> Result<&Stack<C>, MoveError<C>> {
+    game.stacks.get(stack_index).ok_or(MoveError::NoSuchStack {
+        stack_index,
+        stack_count: game.stacks.len(),
+    })
+}
+
 /// Applies a move to a position, if the move is valid
 ///
 /// The validity of the move is checked with [`check_move`] before applying it.
@@ -265,38 +962,548 @@ fn apply_move_(game: &mut NimGame, mov: &NimAction, unchecked: bool) -> Result<(
 /// # Errors
 ///
 /// This function returns
-/// [`Ok`] with the unit type if the move is valid and was applied successfully,
+/// [`Ok`] with the [`StackChanges`] if the move is valid and was applied successfully,
 /// an [`Err`] with the reason why the move is invalid otherwise (see [`MoveError`])
-pub fn apply_move(game: &mut NimGame, mov: &NimAction) -> Result<(), MoveError> {
-    apply_move_(game, mov, false)
+pub fn apply_move<C: Coins>(
+    game: &mut NimGame<C>,
+    mov: &NimAction<C>,
+) -> Result<StackChanges<C>, MoveError<C>> {
+    let mov = check_move(game, mov)?;
+    apply_validated_move(game, mov)
 }
 
-/// Applies a move to a position, even if the move is invalid
+/// Applies a move which was already checked with [`check_move`], without checking it again
 ///
 /// # Arguments
 ///
 /// - `game` - The game state before the move is applied
-/// - `mov` - The move to apply
+/// - `mov` - The move to apply, as returned by [`check_move`]
 ///
-/// # Returns
+/// # Examples
 ///
-/// [`Ok`] with the unit type if the move is valid and was applied successfully,
-/// an [`Err`] otherwise, usually [`MoveError::NoSuchStack`] (see [`MoveError`])
+/// ```
+/// use nimlib::{moves, NimGame, Stack};
+///
+/// let mut game = NimGame::default();
 ///
-/// # Safety
+/// let moves = game.calculate_legal_moves();
+/// let mov = moves::check_move(&game, &moves[0]).unwrap();
 ///
-/// While this function does not perform _traditionally_ unsafe operations,
-/// applying moves without checking them for validity can lead to unexpected
-/// behaviour. This function is therefore marked as unsafe;
-/// but this is possibly subject to change.
+/// moves::apply_validated_move(&mut game, mov).unwrap();
 ///
-/// Please note that the bounds checks of the [`Vec`] indices are not disabled by this function.
+/// assert_eq!(game.get_stacks(), &vec![Stack(9)]);
+/// ```
 ///
 /// # Errors
 ///
-/// If the move is invalid. See [returns](#returns) above.
-pub unsafe fn apply_move_unchecked(game: &mut NimGame, mov: &NimAction) -> Result<(), MoveError> {
-    apply_move_(game, mov, true)
+/// Returns [`MoveError::StaleMove`] if the move was checked for a different position
+/// (i.e. the rules, the player whose turn it is, the heights of the stacks, or the pool of the player differ),
+/// or an error if the resulting number of coins on the stack or in a pool would overflow
+/// (or a stack would be higher than [`u64::MAX`] while the game keeps track of its nimber, see [`MoveError::StackTooHigh`]).
+/// The game is not changed if an error is returned.
+pub fn apply_validated_move<C: Coins>(
+    game: &mut NimGame<C>,
+    mov: ValidatedMove<C>,
+) -> Result<StackChanges<C>, MoveError<C>> {
+    if !mov.is_valid_for(game) {
+        return Err(MoveError::StaleMove);
+    }
+
+    let taken = largest_take(&mov.action);
+    let MoveResult { replacements, pool } = move_result(game, mov.action)?;
+
+    // The nimbers of the changed stacks are looked up, which needs them to fit into a u64
+    if game.nimbers.is_some() {
+        if let Some((stack_index, _)) = replacements
+            .iter()
+            .find(|(_, stacks)| stacks.iter().any(|stack| stack.to_u64().is_none()))
+        {
+            return Err(MoveError::StackTooHigh {
+                stack_index: *stack_index,
+            });
+        }
+    }
+
+    // Only change the game once the move can't fail anymore
+    let mut changes = StackChanges::default();
+    let mut undo = Undo {
+        mover: game.to_move,
+        pools: game.pools.clone(),
+        last_take: game.last_take.clone(),
+        next_id: game.stack_ids.as_ref().map(|ids| ids.next),
+        splices: Vec::new(),
+        state: 0,
+    };
+
+    // Replace the stacks from the back, so the indices of the other stacks stay valid
+    for (stack_index, stacks) in replacements.iter().rev() {
+        // A changed stack keeps its ID, while a split stack is replaced by new stacks
+        let id = match stacks.len() {
+            1 => game.stack_ids.as_ref().map(|ids| ids.ids[*stack_index]),
+            _ => None,
+        };
+        let entries: Vec<StackEntry<C>> = stacks
+            .iter()
+            .map(|stack| StackEntry {
+                stack: stack.clone(),
+                id,
+                nimber: None,
+            })
+            .collect();
+        let replaced = game.splice_stacks(*stack_index, 1, &entries);
+
+        if stacks.len() > 1 {
+            if let Some(ids) = game.get_stack_ids() {
+                changes
+                    .removed
+                    .extend(replaced.iter().filter_map(|entry| entry.id));
+                changes
+                    .created
+                    .extend_from_slice(&ids[*stack_index..*stack_index + stacks.len()]);
+            }
+        }
+        undo.splices.push(Splice {
+            index: *stack_index,
+            inserted: stacks.len(),
+            removed: replaced,
+        });
+    }
+
+    // Drop the emptied stacks, and move the changed stacks into place
+    if game.auto_normalize {
+        // The stacks replacing a stack shift the stacks after it
+        let mut indices = Vec::new();
+        let mut shift = 0;
+        for (stack_index, stacks) in &replacements {
+            let start = stack_index + shift;
+            indices.extend(start..start + stacks.len());
+            shift += stacks.len() - 1;
+        }
+        normalize_changed_stacks(game, &indices, &mut changes, &mut undo);
+    }
+
+    if let Some((player, pool)) = pool {
+        set_pool_coins(game, player, pool);
+    }
+
+    // Rules like `TakeSize::UpToMultipleOfLast` depend on the coins taken by this move
+    if let Some(taken) = taken.filter(|_| game.rules.depends_on_last_take()) {
+        game.last_take = Some(taken);
+    }
+
+    // It's the next player's turn now
+    game.to_move = game.next_player(game.to_move);
+
+    undo.state = game.state_fingerprint();
+    changes.undo = Some(undo);
+
+    Ok(changes)
+}
+
+/// Get the largest number of coins a move takes from a single stack, or [`None`] if it doesn't take coins
+fn largest_take<C: Coins>(mov: &NimAction<C>) -> Option<C> {
+    match mov {
+        NimAction::Take(take) => Some(take.amount.clone()),
+        NimAction::Place(_) | NimAction::Transfer(_) => None,
+        NimAction::TakeMany(take_many) => {
+            take_many.takes.iter().map(|take| take.amount.clone()).max()
+        }
+        NimAction::Compound(compound) => {
+            compound.takes.iter().map(|take| take.amount.clone()).max()
+        }
+    }
+}
+
+/// The result of a move, see [`move_result`]
+struct MoveResult<C> {
+    /// The indices of the stacks the move refers to (in ascending order),
+    /// and the stacks replacing each of them
+    replacements: Vec<(usize, Vec<Stack<C>>)>,
+
+    /// The player whose pool changes, and their new pool
+    pool: Option<(Player, C)>,
+}
+
+/// Find the stacks replacing the stacks a move refers to, and the new pool of the player (if any)
+fn move_result<C: Coins>(
+    game: &NimGame<C>,
+    action: NimAction<C>,
+) -> Result<MoveResult<C>, MoveError<C>> {
+    match action {
+        NimAction::Take(TakeAction {
+            stack_index,
+            amount,
+            split,
+            from,
+            ..
+        }) => {
+            // Take coins from the stack
+            let remainder = remaining_coins(game, stack_index, &amount)?;
+
+            // Add the coins to the player's pool
+            let pool = added_to_pool(game, from, &amount)?;
+
+            let stacks = match split {
+                NimSplit::Yes(a, b) => vec![a, b],
+                NimSplit::No => vec![Stack(remainder)],
+            };
+
+            Ok(MoveResult {
+                replacements: vec![(stack_index, stacks)],
+                pool,
+            })
+        }
+        NimAction::Place(PlaceAction {
+            stack_index,
+            amount,
+            from,
+            ..
+        }) => {
+            let stack = &game.stacks[stack_index];
+            let available = pool_coins(game, from);
+
+            // Place coins onto the stack, taking them from the player's pool
+            let height = stack
+                .0
+                .try_add(&amount)
+                .ok_or(MoveError::StackOverflow { stack_index })?;
+            let Some(pool) = available.try_sub(&amount) else {
+                return Err(MoveError::NotEnoughCoinsOnPlayer {
+                    player: from,
+                    amount,
+                    available,
+                });
+            };
+
+            Ok(MoveResult {
+                replacements: vec![(stack_index, vec![Stack(height)])],
+                pool: Some((from, pool)),
+            })
+        }
+        NimAction::TakeMany(TakeManyAction { takes, from }) => {
+            let mut replacements = Vec::with_capacity(takes.len());
+            let mut taken = C::from(0);
+
+            for StackTake {
+                stack_index,
+                amount,
+                ..
+            } in takes
+            {
+                // Take coins from the stack
+                let remainder = remaining_coins(game, stack_index, &amount)?;
+
+                replacements.push((stack_index, vec![Stack(remainder)]));
+                if let Some(player) = from {
+                    taken = taken
+                        .try_add(&amount)
+                        .ok_or(MoveError::PoolOverflow { player })?;
+                }
+            }
+
+            replacements.sort_unstable_by_key(|(stack_index, _)| *stack_index);
+
+            // Add the coins to the player's pool
+            let pool = added_to_pool(game, from, &taken)?;
+
+            Ok(MoveResult { replacements, pool })
+        }
+        NimAction::Transfer(TransferAction {
+            stack_index,
+            target_index,
+            amount,
+            ..
+        }) => {
+            // Move the coins from one stack onto the other
+            let remainder = remaining_coins(game, stack_index, &amount)?;
+            let height =
+                game.stacks[target_index]
+                    .0
+                    .try_add(&amount)
+                    .ok_or(MoveError::StackOverflow {
+                        stack_index: target_index,
+                    })?;
+
+            let mut replacements = vec![
+                (stack_index, vec![Stack(remainder)]),
+                (target_index, vec![Stack(height)]),
+            ];
+            replacements.sort_unstable_by_key(|(stack_index, _)| *stack_index);
+
+            Ok(MoveResult {
+                replacements,
+                pool: None,
+            })
+        }
+        NimAction::Compound(CompoundAction { takes }) => compound_result(game, takes),
+    }
+}
+
+/// Find the stacks replacing the stacks of a [`NimAction::Compound`] move, and the new pool of the player (if any)
+fn compound_result<C: Coins>(
+    game: &NimGame<C>,
+    takes: Vec<TakeAction<C>>,
+) -> Result<MoveResult<C>, MoveError<C>> {
+    let from = takes.iter().find_map(|take| take.from);
+    let mut replacements = Vec::with_capacity(takes.len());
+    let mut total = C::from(0);
+
+    for TakeAction {
+        stack_index,
+        amount,
+        split,
+        from: take_from,
+        ..
+    } in takes
+    {
+        // Take coins from the stack
+        let remainder = remaining_coins(game, stack_index, &amount)?;
+
+        let stacks = match split {
+            NimSplit::Yes(a, b) => vec![a, b],
+            NimSplit::No => vec![Stack(remainder)],
+        };
+        replacements.push((stack_index, stacks));
+
+        if let Some(player) = take_from {
+            total = total
+                .try_add(&amount)
+                .ok_or(MoveError::PoolOverflow { player })?;
+        }
+    }
+
+    replacements.sort_unstable_by_key(|(stack_index, _)| *stack_index);
+
+    // Add the coins to the player's pool
+    let pool = added_to_pool(game, from, &total)?;
+
+    Ok(MoveResult { replacements, pool })
+}
+
+/// Get the new pool of the player who takes coins into their pool, if any
+fn added_to_pool<C: Coins>(
+    game: &NimGame<C>,
+    from: Option<Player>,
+    amount: &C,
+) -> Result<Option<(Player, C)>, MoveError<C>> {
+    from.map(|player| {
+        pool_coins(game, player)
+            .try_add(amount)
+            .map(|pool| (player, pool))
+            .ok_or(MoveError::PoolOverflow { player })
+    })
+    .transpose()
+}
+
+/// Get the coins left on a stack after taking some of them
+fn remaining_coins<C: Coins>(
+    game: &NimGame<C>,
+    stack_index: usize,
+    amount: &C,
+) -> Result<C, MoveError<C>> {
+    let stack = &game.stacks[stack_index];
+    stack
+        .0
+        .try_sub(amount)
+        .ok_or_else(|| MoveError::NotEnoughCoinsOnStack {
+            stack_index,
+            amount: amount.clone(),
+            available: stack.0.clone(),
+        })
+}
+
+/// Normalize the stacks of a game after a move changed the stacks at the given (ascending) indices
+///
+/// The other stacks are normalized already, so the changed stacks are moved into place,
+/// or dropped if they are empty.  
+/// If the order of the stacks matters, only the empty stacks at the end are dropped instead.
+fn normalize_changed_stacks<C: Coins>(
+    game: &mut NimGame<C>,
+    indices: &[usize],
+    changes: &mut StackChanges<C>,
+    undo: &mut Undo<C>,
+) {
+    if game.rules.depends_on_stack_order() {
+        while game.stacks.last().is_some_and(|stack| stack.0.is_zero()) {
+            let index = game.stacks.len() - 1;
+            let removed = game.splice_stacks(index, 1, &[]);
+            changes
+                .removed
+                .extend(removed.iter().filter_map(|entry| entry.id));
+            undo.splices.push(Splice {
+                index,
+                inserted: 0,
+                removed,
+            });
+        }
+        return;
+    }
+
+    // Remove the changed stacks from the back, so the indices of the other stacks stay valid
+    let mut entries = Vec::with_capacity(indices.len());
+    for &index in indices.iter().rev() {
+        let removed = game.splice_stacks(index, 1, &[]);
+        entries.extend(removed.iter().cloned());
+        undo.splices.push(Splice {
+            index,
+            inserted: 0,
+            removed,
+        });
+    }
+    entries.reverse();
+
+    for entry in entries {
+        if entry.stack.0.is_zero() {
+            changes.removed.extend(entry.id);
+            continue;
+        }
+
+        let index = game.stacks.partition_point(|stack| *stack <= entry.stack);
+        game.splice_stacks(index, 0, &[entry]);
+        undo.splices.push(Splice {
+            index,
+            inserted: 1,
+            removed: Vec::new(),
+        });
+    }
+}
+
+/// Reverts a move, given the [`StackChanges`] returned when it was applied
+///
+/// Undoing moves in the reverse order in which they were applied restores the previous positions exactly,
+/// including the stack IDs and the nimbers kept by the game.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{moves, NimGame};
+///
+/// let mut game = NimGame::default();
+/// let before = game.clone();
+///
+/// let mov = game.calculate_legal_moves()[0].clone();
+/// let changes = moves::apply_move(&mut game, &mov).unwrap();
+/// moves::undo_move(&mut game, changes).unwrap();
+///
+/// assert_eq!(game, before);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`MoveError::StaleMove`] if the changes were not returned by applying a move,
+/// or if the game changed since the move was applied
+/// (e.g. by applying another move, or by replacing the stacks or the rules),
+/// so only the last move can be undone.
+/// Returns [`MoveError::StackTooHigh`] if the game started keeping track of its nimber after the move,
+/// and the move removed a stack higher than [`u64::MAX`].
+/// The game is not changed if an error is returned.
+pub fn undo_move<C: Coins>(
+    game: &mut NimGame<C>,
+    changes: StackChanges<C>,
+) -> Result<(), MoveError<C>> {
+    let Some(undo) = changes.undo else {
+        return Err(MoveError::StaleMove);
+    };
+
+    if undo.state != game.state_fingerprint() {
+        return Err(MoveError::StaleMove);
+    }
+
+    // Restored stacks only lack their nimbers if the game started keeping track of them after the move
+    if game.nimbers.is_some() {
+        if let Some(splice) = undo.splices.iter().find(|splice| {
+            splice
+                .removed
+                .iter()
+                .any(|entry| entry.nimber.is_none() && entry.stack.to_u64().is_none())
+        }) {
+            return Err(MoveError::StackTooHigh {
+                stack_index: splice.index,
+            });
+        }
+    }
+
+    for splice in undo.splices.into_iter().rev() {
+        game.splice_stacks(splice.index, splice.inserted, &splice.removed);
+    }
+
+    if let (Some(ids), Some(next)) = (&mut game.stack_ids, undo.next_id) {
+        ids.next = next;
+    }
+    game.pools = undo.pools;
+    game.last_take = undo.last_take;
+    game.to_move = undo.mover;
+
+    Ok(())
+}
+
+/// How to revert an applied move, see [`undo_move`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Undo<C> {
+    /// The player who made the move
+    mover: Player,
+
+    /// The pools of the players before the move
+    pools: Vec<C>,
+
+    /// The coins taken by the previous move, before the move
+    last_take: Option<C>,
+
+    /// The ID given to the next new stack before the move (if the game has stack IDs)
+    next_id: Option<u64>,
+
+    /// The changes of the stacks, in the order they were made
+    splices: Vec<Splice<C>>,
+
+    /// The fingerprint of the game after the move (see [`NimGame::state_fingerprint`]),
+    /// which the game still has to have when the move is undone
+    state: u64,
+}
+
+/// A replacement of stacks, see [`NimGame::splice_stacks`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Splice<C> {
+    /// The index of the first replaced stack
+    index: usize,
+
+    /// The number of stacks which replaced the removed stacks
+    inserted: usize,
+
+    /// The replaced stacks
+    removed: Vec<StackEntry<C>>,
+}
+
+/// The stacks removed and created by a move, identified by their [`StackId`]s
+///
+/// A split changes the stacks: the split stack is removed, and the two resulting stacks are created.  
+/// Stacks which were emptied are removed as well, if the game normalizes its stacks (see [`NimGame::normalize`]).  
+/// Both lists are empty if the game has no stack IDs (see [`NimGame::set_stack_ids`]).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StackChanges<C = u64> {
+    /// The IDs of the stacks which were removed
+    pub removed: Vec<StackId>,
+
+    /// The IDs of the stacks which were created
+    pub created: Vec<StackId>,
+
+    /// How to revert the move (see [`undo_move`])
+    #[serde(skip)]
+    pub(crate) undo: Option<Undo<C>>,
+}
+
+impl<C> Default for StackChanges<C> {
+    fn default() -> Self {
+        Self {
+            removed: Vec::new(),
+            created: Vec::new(),
+            undo: None,
+        }
+    }
+}
+
+/// Set the number of coins in the pool of a player (who takes part in the game)
+fn set_pool_coins<C>(game: &mut NimGame<C>, player: Player, coins: C) {
+    game.pools[player.0] = coins;
 }
 
 /// Generate all possible (legal) moves for a given position
@@ -305,7 +1512,21 @@ pub unsafe fn apply_move_unchecked(game: &mut NimGame, mov: &NimAction) -> Resul
 ///
 /// - `stacks` - The stacks of coins in the position
 /// - `rules` - The rules of the game (see [`NimRule`])
-/// - `pool_coins` is currently not fully implemented.
+/// - `pool_coins` - The coins in the pools of the players, in the order of the players
+///   (players without a pool have no coins)
+/// - `to_move` - The player whose turn it is
+/// - `last_take` - The largest number of coins taken from a stack by the previous move,
+///   or [`None`] before the first move (see [`NimGame::get_last_take`])
+///
+/// Only the pool of the player whose turn it is is used for [`NimAction::Place`] moves.  
+/// [`TakeSize::AnyFromStacks`] rules generate [`NimAction::Take`] moves for single stacks,
+/// and [`NimAction::TakeMany`] moves for several stacks (after the moves of all other rules),
+/// just like [`TakeSize::SameFromTwoStacks`] rules for pairs of stacks.
+/// [`TakeSize::UpToMultipleOfLast`] rules take up to the limit set by `last_take` (see [`take_limit`]).  
+/// [`TakeSize::TransferToNeighbour`] and [`TakeSize::TransferToLower`] rules generate [`NimAction::Transfer`] moves
+/// (after the moves of all other rules).
+/// If the rules allow placing coins, the [`TakeAction::from`] field of the take moves
+/// is set to the player whose turn it is (the taken coins go to their pool), otherwise it is [`None`].
 ///
 /// # Returns
 ///
@@ -319,7 +1540,7 @@ pub unsafe fn apply_move_unchecked(game: &mut NimGame, mov: &NimAction) -> Resul
 /// # Example
 ///
 /// ```
-/// use nimlib::{moves, NimAction, NimRule, NimSplit, Split, Stack, TakeSize};
+/// use nimlib::{moves, NimAction, NimRule, NimSplit, Player, Split, Stack, TakeSize};
 ///
 /// let rules = vec![NimRule {
 ///     take: TakeSize::List(vec![1, 2, 3]),
@@ -328,7 +1549,8 @@ pub unsafe fn apply_move_unchecked(game: &mut NimGame, mov: &NimAction) -> Resul
 ///
 /// let stacks = vec![Stack(10)];
 ///
-/// let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0))
+/// let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None)
+///     .unwrap()
 ///     .into_iter()
 ///     .map(|mov| {
 ///         if let NimAction::Take(take) = mov {
@@ -354,166 +1576,285 @@ pub unsafe fn apply_move_unchecked(game: &mut NimGame, mov: &NimAction) -> Resul
 /// assert_eq!(moves[2].split, NimSplit::No);
 /// ```
 ///
-/// # Panics    
+/// # Errors
 ///
-/// If a rule with [`NimAction::Place`] is encountered which also has
-/// [`Split::Optional`] or [`Split::Always`], this function will panic.
+/// Returns a [`RuleSetError`] if the rules are invalid (see [`RuleSet::new`]),
+/// e.g. if a [`TakeSize::Place`] rule allows splitting the stack.
 ///
-#[must_use]
+/// This function counts coins with [u64]s;
+/// the legal moves of games with other types of [`Coins`] are generated by [`NimGame::calculate_legal_moves`].
 pub fn calculate_legal_moves(
     stacks: &[Stack],
     rules: &[NimRule],
-    (pool_coins_a, pool_coins_b): (u64, u64),
-) -> Vec<NimAction> {
+    pool_coins: &[u64],
+    to_move: Player,
+    last_take: Option<u64>,
+) -> Result<Vec<NimAction>, RuleSetError> {
+    // Reject invalid rules up front; the moves are generated in the order of the given rules
+    RuleSet::new(rules.to_vec())?;
+
+    Ok(legal_moves(
+        stacks,
+        rules,
+        pool_coins,
+        to_move,
+        last_take.as_ref(),
+    ))
+}
+
+/// Generate all legal moves for a given position with valid rules, see [`calculate_legal_moves`]
+///
+/// Unlike [`calculate_legal_moves`], this works with any type of [`Coins`] (see [`NimGame::calculate_legal_moves`]).
+pub(crate) fn legal_moves<C: Coins>(
+    stacks: &[Stack<C>],
+    rules: &[NimRule],
+    pools: &[C],
+    to_move: Player,
+    last_take: Option<&C>,
+) -> Vec<NimAction<C>> {
+    // When playing with pools, the taken coins go to the pool of the player whose turn it is
+    let from = uses_pools(rules).then_some(to_move);
+    let no_coins = C::from(0);
+    let pool_coins = pools.get(to_move.0).unwrap_or(&no_coins);
+
     let mut moves = Vec::new();
 
     // Iterate over all stacks
     for (s_idx, stack) in stacks.iter().enumerate() {
         // Iterate over all rules
         for NimRule { take, split } in rules {
-            match take {
-                TakeSize::List(take_sizes) => {
-                    for take_size in take_sizes {
-                        if stack.0 >= *take_size {
-                            match split {
-                                Split::Never => {
-                                    // Without split
-                                    moves.push(NimAction::Take(TakeAction {
-                                        stack_index: s_idx,
-                                        amount: *take_size,
-                                        split: NimSplit::No,
-                                        from: None,
-                                    }));
-                                }
-                                Split::Optional => {
-                                    // Without split
-                                    moves.push(NimAction::Take(TakeAction {
-                                        stack_index: s_idx,
-                                        amount: *take_size,
-                                        split: NimSplit::No,
-                                        from: None,
-                                    }));
-
-                                    // With split
-                                    // Enumerate all possible splits
-                                    for (a, b) in
-                                        calculate_splits(stack.0.saturating_sub(*take_size))
-                                    {
-                                        moves.push(NimAction::Take(TakeAction {
-                                            stack_index: s_idx,
-                                            amount: *take_size,
-                                            split: NimSplit::Yes(a, b),
-                                            from: None,
-                                        }));
-                                    }
-                                }
-                                Split::Always => {
-                                    // With split
-                                    // Enumerate all possible splits
-                                    for (a, b) in
-                                        calculate_splits(stack.0.saturating_sub(*take_size))
-                                    {
-                                        moves.push(NimAction::Take(TakeAction {
-                                            stack_index: s_idx,
-                                            amount: *take_size,
-                                            split: NimSplit::Yes(a, b),
-                                            from: None,
-                                        }));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-
-                TakeSize::Any => {
-                    for h in 1..=stack.0 {
-                        match split {
-                            Split::Never => {
-                                // Without split
-                                moves.push(NimAction::Take(TakeAction {
-                                    stack_index: s_idx,
-                                    amount: h,
-                                    split: NimSplit::No,
-                                    from: None,
-                                }));
-                            }
-                            Split::Optional => {
-                                // Without split
-                                moves.push(NimAction::Take(TakeAction {
-                                    stack_index: s_idx,
-                                    amount: h,
-                                    split: NimSplit::No,
-                                    from: None,
-                                }));
-
-                                // With split
-                                // Enumerate all possible splits
-                                for (a, b) in calculate_splits(stack.0.saturating_sub(h)) {
-                                    moves.push(NimAction::Take(TakeAction {
-                                        stack_index: s_idx,
-                                        amount: h,
-                                        split: NimSplit::Yes(a, b),
-                                        from: None,
-                                    }));
-                                }
-                            }
-                            Split::Always => {
-                                // With split
-                                // Enumerate all possible splits
-                                for (a, b) in calculate_splits(stack.0.saturating_sub(h)) {
-                                    moves.push(NimAction::Take(TakeAction {
-                                        stack_index: s_idx,
-                                        amount: h,
-                                        split: NimSplit::Yes(a, b),
-                                        from: None,
-                                    }));
-                                }
-                            }
-                        }
+            // The numbers of coins which may be taken from the stack
+            let take_sizes: Vec<C> = match take {
+                TakeSize::List(take_sizes) => take_sizes
+                    .iter()
+                    .map(|&take_size| C::from(take_size))
+                    .filter(|take_size| stack.0 >= *take_size)
+                    .collect(),
+                // Taking coins from a single stack, see below for several stacks
+                TakeSize::Any | TakeSize::AnyFromStacks(_) => up_to(&stack.0).collect(),
+                TakeSize::UpToMultipleOfLast(multiple) => {
+                    match take_limit(*multiple, last_take, &stack.0) {
+                        Some(limit) => up_to((&limit).min(&stack.0)).collect(),
+                        None => up_to(&stack.0).collect(),
                     }
                 }
-
+                // Only moves across two stacks, see below
+                TakeSize::SameFromTwoStacks
+                | TakeSize::TransferToNeighbour
+                | TakeSize::TransferToLower => continue,
                 TakeSize::Place => {
                     // The player can add 1..pool_coins coins to the stack
-                    // The placed coins are taken from the pool
-                    // FIXME only the coins of player A can be placed; this must not be hardcoded
-                    for c in 1..=pool_coins_a {
-                        match split {
-                            Split::Never => {
-                                // Without split
-                                moves.push(NimAction::Place(PlaceAction {
-                                    stack_index: s_idx,
-                                    amount: c,
-                                    from: Player::A,
-                                }));
-                            }
-                            Split::Optional | Split::Always => {
-                                // TODO consider replacing this panic with a Result or improve the types themselves
-                                panic!("Split is not allowed with Place")
-                            }
-                        }
+                    // The placed coins are taken from their pool (splitting is not allowed)
+                    for c in up_to(pool_coins) {
+                        moves.push(NimAction::Place(PlaceAction {
+                            stack_index: s_idx,
+                            amount: c,
+                            from: to_move,
+                            stack_id: None,
+                        }));
                     }
-                    for c in 1..=pool_coins_b {
-                        match split {
-                            Split::Never => {
-                                // Without split
-                                moves.push(NimAction::Place(PlaceAction {
-                                    stack_index: s_idx,
-                                    amount: c,
-                                    from: Player::B,
-                                }));
-                            }
-                            Split::Optional | Split::Always => {
-                                // TODO consider replacing this panic with a Result or improve the types themselves
-                                panic!("Split is not allowed with Place")
-                            }
-                        }
+                    continue;
+                }
+            };
+
+            for amount in take_sizes {
+                // Without split (taking nothing without splitting is not a move)
+                if *split != Split::Always && !amount.is_zero() {
+                    moves.push(NimAction::Take(TakeAction {
+                        stack_index: s_idx,
+                        amount: amount.clone(),
+                        split: NimSplit::No,
+                        from,
+                        stack_id: None,
+                    }));
+                }
+
+                // With split
+                // Enumerate all possible splits
+                if *split != Split::Never {
+                    let remainder = stack.0.try_sub(&amount).unwrap_or_else(|| C::from(0));
+                    for (a, b) in splits(&remainder) {
+                        moves.push(NimAction::Take(TakeAction {
+                            stack_index: s_idx,
+                            amount: amount.clone(),
+                            split: NimSplit::Yes(a, b),
+                            from,
+                            stack_id: None,
+                        }));
                     }
                 }
             }
         }
     }
 
+    // Take coins from several stacks at once
+    for rule in rules {
+        match rule.take {
+            TakeSize::AnyFromStacks(max_stacks) => {
+                take_many_moves(stacks, 0, max_stacks, &mut Vec::new(), from, &mut moves);
+            }
+            TakeSize::SameFromTwoStacks => joint_moves(stacks, from, &mut moves),
+            TakeSize::TransferToNeighbour | TakeSize::TransferToLower => {
+                transfer_moves(stacks, rule.take == TakeSize::TransferToLower, &mut moves);
+            }
+            _ => {}
+        }
+    }
+
     moves
 }
+
+/// Generate all legal moves of a conjunctive or selective sum of the stacks, see [`NimGame::calculate_legal_moves`]
+///
+/// The rules only take coins from single stacks (see [`NimGame::set_compound`]), so pools are not involved.
+pub(crate) fn compound_moves<C: Coins>(
+    stacks: &[Stack<C>],
+    rules: &[NimRule],
+    compound: Compound,
+) -> Vec<NimAction<C>> {
+    // The moves of the stacks which allow a move
+    let stack_moves: Vec<Vec<TakeAction<C>>> = stacks
+        .iter()
+        .enumerate()
+        .map(|(stack_index, stack)| stack_moves(stack_index, stack, rules))
+        .filter(|moves| !moves.is_empty())
+        .collect();
+
+    let mut moves = Vec::new();
+    combine_moves(
+        &stack_moves,
+        compound == Compound::Selective,
+        &mut Vec::new(),
+        &mut moves,
+    );
+    moves
+}
+
+/// Generate the moves in a single stack, as if it was the only stack
+fn stack_moves<C: Coins>(
+    stack_index: usize,
+    stack: &Stack<C>,
+    rules: &[NimRule],
+) -> Vec<TakeAction<C>> {
+    legal_moves(std::slice::from_ref(stack), rules, &[], Player::A, None)
+        .into_iter()
+        .filter_map(|mov| match mov {
+            NimAction::Take(take) => Some(TakeAction {
+                stack_index,
+                ..take
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate the moves which add a move of each of the remaining stacks to `takes`,
+/// or of any of them if stacks may be `skipped`
+fn combine_moves<C: Coins>(
+    stack_moves: &[Vec<TakeAction<C>>],
+    skipped: bool,
+    takes: &mut Vec<TakeAction<C>>,
+    moves: &mut Vec<NimAction<C>>,
+) {
+    let Some((first, rest)) = stack_moves.split_first() else {
+        match takes.as_slice() {
+            [] => {}
+            [take] => moves.push(NimAction::Take(take.clone())),
+            _ => moves.push(NimAction::Compound(CompoundAction {
+                takes: takes.clone(),
+            })),
+        }
+        return;
+    };
+
+    for take in first {
+        takes.push(take.clone());
+        combine_moves(rest, skipped, takes, moves);
+        takes.pop();
+    }
+    if skipped {
+        combine_moves(rest, skipped, takes, moves);
+    }
+}
+
+/// Generate the [`NimAction::Transfer`] moves which move coins from a stack onto the stack right before it,
+/// or onto any stack before it (if `to_lower` is set)
+fn transfer_moves<C: Coins>(stacks: &[Stack<C>], to_lower: bool, moves: &mut Vec<NimAction<C>>) {
+    for (stack_index, stack) in stacks.iter().enumerate().skip(1) {
+        let first_target = if to_lower { 0 } else { stack_index - 1 };
+        for target_index in first_target..stack_index {
+            for amount in up_to(&stack.0) {
+                moves.push(NimAction::Transfer(TransferAction {
+                    stack_index,
+                    target_index,
+                    amount,
+                    stack_id: None,
+                    target_id: None,
+                }));
+            }
+        }
+    }
+}
+
+/// Generate the [`NimAction::TakeMany`] moves which take the same number of coins from two stacks
+fn joint_moves<C: Coins>(stacks: &[Stack<C>], from: Option<Player>, moves: &mut Vec<NimAction<C>>) {
+    for (first, a) in stacks.iter().enumerate() {
+        for (second, b) in stacks.iter().enumerate().skip(first + 1) {
+            for amount in up_to((&a.0).min(&b.0)) {
+                let mut joint = TakeManyAction::joint(first, second, amount);
+                joint.from = from;
+                moves.push(NimAction::TakeMany(joint));
+            }
+        }
+    }
+}
+
+/// Generate the [`NimAction::TakeMany`] moves which take coins from the stacks in `takes`,
+/// and from up to `max_stacks` stacks in total (at least 2), adding stacks from `start` on
+fn take_many_moves<C: Coins>(
+    stacks: &[Stack<C>],
+    start: usize,
+    max_stacks: usize,
+    takes: &mut Vec<StackTake<C>>,
+    from: Option<Player>,
+    moves: &mut Vec<NimAction<C>>,
+) {
+    if takes.len() >= 2 {
+        moves.push(NimAction::TakeMany(TakeManyAction {
+            takes: takes.clone(),
+            from,
+        }));
+    }
+    if takes.len() >= max_stacks {
+        return;
+    }
+
+    for (stack_index, stack) in stacks.iter().enumerate().skip(start) {
+        for amount in up_to(&stack.0) {
+            takes.push(StackTake {
+                stack_index,
+                amount,
+                stack_id: None,
+            });
+            take_many_moves(stacks, stack_index + 1, max_stacks, takes, from, moves);
+            takes.pop();
+        }
+    }
+}
+
+/// Iterate over the numbers of coins `1..=max`
+fn up_to<C: Coins>(max: &C) -> impl Iterator<Item = C> + '_ {
+    std::iter::successors(Some(C::from(1)), |c| c.try_add(&C::from(1)))
+        .take_while(move |c| c <= max)
+}
+
+/// Calculate all possibilities to split `height` coins into two non-empty stacks,
+/// see [`crate::nimbers::calculate_splits`]
+fn splits<C: Coins>(height: &C) -> Vec<(Stack<C>, Stack<C>)> {
+    up_to(height)
+        .map_while(|a| {
+            let b = height.try_sub(&a)?;
+            (a <= b).then(|| (Stack(a), Stack(b)))
+        })
+        .collect()
+}
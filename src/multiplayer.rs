@@ -0,0 +1,369 @@
+//! Games of more than two players, where the player to move doesn't simply win or lose.
+//! A player who can't make the last move may still decide which of the other players makes it,
+//! so the outcome of a position depends on what the players prefer when they can't win.
+//! This module analyses small positions by brute force under two such conventions:
+//! Li's ranking of the players by their last moves (see [`RankingSolver`]),
+//! and coalitions of players playing together against the others (see [`CoalitionSolver`]).
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    moves,
+    solver::{check_searchable, Outcome, SolveError},
+    CanonicalPosition, Coins, NimAction, NimGame, PlayConvention, Player,
+};
+
+/// A brute-force solver ranking the players by their last moves, after S.-Y. R. Li (1978)
+///
+/// The player making the last move ranks first, the player who moved before them second, and so on;
+/// every player tries to rank as high as possible.
+/// So a player who can't make the last move themselves makes it go to the player right before them, if possible.
+/// Under this convention, every position has a unique result, given by the player making the last move
+/// (see [`RankingSolver::last_mover`]).
+/// In games of two players, the player to move makes the last move if and only if they win under normal play.
+///
+/// Like the [`crate::solver::Solver`], the solver remembers the results by the canonical form of the positions.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{multiplayer::RankingSolver, NimGame, NimRule, Player, Split, Stack, TakeSize};
+///
+/// let game = NimGame::builder()
+///     .rules([NimRule {
+///         take: TakeSize::List(vec![1]),
+///         split: Split::Never,
+///     }])
+///     .stacks(vec![Stack(5)])
+///     .players(3)
+///     .build()
+///     .unwrap();
+///
+/// // The players take the coins one by one: A, B, C, A, B
+/// let mut solver = RankingSolver::new();
+/// assert_eq!(solver.last_mover(&game), Ok(Player::B));
+/// assert_eq!(
+///     solver.ranking(&game),
+///     Ok(vec![Player::B, Player::A, Player(2)])
+/// );
+/// ```
+///
+/// [`RankingSolver::new`] creates a solver for games counting coins with [u64]s,
+/// use [`RankingSolver::default`] for other types of [`Coins`].
+#[derive(Clone, Debug)]
+pub struct RankingSolver<C = u64> {
+    /// The number of turns from the player to move to the player making the last move, for the solved positions
+    last_movers: HashMap<CanonicalPosition<C>, usize>,
+}
+
+impl<C> Default for RankingSolver<C> {
+    fn default() -> Self {
+        Self {
+            last_movers: HashMap::new(),
+        }
+    }
+}
+
+impl RankingSolver {
+    /// Create a solver which doesn't know any results yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C: Coins> RankingSolver<C> {
+    /// Get the number of positions whose results are known
+    #[must_use]
+    pub fn solved_positions(&self) -> usize {
+        self.last_movers.len()
+    }
+
+    /// Find the player making the last move, assuming every player plays to rank as high as possible
+    ///
+    /// If the player to move can't move, the player before them counts as having made the last move.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+    /// or [`SolveError::MisereRanking`] if the game is played under the misère play convention.
+    pub fn last_mover(&mut self, game: &NimGame<C>) -> Result<Player, SolveError> {
+        check_searchable(game)?;
+        if game.get_convention() == PlayConvention::Misere {
+            return Err(SolveError::MisereRanking);
+        }
+
+        let player_count = game.get_player_count();
+        let turns = search(
+            game,
+            &mut self.last_movers,
+            NimGame::canonical_position,
+            |game, options| {
+                // The player to move picks the option whose last move comes soonest after their own move
+                // (with their own move being the best and the move of the player before them the worst)
+                let player_count = game.get_player_count();
+                options
+                    .iter()
+                    .map(|turns| (turns + 1) % player_count)
+                    .min()
+                    .unwrap_or(player_count - 1)
+            },
+        );
+
+        Ok(Player((game.get_to_move().0 + turns) % player_count))
+    }
+
+    /// Rank the players by their last moves (see [`RankingSolver::last_mover`]), from the first rank to the last
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+    /// or [`SolveError::MisereRanking`] if the game is played under the misère play convention.
+    pub fn ranking(&mut self, game: &NimGame<C>) -> Result<Vec<Player>, SolveError> {
+        let last_mover = self.last_mover(game)?;
+        let player_count = game.get_player_count();
+
+        Ok((0..player_count)
+            .map(|rank| Player((last_mover.0 + player_count - rank) % player_count))
+            .collect())
+    }
+}
+
+/// A brute-force solver for coalitions of players, which play together against the other players
+///
+/// Under the normal play convention, a coalition wins if one of its players makes the last move;
+/// under misère play, it wins if none of its players makes the last move.
+/// So a coalition and the other players play a game of two sides, of which exactly one can force a win.
+/// The outcome is given for the coalition (see [`CoalitionSolver::solve`]), regardless of whose turn it is.
+///
+/// Like the [`crate::solver::Solver`], the solver remembers the outcomes by the canonical form of the positions
+/// (together with the coalition, relative to the player to move).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{
+///     multiplayer::CoalitionSolver, solver::Outcome, NimGame, NimRule, Player, Split, Stack, TakeSize,
+/// };
+///
+/// let game = NimGame::builder()
+///     .rules([NimRule {
+///         take: TakeSize::List(vec![1, 2]),
+///         split: Split::Never,
+///     }])
+///     .stacks(vec![Stack(4)])
+///     .players(3)
+///     .build()
+///     .unwrap();
+///
+/// let mut solver = CoalitionSolver::new();
+///
+/// // Player A can't make sure to make the last move on their own, as players B and C play against them,
+/// assert_eq!(solver.solve(&game, &[Player::A]), Ok(Outcome::Loss));
+///
+/// // but players A and B can
+/// assert_eq!(solver.solve(&game, &[Player::A, Player::B]), Ok(Outcome::Win));
+/// ```
+///
+/// [`CoalitionSolver::new`] creates a solver for games counting coins with [u64]s,
+/// use [`CoalitionSolver::default`] for other types of [`Coins`].
+#[derive(Clone, Debug)]
+pub struct CoalitionSolver<C = u64> {
+    /// Whether the coalitions win the solved positions,
+    /// with the members of a coalition given in the order of the turns starting with the player to move
+    outcomes: HashMap<(CanonicalPosition<C>, Vec<bool>), bool>,
+}
+
+impl<C> Default for CoalitionSolver<C> {
+    fn default() -> Self {
+        Self {
+            outcomes: HashMap::new(),
+        }
+    }
+}
+
+impl CoalitionSolver {
+    /// Create a solver which doesn't know any outcomes yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C: Coins> CoalitionSolver<C> {
+    /// Get the number of positions whose outcomes are known (for any coalition)
+    #[must_use]
+    pub fn solved_positions(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Find the outcome of a position for a coalition of players
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+    /// or [`SolveError::NoSuchPlayer`] if a player of the coalition doesn't take part in the game.
+    pub fn solve(
+        &mut self,
+        game: &NimGame<C>,
+        coalition: &[Player],
+    ) -> Result<Outcome, SolveError> {
+        check_searchable(game)?;
+
+        let player_count = game.get_player_count();
+        let mut members = vec![false; player_count];
+        for &player in coalition {
+            *members.get_mut(player.0).ok_or(SolveError::NoSuchPlayer {
+                player,
+                player_count,
+            })? = true;
+        }
+
+        let won = search(
+            game,
+            &mut self.outcomes,
+            |game| {
+                let mut members = members.clone();
+                members.rotate_left(game.get_to_move().0);
+                (game.canonical_position(), members)
+            },
+            |game, options| {
+                let mover = game.get_to_move();
+                if options.is_empty() {
+                    // The player before the player to move made the last move
+                    let last_mover = mover.0 + game.get_player_count() - 1;
+                    let member = members[last_mover % game.get_player_count()];
+                    return match game.get_convention() {
+                        PlayConvention::Normal => member,
+                        PlayConvention::Misere => !member,
+                    };
+                }
+
+                if members[mover.0] {
+                    options.iter().any(|&won| won)
+                } else {
+                    options.iter().all(|&won| won)
+                }
+            },
+        );
+
+        Ok(if won { Outcome::Win } else { Outcome::Loss })
+    }
+
+    /// Find all coalitions which win a position, from the smallest to the largest
+    ///
+    /// A coalition is given by its players in the order of the players.
+    /// With `n` players, there are 2<sup>n</sup> coalitions to solve.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::PlaceRule`] if the rules allow placing coins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the game has more than 127 players.
+    pub fn winning_coalitions(
+        &mut self,
+        game: &NimGame<C>,
+    ) -> Result<Vec<Vec<Player>>, SolveError> {
+        let player_count = game.get_player_count();
+
+        let mut coalitions: Vec<Vec<Player>> = (0..1_u128 << player_count)
+            .map(|members| {
+                (0..player_count)
+                    .filter(|player| members >> player & 1 == 1)
+                    .map(Player)
+                    .collect()
+            })
+            .collect();
+        coalitions.sort_by_key(Vec::len);
+
+        let mut winning = Vec::new();
+        for coalition in coalitions {
+            if self.solve(game, &coalition)? == Outcome::Win {
+                winning.push(coalition);
+            }
+        }
+        Ok(winning)
+    }
+}
+
+/// A position in a [`search`], whose options are being evaluated
+struct Frame<C, V> {
+    /// The position
+    game: NimGame<C>,
+
+    /// The legal moves of the position
+    moves: Vec<NimAction<C>>,
+
+    /// The index of the next move to try
+    next: usize,
+
+    /// The values of the options which were evaluated so far
+    options: Vec<V>,
+}
+
+impl<C: Coins, V> Frame<C, V> {
+    /// Start evaluating the options of a position
+    fn new(game: NimGame<C>) -> Self {
+        Self {
+            moves: game.calculate_legal_moves(),
+            game,
+            next: 0,
+            options: Vec::new(),
+        }
+    }
+}
+
+/// Evaluate a position from the values of its options, remembering the values by the given keys
+///
+/// The search is iterative like the search of [`crate::solver::Solver::solve`],
+/// but evaluates all options of a position.
+///
+/// # Panics
+///
+/// Panics if a generated legal move can't be applied, just like [`crate::solver::Solver::solve`].
+fn search<C: Coins, K: Eq + Hash, V: Clone>(
+    game: &NimGame<C>,
+    values: &mut HashMap<K, V>,
+    key: impl Fn(&NimGame<C>) -> K,
+    value: impl Fn(&NimGame<C>, &[V]) -> V,
+) -> V {
+    let mut root = game.clone();
+    root.set_stack_ids(false);
+    root.nimbers = None;
+    root.set_auto_normalize(true);
+
+    let root_key = key(&root);
+    if let Some(value) = values.get(&root_key) {
+        return value.clone();
+    }
+
+    let mut frames = vec![Frame::new(root)];
+
+    while let Some(frame) = frames.last_mut() {
+        if frame.next < frame.moves.len() {
+            let mut child = frame.game.clone();
+            // A generated move which can't be applied is a bug, which must not prune the search
+            moves::apply_move(&mut child, &frame.moves[frame.next])
+                .expect("Legal moves can be applied");
+            frame.next += 1;
+
+            match values.get(&key(&child)) {
+                Some(value) => frame.options.push(value.clone()),
+                None => frames.push(Frame::new(child)),
+            }
+            continue;
+        }
+
+        let position_value = value(&frame.game, &frame.options);
+        values.insert(key(&frame.game), position_value.clone());
+        frames.pop();
+
+        if let Some(parent) = frames.last_mut() {
+            parent.options.push(position_value);
+        }
+    }
+
+    values[&root_key].clone()
+}
@@ -0,0 +1,223 @@
+//! Arithmetic of nimbers: nim-addition and nim-multiplication.
+//! The nimbers below `2^(2^k)` form a finite field with these operations,
+//! and the [`Nimber`]s (up to `2^64`) are the largest such field which fits into a [u64]
+//! (or a [u128], and any field with a big integer type, see [`crate::Coins`]).
+
+use std::{
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+use crate::{Coins, Nimber};
+
+/// Nim-addition, which is the XOR of the nimbers
+impl<C: Coins> Add for Nimber<C> {
+    type Output = Nimber<C>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Nimber<C>) -> Nimber<C> {
+        self ^ rhs
+    }
+}
+
+impl<C: Coins> AddAssign for Nimber<C> {
+    fn add_assign(&mut self, rhs: Nimber<C>) {
+        self.0 = self.0.xor(&rhs.0);
+    }
+}
+
+/// Nim-subtraction, which is the same as nim-addition (every nimber is its own negative)
+impl<C: Coins> Sub for Nimber<C> {
+    type Output = Nimber<C>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Nimber<C>) -> Nimber<C> {
+        self ^ rhs
+    }
+}
+
+impl<C: Coins> SubAssign for Nimber<C> {
+    fn sub_assign(&mut self, rhs: Nimber<C>) {
+        self.0 = self.0.xor(&rhs.0);
+    }
+}
+
+impl<C: Coins> Sum for Nimber<C> {
+    fn sum<I: Iterator<Item = Nimber<C>>>(iter: I) -> Nimber<C> {
+        iter.fold(Nimber(C::from(0)), Add::add)
+    }
+}
+
+impl<'a, C: Coins> Sum<&'a Nimber<C>> for Nimber<C> {
+    fn sum<I: Iterator<Item = &'a Nimber<C>>>(iter: I) -> Nimber<C> {
+        iter.cloned().sum()
+    }
+}
+
+/// Nim-multiplication, see [`Nimber::pow`] for an example
+impl<C: Coins> Mul for Nimber<C> {
+    type Output = Nimber<C>;
+
+    fn mul(self, rhs: Nimber<C>) -> Nimber<C> {
+        let bits = field_bits(self.0.bit_len().max(rhs.0.bit_len()));
+        Nimber(nim_mul(&self.0, &rhs.0, bits))
+    }
+}
+
+impl<C: Coins> MulAssign for Nimber<C> {
+    fn mul_assign(&mut self, rhs: Nimber<C>) {
+        *self = self.clone() * rhs;
+    }
+}
+
+impl<C: Coins> Product for Nimber<C> {
+    fn product<I: Iterator<Item = Nimber<C>>>(iter: I) -> Nimber<C> {
+        iter.fold(Nimber(C::from(1)), Mul::mul)
+    }
+}
+
+impl<'a, C: Coins> Product<&'a Nimber<C>> for Nimber<C> {
+    fn product<I: Iterator<Item = &'a Nimber<C>>>(iter: I) -> Nimber<C> {
+        iter.cloned().product()
+    }
+}
+
+impl<C: Coins> Nimber<C> {
+    /// Raise the nimber to the power of `exp` using nim-multiplication
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::Nimber;
+    ///
+    /// // *2 * *2 = *3, and *2 * *3 = *1
+    /// assert_eq!(Nimber(2_u64) * Nimber(2), Nimber(3));
+    /// assert_eq!(Nimber(2_u64).pow(3), Nimber(1));
+    ///
+    /// // The Fermat 2-power *16 squares to its sesquimultiple *24
+    /// assert_eq!(Nimber(16_u64).pow(2), Nimber(24));
+    ///
+    /// // And *2^64 squares to *(2^64 + 2^63), which needs more than a u64
+    /// assert_eq!(Nimber(1_u128 << 64).pow(2), Nimber(3 << 63));
+    /// ```
+    #[must_use]
+    pub fn pow(self, mut exp: u64) -> Nimber<C> {
+        let mut base = self;
+        let mut result = Nimber(C::from(1));
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base.clone();
+            }
+            base *= base.clone();
+            exp >>= 1;
+        }
+
+        result
+    }
+
+    /// Find the multiplicative inverse of the nimber in the nim field
+    ///
+    /// Returns [`None`] for `*0`, which has no inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::Nimber;
+    ///
+    /// let inverse = Nimber(7_u64).inverse().unwrap();
+    /// assert_eq!(Nimber(7) * inverse, Nimber(1));
+    ///
+    /// assert_eq!(Nimber(0_u64).inverse(), None);
+    /// ```
+    #[must_use]
+    pub fn inverse(self) -> Option<Nimber<C>> {
+        if self.0.is_zero() {
+            return None;
+        }
+
+        // In the smallest field containing the nimber (of order 2^n), x^(2^n - 2) = x^(-1),
+        // which is the product of the squares x^2, x^4, ..., x^(2^(n - 1))
+        let mut square = self.clone();
+        let mut inverse = Nimber(C::from(1));
+        for _ in 1..field_bits(self.0.bit_len()) {
+            square *= square.clone();
+            inverse *= square.clone();
+        }
+
+        Some(inverse)
+    }
+
+    /// Find the square root of the nimber in the nim field
+    ///
+    /// Every nimber has exactly one square root, as the nim fields have characteristic 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::Nimber;
+    ///
+    /// assert_eq!(Nimber(3_u64).sqrt(), Nimber(2));
+    /// assert_eq!(Nimber(24_u64).sqrt(), Nimber(16));
+    /// ```
+    #[must_use]
+    pub fn sqrt(self) -> Nimber<C> {
+        // In the smallest field containing the nimber (of order 2^n), x = x^(2^n),
+        // so the square root is x^(2^(n - 1)), found by squaring n - 1 times
+        let bits = field_bits(self.0.bit_len());
+        (1..bits).fold(self, |root, _| root.clone() * root)
+    }
+}
+
+/// Find the number of bits `2^k` of the smallest nim field containing the nimbers with `bit_len` bits
+fn field_bits(bit_len: u64) -> u64 {
+    bit_len.max(1).next_power_of_two()
+}
+
+/// Multiply two nimbers below `2^bits` (where `bits` is a power of 2)
+///
+/// With the Fermat 2-power `F = 2^(bits / 2)`, the nimbers are split into `a = a1 F + a0`,
+/// and `F * F = F + F / 2` (nim-multiplication and nim-addition) gives
+/// `a * b = (a1 b1 + a1 b0 + a0 b1) F + a0 b0 + a1 b1 (F / 2)`,
+/// where the middle terms are found with a single multiplication (Karatsuba).
+fn nim_mul<C: Coins>(a: &C, b: &C, bits: u64) -> C {
+    // Nimbers in fields of up to 64 bits are multiplied as u64s
+    if bits <= 64 {
+        if let (Some(a), Some(b)) = (a.to_u64(), b.to_u64()) {
+            return C::from(nim_mul_u64(a, b, bits));
+        }
+    }
+
+    let half = bits / 2;
+    let one = C::from(1);
+    let (a1, a0) = (a.shr_bits(half), a.low_bits(half));
+    let (b1, b0) = (b.shr_bits(half), b.low_bits(half));
+
+    let high = nim_mul(&a1, &b1, half);
+    let low = nim_mul(&a0, &b0, half);
+    let middle = nim_mul(&a1.xor(&a0), &b1.xor(&b0), half).xor(&low);
+
+    let half_fermat = one.shl_bits(half - 1);
+    middle
+        .shl_bits(half)
+        .xor(&low)
+        .xor(&nim_mul(&high, &half_fermat, half))
+}
+
+/// Multiply two nimbers below `2^bits` (where `bits` is a power of 2 up to 64), see [`nim_mul`]
+fn nim_mul_u64(a: u64, b: u64, bits: u64) -> u64 {
+    if a < 2 || b < 2 {
+        return a * b;
+    }
+
+    let half = bits / 2;
+    let mask = (1 << half) - 1;
+    let (a1, a0) = (a >> half, a & mask);
+    let (b1, b0) = (b >> half, b & mask);
+
+    let high = nim_mul_u64(a1, b1, half);
+    let low = nim_mul_u64(a0, b0, half);
+    let middle = nim_mul_u64(a1 ^ a0, b1 ^ b0, half) ^ low;
+
+    (middle << half) ^ low ^ nim_mul_u64(high, 1 << (half - 1), half)
+}
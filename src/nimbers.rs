@@ -5,34 +5,33 @@
 //!
 //! Includes helper functions like [`calculate_splits`].
 
-use std::{collections::HashMap, sync::RwLock};
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
 
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
-use crate::{game::NimRule, moves, NimAction, NimSplit, Nimber, PlaceAction, Stack, TakeAction};
+use crate::{bitset::Bitset, game::NimRule, Nimber, RuleSet, RuleSetError, Split, Stack, TakeSize};
 
-/// The nimber cache is a map from (`height`, `pool_coins`) to nimber.
-///
-/// It is only valid for a specific set of rules.
-///
-/// The pool coins are ignored for now, should always be 0.  
-///
-/// # Arguments
-///
-/// (the elements of the map's key-tuple)
-///
-/// - `height`: The height of the stack
-/// - `pool_coins`: The number of coins in the pool
-///
-/// # Result
-///
-/// (the value of the map)
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
+/// The nimber cache holds the nimbers of all heights calculated so far
+/// for a specific set of rules (see [`NimberTable`]).  
+/// The caches are keyed by the canonical [`RuleSet`], so equivalent rules share a cache.
 ///
-/// - `nimber`: The nimber of the stack given its height and pool coins
-type NimberCache = HashMap<(u64, u64), Nimber>;
+/// Pool coins are not part of the cache, as they are not supported yet.
+type NimberCache = NimberTable;
 
 lazy_static! {
-    static ref NIMBER_CACHE: RwLock<HashMap<Vec<NimRule>, NimberCache>> = Default::default();
+    static ref NIMBER_CACHE: RwLock<HashMap<RuleSet, Arc<Mutex<NimberCache>>>> = RwLock::default();
 }
 
 /// Calculate all possibilities to split a number into two parts,
@@ -89,17 +88,22 @@ pub fn calculate_splits(height: u64) -> Vec<(Stack, Stack)> {
 /// Calls a function with the cache for the given rules.
 ///
 /// If the cache doesn't exist yet, it is created.  
-/// The cache is locked for the duration of the function call.
-fn with_cache<T, F: FnOnce(&mut NimberCache) -> T>(rules: &[NimRule], f: F) -> T {
-    let mut caches = NIMBER_CACHE.write().unwrap();
-    let cache = if let Some(cache) = caches.get_mut(rules) {
-        cache
-    } else {
-        caches.insert(rules.to_vec(), Default::default());
-        caches.get_mut(rules).unwrap()
-    };
+/// The cache is locked for the duration of the function call;
+/// the caches of other rule sets can be used concurrently.
+fn with_cache<T, F: FnOnce(&mut NimberCache) -> T>(rules: &RuleSet, f: F) -> T {
+    let cache = NIMBER_CACHE.read().unwrap().get(rules).cloned();
+    let cache = cache.unwrap_or_else(|| {
+        let table = NimberTable::new(rules);
+        NIMBER_CACHE
+            .write()
+            .unwrap()
+            .entry(rules.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(table)))
+            .clone()
+    });
 
-    f(cache)
+    let mut cache = cache.lock().unwrap();
+    f(&mut cache)
 }
 
 /// Calculate the nimber of a stack of height `height` given a set of rules
@@ -111,71 +115,843 @@ fn with_cache<T, F: FnOnce(&mut NimberCache) -> T>(rules: &[NimRule], f: F) -> T
 /// are stored in an _exclusion list_. The nimber of the original stack is the smallest non-negative
 /// integer that is not in the exclusion list.
 ///
+/// The nimbers of all lower heights are calculated (and cached) along the way,
+/// see [`calculate_nimbers_up_to`] for details on the algorithm.  
+/// So the work grows with the height, and can't be followed or cancelled;
+/// use [`calculate_nimber_for_height_with`] for large heights.
+///
+/// # Errors
+///
+/// Returns [`NimberError::InvalidRules`] if the rules are invalid (see [`RuleSet::new`]),
+/// or [`NimberError::PoolCoinsUnsupported`] if `pool_coins` is not 0.
+pub fn calculate_nimber_for_height(
+    height: u64,
+    rules: &[NimRule],
+    pool_coins: u64,
+) -> Result<Nimber, NimberError> {
+    // TODO handle pool coins correctly
+    if pool_coins != 0 {
+        return Err(NimberError::PoolCoinsUnsupported { pool_coins });
+    }
+
+    Ok(nimber_for_height(height, &RuleSet::new(rules.to_vec())?))
+}
+
+/// Calculate the nimber of a stack of height `height` given a set of rules,
+/// using the specified [`CalculationOptions`]
+///
+/// Like [`calculate_nimber_for_height`], the nimbers of all lower heights are calculated (and cached),
+/// reporting the progress and checking for cancellation just like [`calculate_nimbers_up_to_with`].
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{
+///     nimbers::{calculate_nimber_for_height_with, CalculationOptions, CancellationToken, NimberError},
+///     NimRule, Split, TakeSize,
+/// };
+///
+/// let rules = vec![NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Optional,
+/// }];
+///
+/// let options = CalculationOptions::default();
+/// assert_eq!(calculate_nimber_for_height_with(11, &rules, &options).unwrap().0, 6);
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// let options = CalculationOptions {
+///     cancellation: Some(&token),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     calculate_nimber_for_height_with(1_000_000, &rules, &options),
+///     Err(NimberError::Cancelled)
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns the errors of [`calculate_nimbers_up_to_with`].
+pub fn calculate_nimber_for_height_with(
+    height: u64,
+    rules: &[NimRule],
+    options: &CalculationOptions,
+) -> Result<Nimber, NimberError> {
+    with_cache(&RuleSet::new(rules.to_vec())?, |table| {
+        table.extend_with(height, options)?;
+        Ok(table.nimbers[to_index(height)])
+    })
+}
+
+/// Calculate the nimber of a stack of height `height` given a (validated) rule set
+pub(crate) fn nimber_for_height(height: u64, rules: &RuleSet) -> Nimber {
+    with_cache(rules, |table| {
+        table
+            .extend_to(height, &CalculationOptions::default())
+            .expect("Calculations without a cancellation token can't be cancelled");
+        table.nimbers[to_index(height)]
+    })
+}
+
+/// Calculate the nimbers of all stacks of heights `0..=max_height` given a set of rules
+///
+/// Returns a [`Vec`] where the element at index `h` is the nimber of a stack of height `h`.
+///
+/// The nimbers are calculated bottom-up and cached, so subsequent calls
+/// (and calls to [`calculate_nimber_for_height`]) with the same rules are fast.
+///
+/// The algorithm keeps the options of a stack in a bitset, so the MEX is found in a single pass.  
+/// For rules which split the stack, the nimbers of the splits `(a, r - a)` of a remainder `r`
+/// are collected by grouping the lower heights by their nimber ("buckets"):
+/// for every pair of buckets whose XOR is not known to be an option yet,
+/// only the smaller bucket is searched for a matching split
+/// (unless there are fewer splits than pairs of buckets),
+/// and the evaluation stops once every reachable nimber is found.
+/// The split options of a remainder are evaluated only once,
+/// even if several take sizes lead to the same remainder.
+///
+/// The rules are validated first (see [`RuleSet::new`]);
+/// taking 0 coins is only a move when splitting the stack.  
+/// [`TakeSize::Place`] rules are ignored, as pool coins are not supported yet,
+/// [`TakeSize::AnyFromStacks`] rules only allow taking any number of coins from a single stack,
+/// and [`TakeSize::SameFromTwoStacks`] rules are ignored, as they need two stacks.
+/// [`TakeSize::UpToMultipleOfLast`] rules are ignored as well, as their moves depend on the previous move,
+/// and so are [`TakeSize::TransferToNeighbour`] and [`TakeSize::TransferToLower`] rules, which move coins between stacks.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{nimbers::calculate_nimbers_up_to, NimRule, Split, TakeSize};
+///
+/// // Kayles: take one or two coins, optionally splitting the rest
+/// let kayles = vec![NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Optional,
+/// }];
+///
+/// let nimbers = calculate_nimbers_up_to(11, &kayles).unwrap();
+///
+/// assert_eq!(
+///     nimbers.into_iter().map(|n| n.0).collect::<Vec<_>>(),
+///     vec![0, 1, 2, 3, 1, 4, 3, 2, 1, 4, 2, 6]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns [`NimberError::InvalidRules`] if the rules are invalid (see [`RuleSet::new`]).
+pub fn calculate_nimbers_up_to(
+    max_height: u64,
+    rules: &[NimRule],
+) -> Result<Vec<Nimber>, NimberError> {
+    calculate_nimbers_up_to_with(max_height, rules, &CalculationOptions::default())
+}
+
+/// Calculate the nimbers of all stacks of heights `0..=max_height` given a set of rules,
+/// using the specified [`CalculationOptions`]
+///
+/// The result is the same for every [`Algorithm`], only the time needed to calculate it differs.
+/// The nimbers are cached just like with [`calculate_nimbers_up_to`],
+/// even if the calculation is cancelled (up to the height calculated last).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{
+///     nimbers::{calculate_nimbers_up_to_with, Algorithm, CalculationOptions},
+///     NimRule, Split, TakeSize,
+/// };
+///
+/// // Take one coin, and split the rest of the stack into two non-empty stacks
+/// let rules = vec![NimRule {
+///     take: TakeSize::List(vec![1]),
+///     split: Split::Always,
+/// }];
+///
+/// let options = CalculationOptions {
+///     algorithm: Algorithm::SparseSpace,
+///     progress: Some(&|progress| println!("{}/{}", progress.done, progress.total)),
+///     ..Default::default()
+/// };
+///
+/// let nimbers = calculate_nimbers_up_to_with(10_000, &rules, &options).unwrap();
+///
+/// assert_eq!(nimbers.len(), 10_001);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`NimberError::Cancelled`] if the calculation was cancelled
+/// using the [`CancellationToken`] of the options,
+/// or [`NimberError::InvalidRules`] if the rules are invalid (see [`RuleSet::new`]).
+pub fn calculate_nimbers_up_to_with(
+    max_height: u64,
+    rules: &[NimRule],
+    options: &CalculationOptions,
+) -> Result<Vec<Nimber>, NimberError> {
+    with_cache(&RuleSet::new(rules.to_vec())?, |table| {
+        table.extend_with(max_height, options)?;
+        Ok(table.nimbers[..=to_index(max_height)].to_vec())
+    })
+}
+
+/// Errors which may occur when calculating nimbers
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum NimberError {
+    /// The calculation was cancelled using a [`CancellationToken`]
+    Cancelled,
+
+    /// The rules are invalid (see [`RuleSet::new`])
+    InvalidRules(RuleSetError),
+
+    /// The pool of the player holds coins, which isn't supported by the nimber calculation yet
+    PoolCoinsUnsupported {
+        /// The number of coins in the pool
+        pool_coins: u64,
+    },
+}
+
+impl Display for NimberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NimberError::Cancelled => write!(f, "The calculation was cancelled"),
+            NimberError::InvalidRules(error) => write!(f, "{error}"),
+            NimberError::PoolCoinsUnsupported { pool_coins } => write!(
+                f,
+                "The pool holds {pool_coins} coins, but nimbers can only be calculated without pool coins"
+            ),
+        }
+    }
+}
+
+impl Error for NimberError {}
+
+impl From<RuleSetError> for NimberError {
+    fn from(error: RuleSetError) -> Self {
+        NimberError::InvalidRules(error)
+    }
+}
+
+/// Options for long-running nimber calculations
+///
+/// See [`calculate_nimbers_up_to_with`].
+#[derive(Clone, Copy, Default)]
+pub struct CalculationOptions<'a> {
+    /// The algorithm used to calculate the nimbers
+    pub algorithm: Algorithm,
+
+    /// A callback which is called regularly with the progress of the calculation
+    pub progress: Option<&'a (dyn Fn(Progress) + Sync)>,
+
+    /// A token which cancels the calculation when triggered
+    pub cancellation: Option<&'a CancellationToken>,
+}
+
+impl CalculationOptions<'_> {
+    /// Called before the next height is calculated
+    ///
+    /// Reports the progress every [`PROGRESS_INTERVAL`] heights,
+    /// and checks if the calculation was cancelled.
+    fn step(&self, height: u64, max_height: u64) -> Result<(), NimberError> {
+        if self
+            .cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(NimberError::Cancelled);
+        }
+
+        if height.is_multiple_of(PROGRESS_INTERVAL) {
+            self.report(height, max_height + 1);
+        }
+
+        Ok(())
+    }
+
+    /// Report the progress of the calculation, if a callback was given
+    fn report(&self, done: u64, total: u64) {
+        if let Some(progress) = self.progress {
+            progress(Progress { done, total });
+        }
+    }
+}
+
+/// The progress of a nimber calculation
+///
+/// Reported to the callback of [`CalculationOptions::progress`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Progress {
+    /// The number of steps done, usually the number of heights calculated
+    pub done: u64,
+
+    /// The total number of steps
+    pub total: u64,
+}
+
+/// A token to cancel long-running nimber calculations from another thread
+///
+/// Clones of a token share their state, so one clone can be passed to the calculation
+/// (see [`CalculationOptions::cancellation`]), while another one is used to cancel it.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{
+///     nimbers::{calculate_nimbers_up_to_with, CalculationOptions, CancellationToken, NimberError},
+///     NimRule, Split, TakeSize,
+/// };
+///
+/// let rules = vec![NimRule {
+///     take: TakeSize::Any,
+///     split: Split::Always,
+/// }];
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+///
+/// let options = CalculationOptions {
+///     cancellation: Some(&token),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     calculate_nimbers_up_to_with(1_000_000, &rules, &options),
+///     Err(NimberError::Cancelled)
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token, which is not cancelled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all calculations using this token (or a clone of it)
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if the token was cancelled
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The algorithm used to calculate the nimbers of a table of heights
+///
+/// See [`CalculationOptions`].
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Algorithm {
+    /// Evaluate every option of every height, grouping the heights by their nimbers
+    /// (see [`calculate_nimbers_up_to`])
+    #[default]
+    Bucketed,
+
+    /// Exploit the _sparse space_ phenomenon of games which split stacks
+    ///
+    /// In many games with [`Split::Always`] or [`Split::Optional`] rules (e.g. Grundy's game),
+    /// the nimbers can be divided into _common_ and _rare_ ones, the rare ones being the nimbers
+    /// with an odd number of bits set in some mask; only few heights have a rare nimber.  
+    /// XOR-ing two common or two rare nimbers results in a common nimber,
+    /// XOR-ing a rare and a common nimber results in a rare one.
+    ///
+    /// All options of a height which involve a stack with a rare nimber
+    /// are found by only visiting the few rare heights.
+    /// Common nimbers are searched for among all splits, but are usually found after very few steps,
+    /// so only the nimber of the height itself needs an exhaustive search (if it is common).
+    /// The visited splits are remembered, so every split is visited at most once,
+    /// even if several take sizes lead to the same remainder.
+    ///
+    /// The mask is chosen automatically to minimize the number of rare heights calculated so far,
+    /// and is re-evaluated whenever the table has doubled in size.
+    /// The first heights are calculated using [`Algorithm::Bucketed`] to have a basis for the mask.
+    ///
+    /// Rule sets without [`TakeSize::List`] rules which split the stack,
+    /// or with [`TakeSize::Any`] rules which split the stack, use [`Algorithm::Bucketed`] instead.
+    SparseSpace,
+}
+
+/// The number of heights calculated using [`Algorithm::Bucketed`]
+/// before switching to [`Algorithm::SparseSpace`]
+const SPARSE_SPACE_WARM_UP: u64 = 1000;
+
+/// The maximum number of bits of the masks considered by [`Algorithm::SparseSpace`]
+const SPARSE_SPACE_MASK_BITS: u32 = 12;
+
+/// The number of heights calculated between two progress reports
+const PROGRESS_INTERVAL: u64 = 256;
+
+/// Convert a height into an index into a table of heights (e.g. a [`NimberTable`])
+///
 /// # Panics
 ///
-/// Panics if `rules` match a [`NimAction::Place`] action.
+/// Panics if the height exceeds the addressable memory.
+pub(crate) fn to_index(height: u64) -> usize {
+    usize::try_from(height).expect("Height exceeds the addressable memory")
+}
+
+/// The nimbers of all heights up to some maximum height for a specific set of rules,
+/// along with the bookkeeping needed to extend the table to greater heights.
 ///
-#[must_use]
-pub fn calculate_nimber_for_height(height: u64, rules: &[NimRule], pool_coins: u64) -> Nimber {
-    // Check if we've already calculated this nimber
-    // if let Some(nimber) = get_cache_for_rules!(rules).get(&(height, pool_coins)) {
-    if let Some(nimber) = with_cache(rules, |cache| cache.get(&(height, pool_coins)).copied()) {
-        return nimber;
+/// See [`calculate_nimbers_up_to`] for a description of the algorithm.
+#[derive(Debug)]
+struct NimberTable {
+    /// Take sizes which may be taken without splitting the stack
+    takes: Vec<u64>,
+
+    /// Take sizes after which the remainder of the stack may be split
+    split_takes: Vec<u64>,
+
+    /// If any number of coins may be taken without splitting the stack
+    any_take: bool,
+
+    /// If any number of coins may be taken, splitting the remainder of the stack
+    any_split_take: bool,
+
+    /// The nimbers calculated so far, indexed by height
+    nimbers: Vec<Nimber>,
+
+    /// The heights (greater than 0) grouped by their nimber, in ascending order
+    buckets: Vec<Vec<u64>>,
+
+    /// All nimbers of heights greater than 0
+    present: Bitset,
+
+    /// All nimbers which are the XOR of two nimbers in `present`,
+    /// i.e. all nimbers a split could possibly produce
+    reachable: Bitset,
+
+    /// All nimbers of the heights calculated so far (for [`TakeSize::Any`] without splits)
+    all_nimbers: Bitset,
+
+    /// The split options of all remainders calculated so far (for [`TakeSize::Any`] with splits)
+    all_split_options: Bitset,
+
+    /// The split options of the most recent remainders, the last one being the current height
+    /// (for [`TakeSize::List`] with splits)
+    recent_split_options: VecDeque<Bitset>,
+
+    /// The remainder following the last one in `recent_split_options`
+    ///
+    /// Differs from the number of calculated heights if the table was extended
+    /// using [`Algorithm::SparseSpace`], which doesn't use `recent_split_options`.
+    recent_split_options_end: u64,
+}
+
+impl NimberTable {
+    /// Create an empty table for the given rules
+    fn new(rules: &[NimRule]) -> Self {
+        let mut table = Self {
+            takes: Vec::new(),
+            split_takes: Vec::new(),
+            any_take: false,
+            any_split_take: false,
+            nimbers: Vec::new(),
+            buckets: Vec::new(),
+            present: Bitset::default(),
+            reachable: Bitset::default(),
+            all_nimbers: Bitset::default(),
+            all_split_options: Bitset::default(),
+            recent_split_options: VecDeque::new(),
+            recent_split_options_end: 0,
+        };
+
+        for NimRule { take, split } in rules {
+            let (no_split, with_split) = match split {
+                Split::Never => (true, false),
+                Split::Optional => (true, true),
+                Split::Always => (false, true),
+            };
+
+            match take {
+                TakeSize::List(list) => {
+                    if no_split {
+                        // Taking nothing without splitting is not a move
+                        table.takes.extend(list.iter().filter(|&&t| t != 0));
+                    }
+                    if with_split {
+                        table.split_takes.extend(list);
+                    }
+                }
+                TakeSize::Any => {
+                    table.any_take |= no_split;
+                    table.any_split_take |= with_split;
+                }
+                // TODO handle pool coins; without them, no coins can be placed
+                // A single stack can't be involved in a move across two stacks,
+                // and the nimber of a stack can't depend on the previous move or the other stacks
+                TakeSize::Place
+                | TakeSize::SameFromTwoStacks
+                | TakeSize::UpToMultipleOfLast(_)
+                | TakeSize::TransferToNeighbour
+                | TakeSize::TransferToLower => {}
+                // A single stack can only be involved in the moves across stacks on its own
+                TakeSize::AnyFromStacks(_) => table.any_take = true,
+            }
+        }
+
+        for takes in [&mut table.takes, &mut table.split_takes] {
+            takes.sort_unstable();
+            takes.dedup();
+        }
+
+        table
     }
 
-    // TODO handle pool coins correctly
-    assert_eq!(pool_coins, 0, "Pool coins not yet supported");
-
-    // Use the MEX (minimum excluded) rule to calculate the nimber
-    let mut exclusion_list: Vec<Nimber> = Vec::new();
-
-    // Enumerate all possible moves
-    // Calculate the nimber for each possible move
-    // XOR the nimbers resulting from a split
-    for mov in moves::calculate_legal_moves(&[Stack(height)], rules, (pool_coins, 0)) {
-        match mov {
-            NimAction::Take(TakeAction {
-                stack_index: _,
-                amount: take,
-                split,
-                from: _,
-            }) => match split {
-                NimSplit::Yes(a, b) => {
-                    // TODO check if this handles pool coins correctly
-                    //  note: probably yes, since we'd want to avoid infinite recursion
-                    //  more notes: we're probably missing cases where we could re-distribute coins from one stack to another
-                    let nimber_a = calculate_nimber_for_height(a.0, rules, pool_coins);
-                    let nimber_b = calculate_nimber_for_height(b.0, rules, pool_coins);
-                    exclusion_list.push(nimber_a ^ nimber_b);
+    /// Calculate the nimbers of all heights up to (and including) `max_height`
+    /// using the algorithm of the options
+    fn extend_with(
+        &mut self,
+        max_height: u64,
+        options: &CalculationOptions,
+    ) -> Result<(), NimberError> {
+        match options.algorithm {
+            Algorithm::Bucketed => self.extend_to(max_height, options),
+            Algorithm::SparseSpace => self.extend_sparse_to(max_height, options),
+        }
+    }
+
+    /// Calculate the nimbers of all heights up to (and including) `max_height`
+    fn extend_to(
+        &mut self,
+        max_height: u64,
+        options: &CalculationOptions,
+    ) -> Result<(), NimberError> {
+        self.extend_bucketed(max_height, max_height, options)?;
+        options.report(max_height + 1, max_height + 1);
+        Ok(())
+    }
+
+    /// Calculate the nimbers of all heights up to (and including) `until`
+    /// using [`Algorithm::Bucketed`], as part of a calculation up to `max_height`
+    fn extend_bucketed(
+        &mut self,
+        until: u64,
+        max_height: u64,
+        options: &CalculationOptions,
+    ) -> Result<(), NimberError> {
+        while self.nimbers.len() <= to_index(until) {
+            options.step(self.nimbers.len() as u64, max_height)?;
+            self.push_next();
+        }
+        Ok(())
+    }
+
+    /// Calculate the nimbers of all heights up to (and including) `max_height`
+    /// using [`Algorithm::SparseSpace`]
+    fn extend_sparse_to(
+        &mut self,
+        max_height: u64,
+        options: &CalculationOptions,
+    ) -> Result<(), NimberError> {
+        if self.split_takes.is_empty() || self.any_split_take {
+            return self.extend_to(max_height, options);
+        }
+
+        // Calculate some heights the regular way to have a basis for choosing the mask
+        self.extend_bucketed(max_height.min(SPARSE_SPACE_WARM_UP), max_height, options)?;
+
+        while self.nimbers.len() <= to_index(max_height) {
+            let mask = self.choose_sparse_mask();
+            let is_rare = |nimber: u64| (nimber & mask).count_ones() % 2 == 1;
+
+            let mut rare_heights = self
+                .present
+                .iter()
+                .filter(|&nimber| is_rare(nimber))
+                .flat_map(|nimber| self.buckets[to_index(nimber)].iter().copied())
+                .collect::<Vec<_>>();
+            rare_heights.sort_unstable();
+
+            // The splits of the remainders which may still be needed, scanned so far
+            let mut scans = HashMap::new();
+
+            // Re-evaluate the mask once the table has doubled in size
+            let end = max_height.min(self.nimbers.len() as u64 * 2);
+            while self.nimbers.len() as u64 <= end {
+                let height = self.nimbers.len() as u64;
+                options.step(height, max_height)?;
+
+                let oldest = height.saturating_sub(*self.split_takes.last().unwrap());
+                scans.retain(|&remainder, _| remainder >= oldest);
+
+                let nimber = self.calculate_sparse(&rare_heights, is_rare, &mut scans);
+
+                if height > 0 && is_rare(nimber.0) {
+                    rare_heights.push(height);
                 }
-                NimSplit::No => {
-                    let nimber = calculate_nimber_for_height(height - take, rules, pool_coins);
-                    exclusion_list.push(nimber);
+                self.push(nimber);
+            }
+        }
+
+        options.report(max_height + 1, max_height + 1);
+        Ok(())
+    }
+
+    /// Choose the mask of [`Algorithm::SparseSpace`] which minimizes the number of rare heights
+    fn choose_sparse_mask(&self) -> u64 {
+        let bits = self
+            .present
+            .iter()
+            .max()
+            .map_or(0, |max| u64::BITS - max.leading_zeros());
+
+        (1..1 << bits.min(SPARSE_SPACE_MASK_BITS))
+            .min_by_key(|&mask| {
+                self.present
+                    .iter()
+                    .filter(|nimber| (nimber & mask).count_ones() % 2 == 1)
+                    .map(|nimber| self.buckets[to_index(nimber)].len())
+                    .sum::<usize>()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Calculate the nimber of the next height using [`Algorithm::SparseSpace`]
+    ///
+    /// `rare_heights` are all heights (greater than 0) with a rare nimber, in ascending order.  
+    /// `scans` holds the progress of searching the splits of each remainder,
+    /// so no split is visited twice when searching for common nimbers.
+    fn calculate_sparse(
+        &self,
+        rare_heights: &[u64],
+        is_rare: impl Fn(u64) -> bool,
+        scans: &mut HashMap<u64, SplitScan>,
+    ) -> Nimber {
+        let height = self.nimbers.len() as u64;
+        let nimber = |height: u64| self.nimbers[to_index(height)].0;
+
+        let mut options = Bitset::default();
+
+        for &take in self.takes.iter().take_while(|&&t| t <= height) {
+            options.insert(nimber(height - take));
+        }
+
+        if self.any_take {
+            options.union_with(&self.all_nimbers);
+        }
+
+        let remainders = self
+            .split_takes
+            .iter()
+            .take_while(|&&t| t <= height)
+            .map(|&t| height - t)
+            .collect::<Vec<_>>();
+
+        // Every split involving a rare stack
+        for &remainder in &remainders {
+            for &rare in rare_heights.iter().take_while(|&&h| h < remainder) {
+                options.insert(nimber(rare) ^ nimber(remainder - rare));
+            }
+        }
+
+        loop {
+            let candidate = options.mex();
+
+            // Rare options only result from splits involving a rare stack, which are all known
+            if is_rare(candidate) {
+                return Nimber(candidate);
+            }
+
+            // Search all splits for the common candidate
+            let found = remainders.iter().any(|&remainder| {
+                scans
+                    .entry(remainder)
+                    .or_insert_with(|| SplitScan::new(remainder))
+                    .contains(candidate, &self.nimbers)
+            });
+
+            if !found {
+                return Nimber(candidate);
+            }
+
+            options.insert(candidate);
+        }
+    }
+
+    /// Calculate the nimber of the next height, and update the bookkeeping
+    fn push_next(&mut self) {
+        let height = self.nimbers.len() as u64;
+
+        // The split options of the current height are needed for splits after taking 0 coins
+        if !self.split_takes.is_empty() || self.any_split_take {
+            let window = self.split_takes.last().map_or(1, |&t| to_index(t) + 1);
+
+            if self.recent_split_options_end != height {
+                self.recent_split_options = (height.saturating_sub(window as u64 - 1)..height)
+                    .map(|remainder| self.split_options(remainder))
+                    .collect();
+            }
+
+            let split_options = self.split_options(height);
+
+            if self.any_split_take && height > 0 {
+                let previous = self.recent_split_options.back().unwrap();
+                self.all_split_options.union_with(previous);
+            }
+
+            // Only keep the split options of remainders which may still be needed
+            if self.recent_split_options.len() >= window {
+                self.recent_split_options.pop_front();
+            }
+            self.recent_split_options.push_back(split_options);
+            self.recent_split_options_end = height + 1;
+        }
+
+        let mut options = Bitset::default();
+
+        for &take in self.takes.iter().take_while(|&&t| t <= height) {
+            options.insert(self.nimbers[to_index(height - take)].0);
+        }
+
+        for &take in self.split_takes.iter().take_while(|&&t| t <= height) {
+            let back = self.recent_split_options.len() - 1;
+            options.union_with(&self.recent_split_options[back - to_index(take)]);
+        }
+
+        if self.any_take {
+            options.union_with(&self.all_nimbers);
+        }
+
+        if self.any_split_take {
+            options.union_with(&self.all_split_options);
+        }
+
+        self.push(Nimber(options.mex()));
+    }
+
+    /// Append the nimber of the next height to the table
+    fn push(&mut self, nimber: Nimber) {
+        let height = self.nimbers.len() as u64;
+        self.nimbers.push(nimber);
+        self.all_nimbers.insert(nimber.0);
+
+        if height == 0 {
+            return;
+        }
+
+        if !self.present.contains(nimber.0) {
+            self.present.insert(nimber.0);
+            for other in self.present.iter() {
+                self.reachable.insert(nimber.0 ^ other);
+            }
+        }
+
+        let bucket = to_index(nimber.0);
+        if self.buckets.len() <= bucket {
+            self.buckets.resize_with(bucket + 1, Vec::new);
+        }
+        self.buckets[bucket].push(height);
+    }
+
+    /// Calculate the nimbers of all splits of a stack of height `remainder`
+    /// into two non-empty stacks
+    ///
+    /// For every pair of buckets whose XOR is not yet known to be an option,
+    /// the smaller bucket is searched for a height which completes a split with the other one.  
+    /// If there are more pairs of buckets than splits, the splits are visited directly instead.
+    fn split_options(&self, remainder: u64) -> Bitset {
+        let mut options = Bitset::default();
+
+        let values = self.present.iter().count() as u64;
+
+        #[cfg(feature = "parallel")]
+        if values * (values + 1) > remainder && parallel::should_split_scan(remainder) {
+            return parallel::split_options(&self.nimbers, remainder);
+        }
+
+        if values * (values + 1) > remainder {
+            for a in 1..=remainder / 2 {
+                options
+                    .insert(self.nimbers[to_index(a)].0 ^ self.nimbers[to_index(remainder - a)].0);
+
+                if a % 64 == 0 && options.is_superset(&self.reachable) {
+                    break;
+                }
+            }
+
+            return options;
+        }
+
+        // Visit small buckets first, they are cheap to search
+        let mut order = self.present.iter().collect::<Vec<_>>();
+        order.sort_by_key(|&nimber| self.buckets[to_index(nimber)].len());
+
+        for (i, &small) in order.iter().enumerate() {
+            if options.is_superset(&self.reachable) {
+                break;
+            }
+
+            for &large in &order[i..] {
+                if options.contains(small ^ large) {
+                    continue;
+                }
+
+                let found = self.buckets[to_index(small)]
+                    .iter()
+                    .take_while(|&&h| h < remainder)
+                    .any(|&h| self.nimbers[to_index(remainder - h)].0 == large);
+
+                if found {
+                    options.insert(small ^ large);
                 }
-            },
-            NimAction::Place(PlaceAction {
-                stack_index,
-                amount,
-                from: _,
-            }) => {
-                // We set the `pool_coins` to 0, since we don't want to get into an infinite loop
-                // TODO check if that's correct
-                let nimber = calculate_nimber_for_height(height + amount, rules, 0);
-                exclusion_list.push(nimber);
             }
         }
+
+        options
     }
+}
+
+/// The progress of visiting the splits of a stack, used by [`Algorithm::SparseSpace`]
+#[derive(Debug)]
+struct SplitScan {
+    /// The height of the stack to split
+    remainder: u64,
+
+    /// The height of the smaller part of the next split to visit
+    next: u64,
+
+    /// The nimbers of the splits visited so far
+    found: Bitset,
+}
 
-    // Calculate the nimber using the MEX rule
-    let mut nimber = Nimber(0);
-    while exclusion_list.contains(&nimber) {
-        nimber.0 += 1;
+impl SplitScan {
+    /// Start visiting the splits of a stack of height `remainder`
+    fn new(remainder: u64) -> Self {
+        Self {
+            remainder,
+            next: 1,
+            found: Bitset::default(),
+        }
     }
 
-    // // Cache the nimber
-    with_cache(rules, |cache| cache.insert((height, pool_coins), nimber));
+    /// Check if a split of the stack has the given nimber,
+    /// visiting further splits until one is found
+    fn contains(&mut self, nimber: u64, nimbers: &[Nimber]) -> bool {
+        if self.found.contains(nimber) {
+            return true;
+        }
+
+        while self.next <= self.remainder / 2 {
+            let split =
+                nimbers[to_index(self.next)].0 ^ nimbers[to_index(self.remainder - self.next)].0;
+            self.found.insert(split);
+            self.next += 1;
 
-    nimber
+            if split == nimber {
+                return true;
+            }
+        }
+
+        false
+    }
 }
 
 // #[cfg(test)]
@@ -0,0 +1,210 @@
+//! Parallel nimber calculations (requires the `parallel` feature)
+//!
+//! Tables of different rule sets are calculated concurrently,
+//! and the splits of large stacks are visited by several threads at once.
+//!
+//! Every function takes the number of threads to use (`jobs`);
+//! [`None`] uses one thread per CPU core.  
+//! The results are cached just like the results of the sequential functions in [`crate::nimbers`].
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rayon::prelude::*;
+
+use super::{CalculationOptions, NimberError};
+use crate::{bitset::Bitset, NimRule, Nimber, RuleSet, Stack};
+
+/// The minimal height of a stack whose splits are visited in parallel
+const PARALLEL_SCAN_THRESHOLD: u64 = 1 << 14;
+
+/// Calculate the nimbers of all heights `0..=max_height` for each of the given rule sets
+///
+/// The tables of the rule sets are calculated concurrently.
+/// The returned tables are in the same order as the rule sets.
+///
+/// The progress is reported as the number of tables calculated,
+/// the cancellation token of the `options` cancels the calculation of all tables.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{nimbers::parallel::calculate_nimber_tables, NimRule, Nimber, Split, TakeSize};
+///
+/// let rule_sets = (1..=4)
+///     .map(|max| {
+///         vec![NimRule {
+///             take: TakeSize::List((1..=max).collect()),
+///             split: Split::Never,
+///         }]
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let tables = calculate_nimber_tables(&rule_sets, 20, None, &Default::default()).unwrap();
+///
+/// // Subtraction games are periodic: taking up to `max` coins has period `max + 1`
+/// for (max, table) in (1..=4).zip(tables) {
+///     assert_eq!(table[20], Nimber(20 % (max + 1)));
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`NimberError::Cancelled`] if the calculation was cancelled,
+/// or [`NimberError::InvalidRules`] if any of the rules are invalid.
+///
+/// # Panics
+///
+/// Panics if the thread pool can't be created.
+pub fn calculate_nimber_tables(
+    rule_sets: &[Vec<NimRule>],
+    max_height: u64,
+    jobs: Option<usize>,
+    options: &CalculationOptions,
+) -> Result<Vec<Vec<Nimber>>, NimberError> {
+    // Calculate each table once; a thread must not wait for the table it is calculating itself.
+    // Equivalent rules share a table, so they are compared in canonical form.
+    let rule_sets = rule_sets
+        .iter()
+        .map(|rules| RuleSet::new(rules.iter().cloned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut unique = rule_sets.iter().collect::<Vec<_>>();
+    unique.sort();
+    unique.dedup();
+
+    let done = AtomicU64::new(0);
+    let table_options = CalculationOptions {
+        progress: None,
+        ..*options
+    };
+
+    let tables = with_jobs(jobs, || {
+        unique
+            .par_iter()
+            .map(|rules| {
+                let table = super::calculate_nimbers_up_to_with(max_height, rules, &table_options);
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                options.report(done, unique.len() as u64);
+                table
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(rule_sets
+        .iter()
+        .map(|rules| tables[unique.binary_search(&rules).unwrap()].clone())
+        .collect())
+}
+
+/// Calculate the nimbers of all heights `0..=max_height` given a set of rules,
+/// visiting the splits of large stacks in parallel
+///
+/// See [`super::calculate_nimbers_up_to_with`] for details.
+///
+/// # Errors
+///
+/// Returns [`NimberError::Cancelled`] if the calculation was cancelled,
+/// or [`NimberError::InvalidRules`] if any of the rules are invalid.
+///
+/// # Panics
+///
+/// Panics if the thread pool can't be created.
+pub fn calculate_nimbers_up_to(
+    max_height: u64,
+    rules: &[NimRule],
+    jobs: Option<usize>,
+    options: &CalculationOptions,
+) -> Result<Vec<Nimber>, NimberError> {
+    with_jobs(jobs, || {
+        super::calculate_nimbers_up_to_with(max_height, rules, options)
+    })
+}
+
+/// Calculate the nimbers of many positions given a set of rules
+///
+/// The nimbers of the stacks are calculated once (up to the highest stack, using the `options`),
+/// the nimbers of the positions are then calculated concurrently.
+/// The returned nimbers are in the same order as the positions.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{nimbers::parallel::calculate_position_nimbers, NimRule, Nimber, Split, Stack, TakeSize};
+///
+/// let rules = vec![NimRule {
+///     take: TakeSize::Any,
+///     split: Split::Never,
+/// }];
+///
+/// let positions = vec![vec![Stack(1), Stack(2), Stack(3)], vec![Stack(4), Stack(1)]];
+///
+/// assert_eq!(
+///     calculate_position_nimbers(&positions, &rules, Some(2), &Default::default()),
+///     Ok(vec![Nimber(0), Nimber(5)])
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns [`NimberError::Cancelled`] if the calculation was cancelled,
+/// or [`NimberError::InvalidRules`] if any of the rules are invalid.
+///
+/// # Panics
+///
+/// Panics if the thread pool can't be created.
+pub fn calculate_position_nimbers(
+    positions: &[Vec<Stack>],
+    rules: &[NimRule],
+    jobs: Option<usize>,
+    options: &CalculationOptions,
+) -> Result<Vec<Nimber>, NimberError> {
+    let max_height = positions.iter().flatten().map(|stack| stack.0).max();
+    let Some(max_height) = max_height else {
+        return Ok(vec![Nimber(0); positions.len()]);
+    };
+
+    with_jobs(jobs, || {
+        let table = super::calculate_nimbers_up_to_with(max_height, rules, options)?;
+
+        Ok(positions
+            .par_iter()
+            .map(|stacks| {
+                stacks
+                    .iter()
+                    .map(|stack| table[super::to_index(stack.0)])
+                    .sum()
+            })
+            .collect())
+    })
+}
+
+/// Check if the splits of a stack of height `remainder` should be visited in parallel
+///
+/// This is only the case for large stacks, and only when called from within a thread pool
+/// (i.e. from the functions of this module), so the sequential functions stay sequential.
+pub(crate) fn should_split_scan(remainder: u64) -> bool {
+    remainder >= PARALLEL_SCAN_THRESHOLD && rayon::current_thread_index().is_some()
+}
+
+/// Calculate the nimbers of all splits of a stack of height `remainder` in parallel
+pub(crate) fn split_options(nimbers: &[Nimber], remainder: u64) -> Bitset {
+    (1..=remainder / 2)
+        .into_par_iter()
+        .fold(Bitset::default, |mut options, a| {
+            options
+                .insert(nimbers[super::to_index(a)].0 ^ nimbers[super::to_index(remainder - a)].0);
+            options
+        })
+        .reduce(Bitset::default, |mut options, other| {
+            options.union_with(&other);
+            options
+        })
+}
+
+/// Run a function in a thread pool with the given number of threads
+fn with_jobs<T: Send>(jobs: Option<usize>, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()
+        .expect("Failed to create a thread pool")
+        .install(f)
+}
@@ -0,0 +1,1062 @@
+//! Partizan games, where the players may make different moves (see [`RuleSet::partizan`]).
+//! The Sprague-Grundy theory doesn't apply to such games, as a position can't be replaced by a Nim stack anymore.
+//! Instead, this module calculates the values of combinatorial game theory after Conway:
+//! the canonical forms of the positions (see [`Value`]), which include numbers, switches, and infinitesimals
+//! like up and star, and which are added up like nimbers to find the values of sums of stacks.
+//!
+//! Player A is _Left_ and player B is _Right_, so positive values are good for player A,
+//! and negative values for player B (see [`OutcomeClass`]).
+//! Values are only defined for games of two players under the normal play convention.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, Neg, Sub},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    nimbers::to_index,
+    solver::{check_searchable, check_stackwise, check_two_players, Outcome, SolveError},
+    Compound, NimGame, PlayConvention, Player, RuleSet, Split, TakeSize,
+};
+
+/// A dyadic rational number, i.e. `numerator / 2^exponent` (the numbers which are values of games)
+///
+/// The number is kept in lowest terms, so the numerator is odd unless the exponent is 0
+/// (deserialized numbers are brought into lowest terms as well).  
+/// Negating the integer [`i64::MIN`] saturates at [`i64::MAX`], the only number whose negation doesn't fit.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::partizan::Dyadic;
+///
+/// let number = Dyadic::new(6, 3);
+/// assert_eq!((number.numerator(), number.exponent()), (3, 2));
+/// assert_eq!(number.to_string(), "3/4");
+/// assert!(Dyadic::new(-1, 1) < Dyadic::integer(0));
+///
+/// // Denominators beyond u64 are displayed as powers of two
+/// assert_eq!(Dyadic::new(-1, 200).to_string(), "-1/2^200");
+/// assert!(Dyadic::new(-1, 200) < Dyadic::integer(0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "DyadicData")]
+pub struct Dyadic {
+    /// The numerator
+    numerator: i64,
+
+    /// The exponent of the power of two in the denominator
+    exponent: u32,
+}
+
+impl Dyadic {
+    /// Create the number `numerator / 2^exponent` (in lowest terms)
+    #[must_use]
+    pub fn new(mut numerator: i64, mut exponent: u32) -> Self {
+        while exponent > 0 && numerator % 2 == 0 {
+            numerator /= 2;
+            exponent -= 1;
+        }
+        Self {
+            numerator,
+            exponent,
+        }
+    }
+
+    /// Create an integer
+    #[must_use]
+    pub fn integer(integer: i64) -> Self {
+        Self::new(integer, 0)
+    }
+
+    /// Get the numerator (in lowest terms)
+    #[must_use]
+    pub fn numerator(self) -> i64 {
+        self.numerator
+    }
+
+    /// Get the exponent of the power of two in the denominator (in lowest terms)
+    #[must_use]
+    pub fn exponent(self) -> u32 {
+        self.exponent
+    }
+
+    /// Check if the number is an integer
+    #[must_use]
+    pub fn is_integer(self) -> bool {
+        self.exponent == 0
+    }
+
+    /// Get the numerator for the given (larger) exponent, or [`None`] if it doesn't fit into an [i128]
+    ///
+    /// Shifting a non-zero numerator by 64 bits or more makes it larger than every [i64] numerator.
+    fn scaled(self, exponent: u32) -> Option<i128> {
+        match exponent - self.exponent {
+            shift if shift < 64 => Some(i128::from(self.numerator) << shift),
+            _ if self.numerator == 0 => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Create the number `numerator / 2^exponent` (in lowest terms),
+    /// or [`None`] if the numerator in lowest terms doesn't fit into an [i64]
+    fn from_wide(mut numerator: i128, mut exponent: u32) -> Option<Self> {
+        while exponent > 0 && numerator % 2 == 0 {
+            numerator /= 2;
+            exponent -= 1;
+        }
+        Some(Self {
+            numerator: i64::try_from(numerator).ok()?,
+            exponent,
+        })
+    }
+
+    /// Calculate half of the sum (or, if `subtract` is set, of the difference) of two numbers,
+    /// or [`None`] if the result doesn't fit
+    fn halve_sum(self, other: Self, subtract: bool) -> Option<Self> {
+        let exponent = self.exponent.max(other.exponent);
+        let (a, b) = (self.scaled(exponent)?, other.scaled(exponent)?);
+        let numerator = if subtract {
+            a.checked_sub(b)?
+        } else {
+            a.checked_add(b)?
+        };
+        Self::from_wide(numerator, exponent.checked_add(1)?)
+    }
+
+    /// Calculate the mean of two numbers, or [`None`] if it doesn't fit
+    fn mean(self, other: Self) -> Option<Self> {
+        self.halve_sum(other, false)
+    }
+
+    /// Calculate half of the distance between two numbers, or [`None`] if it doesn't fit
+    fn radius(self, other: Self) -> Option<Self> {
+        self.halve_sum(other, true)
+    }
+
+    /// Calculate the number 2<sup>-exponent</sup> below or above the number (in lowest terms),
+    /// or [`None`] if it doesn't fit
+    fn step(self, up: bool) -> Option<Self> {
+        let step = if up { 1 } else { -1 };
+        Self::from_wide(i128::from(self.numerator) + step, self.exponent)
+    }
+}
+
+impl Neg for Dyadic {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        // Only the integer `i64::MIN` has no negation (see `Dyadic`)
+        Self::new(
+            self.numerator.checked_neg().unwrap_or(i64::MAX),
+            self.exponent,
+        )
+    }
+}
+
+impl PartialOrd for Dyadic {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Dyadic {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Cross-multiply by the denominators; only the number with the smaller exponent is scaled,
+        // and if it doesn't fit, it is further from 0 than the other number
+        let exponent = self.exponent.max(other.exponent);
+        match (self.scaled(exponent), other.scaled(exponent)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (None, _) => self.numerator.cmp(&0),
+            (_, None) => 0.cmp(&other.numerator),
+        }
+    }
+}
+
+impl Display for Dyadic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match 1_u64.checked_shl(self.exponent) {
+            _ if self.is_integer() => write!(f, "{}", self.numerator),
+            Some(denominator) => write!(f, "{}/{denominator}", self.numerator),
+            None => write!(f, "{}/2^{}", self.numerator, self.exponent),
+        }
+    }
+}
+
+/// The serialized form of a [`Dyadic`], which may not be in lowest terms
+#[derive(Deserialize)]
+struct DyadicData {
+    /// See [`Dyadic::numerator`]
+    numerator: i64,
+
+    /// See [`Dyadic::exponent`]
+    exponent: u32,
+}
+
+impl From<DyadicData> for Dyadic {
+    fn from(data: DyadicData) -> Self {
+        Dyadic::new(data.numerator, data.exponent)
+    }
+}
+
+/// The outcome of a position, depending on which player moves first (assuming perfect play)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum OutcomeClass {
+    /// Left (player A) wins, whoever moves first (a positive value)
+    Left,
+
+    /// Right (player B) wins, whoever moves first (a negative value)
+    Right,
+
+    /// The player who moves second wins (a value of 0)
+    Previous,
+
+    /// The player who moves first wins (a value confused with 0)
+    Next,
+}
+
+impl OutcomeClass {
+    /// Get the outcome for the given player to move (player A is Left, every other player is Right)
+    #[must_use]
+    pub fn outcome(self, to_move: Player) -> Outcome {
+        let wins = match self {
+            OutcomeClass::Left => to_move == Player::A,
+            OutcomeClass::Right => to_move != Player::A,
+            OutcomeClass::Previous => false,
+            OutcomeClass::Next => true,
+        };
+        if wins {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+}
+
+/// The value of a game in canonical form, i.e. the simplest game equal to it
+///
+/// A game is given by the games Left and Right may move to, written `{L | R}`.
+/// Games are compared by who wins their differences: `G ≥ H` if Left wins `G - H` when Right moves first.
+/// So values are only partially ordered; e.g. star is neither positive, negative, nor 0,
+/// but _confused_ with 0 (the player who moves first wins).
+/// Every game has a unique canonical form, so equal values are structurally equal
+/// (which is how [`PartialEq`] and [`Hash`] work).
+///
+/// Values are displayed by their well-known names if possible:
+/// - Numbers like `2` (Left has two free moves) or `-1/2`
+/// - Nimbers like `*` and `*3`, the values of Nim stacks
+/// - Multiples of up (`↑ = {0 | *}`) and down like `↑`, `⇓`, `↑3`, and `↑*`
+/// - Switches like `±1` (`{1 | -1}`) or `2±1` (`{3 | 1}`), where both players are eager to move
+/// - Each of the above plus a number, e.g. `1*` or `1/2↑`
+///
+/// Other values are displayed by their options, e.g. `{2 | ±1}`.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::partizan::{Dyadic, OutcomeClass, Value};
+///
+/// let up = Value::up();
+/// assert_eq!((up.clone() + up.clone()).to_string(), "⇑");
+/// assert_eq!((up.clone() + Value::star()).to_string(), "↑*");
+/// assert_eq!(Value::star() + Value::star(), Value::zero());
+///
+/// // Up is positive, but smaller than every positive number
+/// assert!(up > Value::zero());
+/// assert!(up < Value::number(Dyadic::new(1, 10)));
+/// assert_eq!(up.partial_cmp(&Value::star()), None);
+/// assert_eq!(Value::star().class(), OutcomeClass::Next);
+///
+/// let switch = Value::new(vec![Value::integer(1)], vec![Value::integer(-1)]);
+/// assert_eq!(switch.to_string(), "±1");
+/// ```
+#[derive(Clone)]
+pub struct Value(Arc<Node>);
+
+/// The options of a [`Value`]
+struct Node {
+    /// Left's options in canonical form, sorted by [`structural_cmp`]
+    left: Vec<Value>,
+
+    /// Right's options in canonical form, sorted by [`structural_cmp`]
+    right: Vec<Value>,
+
+    /// A hash of the options, so values can be hashed and told apart quickly
+    hash: u64,
+
+    /// The birthday of the value, i.e. the height of its game tree
+    birthday: u64,
+}
+
+impl Value {
+    /// Create a value from options which are in canonical form and sorted already
+    fn from_sorted(left: Vec<Value>, right: Vec<Value>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        left.len().hash(&mut hasher);
+        for option in left.iter().chain(&right) {
+            option.0.hash.hash(&mut hasher);
+        }
+
+        let birthday = left
+            .iter()
+            .chain(&right)
+            .map(|option| option.0.birthday + 1)
+            .max()
+            .unwrap_or(0);
+
+        Self(Arc::new(Node {
+            left,
+            right,
+            hash: hasher.finish(),
+            birthday,
+        }))
+    }
+
+    /// Create the value of the game `{left | right}`, bringing it into canonical form
+    #[must_use]
+    pub fn new(left: Vec<Value>, right: Vec<Value>) -> Self {
+        Engine::default().canonical(left, right)
+    }
+
+    /// The value 0, the game without any moves (which the player to move loses)
+    #[must_use]
+    pub fn zero() -> Self {
+        Self::from_sorted(Vec::new(), Vec::new())
+    }
+
+    /// The value of a number
+    #[must_use]
+    pub fn number(number: Dyadic) -> Self {
+        if number.is_integer() {
+            return Self::integer(number.numerator);
+        }
+
+        // The simplest number between the numbers just below and above,
+        // which have the next smaller exponent as the numerator is odd
+        let below = number.numerator >> 1;
+        let exponent = number.exponent - 1;
+        Self::from_sorted(
+            vec![Self::number(Dyadic::new(below, exponent))],
+            vec![Self::number(Dyadic::new(below + 1, exponent))],
+        )
+    }
+
+    /// The value of an integer, which is the number of free moves of Left (or Right, if negative)
+    #[must_use]
+    pub fn integer(integer: i64) -> Self {
+        (0..integer.unsigned_abs()).fold(Self::zero(), |value, _| {
+            if integer > 0 {
+                Self::from_sorted(vec![value], Vec::new())
+            } else {
+                Self::from_sorted(Vec::new(), vec![value])
+            }
+        })
+    }
+
+    /// The value `*n` of a Nim stack of `n` coins
+    #[must_use]
+    pub fn nimber(nimber: u64) -> Self {
+        let mut options = Vec::new();
+        for _ in 0..nimber {
+            let next = Self::from_sorted(options.clone(), options.clone());
+            options.push(next);
+        }
+        Self::from_sorted(options.clone(), options)
+    }
+
+    /// The value star (`*1 = {0 | 0}`)
+    #[must_use]
+    pub fn star() -> Self {
+        Self::nimber(1)
+    }
+
+    /// The value up (`↑ = {0 | *}`), which is positive but smaller than every positive number
+    #[must_use]
+    pub fn up() -> Self {
+        Self::from_sorted(vec![Self::zero()], vec![Self::star()])
+    }
+
+    /// Get the options of Left, in canonical form
+    #[must_use]
+    pub fn left_options(&self) -> &[Value] {
+        &self.0.left
+    }
+
+    /// Get the options of Right, in canonical form
+    #[must_use]
+    pub fn right_options(&self) -> &[Value] {
+        &self.0.right
+    }
+
+    /// Get the number this value is equal to, if it is a number
+    #[must_use]
+    pub fn as_number(&self) -> Option<Dyadic> {
+        match self.kind(&mut HashMap::new()) {
+            Kind::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Get the outcome class of the value, i.e. how it compares to 0
+    #[must_use]
+    pub fn class(&self) -> OutcomeClass {
+        let mut engine = Engine::default();
+        let zero = Self::zero();
+        match (engine.le(&zero, self), engine.le(self, &zero)) {
+            (true, true) => OutcomeClass::Previous,
+            (true, false) => OutcomeClass::Left,
+            (false, true) => OutcomeClass::Right,
+            (false, false) => OutcomeClass::Next,
+        }
+    }
+
+    /// Classify the value by the well-known values, remembering the kinds of the visited values
+    fn kind(&self, kinds: &mut HashMap<Value, Kind>) -> Kind {
+        if let Some(kind) = kinds.get(self) {
+            return kind.clone();
+        }
+
+        let left: Vec<Kind> = self.left_options().iter().map(|o| o.kind(kinds)).collect();
+        let right: Vec<Kind> = self.right_options().iter().map(|o| o.kind(kinds)).collect();
+
+        let kind = match (left.as_slice(), right.as_slice()) {
+            ([], []) => Kind::Number(Dyadic::integer(0)),
+            // Numbers too large or too precise for a `Dyadic` are displayed by their options
+            ([Kind::Number(x)], []) if x.is_integer() => {
+                x.step(true).map_or(Kind::Other, Kind::Number)
+            }
+            ([], [Kind::Number(x)]) if x.is_integer() => {
+                x.step(false).map_or(Kind::Other, Kind::Number)
+            }
+            ([Kind::Number(a)], [Kind::Number(b)]) if a < b => {
+                a.mean(*b).map_or(Kind::Other, Kind::Number)
+            }
+            ([Kind::Number(a)], [Kind::Number(b)]) if a > b => a
+                .mean(*b)
+                .zip(a.radius(*b))
+                .map_or(Kind::Other, |(mean, radius)| Kind::Switch(mean, radius)),
+            _ if self.left_options() == self.right_options() => {
+                nimber_kind(&left).unwrap_or(Kind::Other)
+            }
+            _ => up_kind(&left, &right).unwrap_or(Kind::Other),
+        };
+
+        kinds.insert(self.clone(), kind.clone());
+        kind
+    }
+
+    /// Display the value, using the kinds of the visited values
+    fn write(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        kinds: &mut HashMap<Value, Kind>,
+    ) -> std::fmt::Result {
+        let number = |f: &mut std::fmt::Formatter<'_>, x: Dyadic| {
+            if x == Dyadic::integer(0) {
+                Ok(())
+            } else {
+                write!(f, "{x}")
+            }
+        };
+
+        match self.kind(kinds) {
+            Kind::Number(x) => write!(f, "{x}"),
+            Kind::Nimber(x, n) => {
+                number(f, x)?;
+                match n {
+                    1 => write!(f, "*"),
+                    n => write!(f, "*{n}"),
+                }
+            }
+            Kind::Up(x, ups, star) => {
+                number(f, x)?;
+                match ups {
+                    1 => write!(f, "↑")?,
+                    2 => write!(f, "⇑")?,
+                    -1 => write!(f, "↓")?,
+                    -2 => write!(f, "⇓")?,
+                    ups if ups > 0 => write!(f, "↑{ups}")?,
+                    ups => write!(f, "↓{}", -ups)?,
+                }
+                if star {
+                    write!(f, "*")?;
+                }
+                Ok(())
+            }
+            Kind::Switch(mean, radius) => {
+                number(f, mean)?;
+                write!(f, "±{radius}")
+            }
+            Kind::Other => {
+                write!(f, "{{")?;
+                for (i, option) in self.left_options().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    option.write(f, kinds)?;
+                }
+                write!(f, " | ")?;
+                for (i, option) in self.right_options().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    option.write(f, kinds)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        structurally_equal(self, other, &mut HashSet::new())
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.hash);
+    }
+}
+
+impl PartialOrd for Value {
+    /// Compare two values, which are [`None`] if they are confused with each other
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let mut engine = Engine::default();
+        match (engine.le(self, other), engine.le(other, self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, &mut HashMap::new())
+    }
+}
+
+impl Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Value({self})")
+    }
+}
+
+impl Add<&Value> for &Value {
+    type Output = Value;
+
+    fn add(self, rhs: &Value) -> Self::Output {
+        Engine::default().add(self, rhs)
+    }
+}
+
+impl Add for Value {
+    type Output = Value;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Neg for &Value {
+    type Output = Value;
+
+    fn neg(self) -> Self::Output {
+        negate(self, &mut HashMap::new())
+    }
+}
+
+impl Neg for Value {
+    type Output = Value;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Sub for Value {
+    type Output = Value;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Sum for Value {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut engine = Engine::default();
+        iter.fold(Value::zero(), |sum, value| engine.add(&sum, &value))
+    }
+}
+
+/// The well-known kinds of values, see [`Value`]
+#[derive(Clone, PartialEq)]
+enum Kind {
+    /// A number
+    Number(Dyadic),
+
+    /// A number plus a nimber (of at least 1)
+    Nimber(Dyadic, u64),
+
+    /// A number plus a multiple of up (negative for down, but not 0), plus star (if set)
+    Up(Dyadic, i64, bool),
+
+    /// A switch `{mean + radius | mean - radius}` (with a positive radius)
+    Switch(Dyadic, Dyadic),
+
+    /// Any other value
+    Other,
+}
+
+/// Find the kind of a value `x + *n` from the kinds of its options (which are the same for both players)
+fn nimber_kind(options: &[Kind]) -> Option<Kind> {
+    let mut number = None;
+    let mut nimbers = Vec::new();
+    for option in options {
+        let (x, n) = match option {
+            Kind::Number(x) => (*x, 0),
+            Kind::Nimber(x, n) => (*x, *n),
+            _ => return None,
+        };
+        if *number.get_or_insert(x) != x {
+            return None;
+        }
+        nimbers.push(n);
+    }
+
+    let number = number?;
+    nimbers.sort_unstable();
+    let count = nimbers.len() as u64;
+    nimbers
+        .into_iter()
+        .eq(0..count)
+        .then_some(Kind::Nimber(number, count))
+}
+
+/// Find the kind of a value `x` plus a multiple of up (and maybe star) from the kinds of its options
+fn up_kind(left: &[Kind], right: &[Kind]) -> Option<Kind> {
+    // `x + n·↑ = {x | x + (n - 1)·↑ + *}` and `x + n·↑ + * = {x | x + (n - 1)·↑}`, and likewise for down
+    let kind = match (left, right) {
+        ([Kind::Number(x)], [Kind::Nimber(y, 1)]) if x == y => Kind::Up(*x, 1, false),
+        ([Kind::Number(x)], [Kind::Up(y, ups, star)]) if x == y && *ups > 0 => {
+            Kind::Up(*x, ups + 1, !star)
+        }
+        ([Kind::Nimber(y, 1)], [Kind::Number(x)]) if x == y => Kind::Up(*x, -1, false),
+        ([Kind::Up(y, ups, star)], [Kind::Number(x)]) if x == y && *ups < 0 => {
+            Kind::Up(*x, ups - 1, !star)
+        }
+        // `x + ↑ + * = {x, x + * | x}`, and likewise for down
+        ([a, b], [Kind::Number(x)]) if is_number_and_star(a, b, *x) => Kind::Up(*x, 1, true),
+        ([Kind::Number(x)], [a, b]) if is_number_and_star(a, b, *x) => Kind::Up(*x, -1, true),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Check if two kinds are `x` and `x + *` (in any order)
+fn is_number_and_star(a: &Kind, b: &Kind, x: Dyadic) -> bool {
+    let number = Kind::Number(x);
+    let star = Kind::Nimber(x, 1);
+    (*a == number && *b == star) || (*a == star && *b == number)
+}
+
+/// Order values to sort the options of values, with the simpler values (born earlier) first
+///
+/// Values of the same birthday are ordered by their hashes, and by their structure if the hashes are equal.
+fn structural_cmp(a: &Value, b: &Value) -> Ordering {
+    let key = |value: &Value| {
+        (
+            value.0.birthday,
+            value.0.left.len(),
+            value.0.right.len(),
+            value.0.hash,
+        )
+    };
+    key(a).cmp(&key(b)).then_with(|| {
+        if a == b {
+            return Ordering::Equal;
+        }
+        a.0.left
+            .iter()
+            .chain(&a.0.right)
+            .zip(b.0.left.iter().chain(&b.0.right))
+            .map(|(a, b)| structural_cmp(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+/// Check if two values have the same structure, remembering the pairs of nodes found to be equal
+///
+/// Equal values may be made of different nodes, which share their options;
+/// without remembering them, the options would be compared over and over again.
+fn structurally_equal(
+    a: &Value,
+    b: &Value,
+    equal: &mut HashSet<(*const Node, *const Node)>,
+) -> bool {
+    if Arc::ptr_eq(&a.0, &b.0) {
+        return true;
+    }
+    if a.0.hash != b.0.hash
+        || a.0.left.len() != b.0.left.len()
+        || a.0.right.len() != b.0.right.len()
+    {
+        return false;
+    }
+
+    let pair = (Arc::as_ptr(&a.0), Arc::as_ptr(&b.0));
+    if equal.contains(&pair) {
+        return true;
+    }
+
+    let options_equal =
+        a.0.left
+            .iter()
+            .chain(&a.0.right)
+            .zip(b.0.left.iter().chain(&b.0.right))
+            .all(|(a, b)| structurally_equal(a, b, equal));
+    if options_equal {
+        equal.insert(pair);
+    }
+    options_equal
+}
+
+/// Negate a value, remembering the negated values
+fn negate(value: &Value, negated: &mut HashMap<Value, Value>) -> Value {
+    if let Some(negation) = negated.get(value) {
+        return negation.clone();
+    }
+
+    let mut options = |options: &[Value]| {
+        let mut options: Vec<Value> = options.iter().map(|o| negate(o, negated)).collect();
+        options.sort_by(structural_cmp);
+        options
+    };
+    let left = options(value.right_options());
+    let right = options(value.left_options());
+    let negation = Value::from_sorted(left, right);
+
+    negated.insert(value.clone(), negation.clone());
+    negation
+}
+
+/// Calculates with values, remembering the results of comparisons and sums
+#[derive(Default)]
+struct Engine {
+    /// Whether the first value is less than or equal to the second one
+    le: HashMap<(Value, Value), bool>,
+
+    /// The sums of two values
+    sums: HashMap<(Value, Value), Value>,
+
+    /// The values created so far, so equal values share their nodes and are compared quickly
+    values: HashSet<Value>,
+}
+
+impl Engine {
+    /// Check if `g ≤ h`, i.e. there is no Left option of `g` which is at least `h`,
+    /// and no Right option of `h` which is at most `g`
+    fn le(&mut self, g: &Value, h: &Value) -> bool {
+        if g == h {
+            return true;
+        }
+
+        let key = (g.clone(), h.clone());
+        if let Some(&le) = self.le.get(&key) {
+            return le;
+        }
+
+        let le = !g.left_options().iter().any(|option| self.le(h, option))
+            && !h.right_options().iter().any(|option| self.le(option, g));
+        self.le.insert(key, le);
+        le
+    }
+
+    /// Calculate the sum of two values, where each player moves in either of them
+    fn add(&mut self, g: &Value, h: &Value) -> Value {
+        let zero = Value::zero();
+        if *g == zero {
+            return h.clone();
+        }
+        if *h == zero {
+            return g.clone();
+        }
+
+        let key = match structural_cmp(g, h) {
+            Ordering::Greater => (h.clone(), g.clone()),
+            _ => (g.clone(), h.clone()),
+        };
+        if let Some(sum) = self.sums.get(&key) {
+            return sum.clone();
+        }
+
+        let mut options = |g_options: &[Value], h_options: &[Value]| {
+            let mut options = Vec::with_capacity(g_options.len() + h_options.len());
+            for option in g_options {
+                options.push(self.add(option, h));
+            }
+            for option in h_options {
+                options.push(self.add(g, option));
+            }
+            options
+        };
+        let left = options(g.left_options(), h.left_options());
+        let right = options(g.right_options(), h.right_options());
+        let sum = self.canonical(left, right);
+
+        self.sums.insert(key, sum.clone());
+        sum
+    }
+
+    /// Bring the game `{left | right}` into canonical form, where the options are in canonical form already
+    ///
+    /// Dominated options are removed, and reversible options are bypassed, until there are no such options left.
+    fn canonical(&mut self, mut left: Vec<Value>, mut right: Vec<Value>) -> Value {
+        loop {
+            left = self.undominated(left, true);
+            right = self.undominated(right, false);
+            let game = self.intern(Value::from_sorted(left.clone(), right.clone()));
+
+            // A Left option is reversible if Right has a reply which is at least as good for Right as the game itself,
+            // and then it can be replaced by Left's options after that reply
+            let mut reversed = false;
+            let mut new_left = Vec::with_capacity(left.len());
+            for option in left {
+                let reply = option
+                    .right_options()
+                    .iter()
+                    .find(|reply| self.le(reply, &game))
+                    .cloned();
+                match reply {
+                    Some(reply) => {
+                        new_left.extend_from_slice(reply.left_options());
+                        reversed = true;
+                    }
+                    None => new_left.push(option),
+                }
+            }
+
+            // Likewise for Right
+            let mut new_right = Vec::with_capacity(right.len());
+            for option in right {
+                let reply = option
+                    .left_options()
+                    .iter()
+                    .find(|reply| self.le(&game, reply))
+                    .cloned();
+                match reply {
+                    Some(reply) => {
+                        new_right.extend_from_slice(reply.right_options());
+                        reversed = true;
+                    }
+                    None => new_right.push(option),
+                }
+            }
+
+            if !reversed {
+                return game;
+            }
+            left = new_left;
+            right = new_right;
+        }
+    }
+
+    /// Get the node of a value created earlier, if any
+    fn intern(&mut self, value: Value) -> Value {
+        if let Some(existing) = self.values.get(&value) {
+            return existing.clone();
+        }
+        self.values.insert(value.clone());
+        value
+    }
+
+    /// Remove the duplicate and the dominated options of a player, and sort the remaining options
+    ///
+    /// The options of Left are dominated by larger options, those of Right by smaller options.
+    fn undominated(&mut self, mut options: Vec<Value>, left: bool) -> Vec<Value> {
+        options.sort_by(structural_cmp);
+        options.dedup();
+
+        let mut undominated = Vec::with_capacity(options.len());
+        for (i, option) in options.iter().enumerate() {
+            let dominated = options.iter().enumerate().any(|(j, other)| {
+                i != j
+                    && if left {
+                        self.le(option, other)
+                    } else {
+                        self.le(other, option)
+                    }
+            });
+            if !dominated {
+                undominated.push(option.clone());
+            }
+        }
+        undominated
+    }
+}
+
+/// Calculate the values of single stacks of all heights up to `max_height`
+///
+/// Left (player A) moves by the rules of player A, and Right (player B) by the rules of player B
+/// (see [`RuleSet::rules_for`]).
+/// A stack split into two stacks is worth the sum of their values.
+/// For impartial rules, the values are the nimbers of the stacks (see [`crate::nimbers`]).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{partizan, NimRule, Player, RuleSet, Split, TakeSize};
+///
+/// let take = |list| NimRule {
+///     take: TakeSize::List(list),
+///     split: Split::Never,
+/// };
+///
+/// // Player A may take 1 coin, player B 1 or 2 coins
+/// let rules = RuleSet::partizan([
+///     (Some(Player::A), take(vec![1])),
+///     (Some(Player::B), take(vec![1, 2])),
+/// ])
+/// .unwrap();
+///
+/// let values: Vec<String> = partizan::stack_values(&rules, 4)
+///     .unwrap()
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+/// assert_eq!(values, ["0", "*", "↓", "⇓*", "↓3"]);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+/// or [`SolveError::NotStackwise`] if the rules allow moves which involve several stacks or depend on the previous move.
+pub fn stack_values(rules: &RuleSet, max_height: u64) -> Result<Vec<Value>, SolveError> {
+    check_stackwise(rules)?;
+
+    let mut engine = Engine::default();
+    let mut values: Vec<Value> = Vec::new();
+    for height in 0..=max_height {
+        let mut options = |player: Player| {
+            let mut options = Vec::new();
+            for rule in rules.rules_for(player) {
+                let takes: Vec<u64> = match &rule.take {
+                    TakeSize::List(list) => list.iter().copied().filter(|&t| t <= height).collect(),
+                    _ => (1..=height).collect(),
+                };
+
+                for take in takes {
+                    let rest = height - take;
+                    if rule.split != Split::Always {
+                        options.push(values[to_index(rest)].clone());
+                    }
+                    if rule.split != Split::Never {
+                        for a in 1..=rest / 2 {
+                            let sum = engine.add(&values[to_index(a)], &values[to_index(rest - a)]);
+                            options.push(sum);
+                        }
+                    }
+                }
+            }
+            options
+        };
+
+        let left = options(Player::A);
+        let right = options(Player::B);
+        let value = engine.canonical(left, right);
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Calculate the value of a position, the sum of the values of its stacks (see [`stack_values`])
+///
+/// The value doesn't depend on the player to move; see [`Value::class`] for who wins.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{partizan, NimGame, NimRule, Player, Split, Stack, TakeSize};
+///
+/// // Player A may take any number of coins, player B only a single coin
+/// let game = NimGame::builder()
+///     .player_rules(
+///         Player::A,
+///         [NimRule {
+///             take: TakeSize::Any,
+///             split: Split::Never,
+///         }],
+///     )
+///     .player_rules(
+///         Player::B,
+///         [NimRule {
+///             take: TakeSize::List(vec![1]),
+///             split: Split::Never,
+///         }],
+///     )
+///     .stacks(vec![Stack(1), Stack(2), Stack(3)])
+///     .build()
+///     .unwrap();
+///
+/// // The stacks are worth `*`, `↑`, and `⇑*`
+/// assert_eq!(partizan::value(&game).unwrap().to_string(), "↑3");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+/// [`SolveError::NotStackwise`] if the rules allow moves which involve several stacks or depend on the previous move,
+/// [`SolveError::NotTwoPlayers`] if the game doesn't have two players,
+/// [`SolveError::NotDisjunctive`] if the stacks are not a disjunctive sum (see [`crate::compound`]),
+/// or [`SolveError::MisereValue`] if the game is played under the misère play convention.
+pub fn value(game: &NimGame) -> Result<Value, SolveError> {
+    check_searchable(game)?;
+    check_stackwise(game.get_rules())?;
+    check_two_players(game)?;
+    if game.get_compound() != Compound::Disjunctive {
+        return Err(SolveError::NotDisjunctive {
+            compound: game.get_compound(),
+        });
+    }
+    if game.get_convention() == PlayConvention::Misere {
+        return Err(SolveError::MisereValue);
+    }
+
+    let max_height = game.get_stacks().iter().map(|stack| stack.0).max();
+    let values = stack_values(game.get_rules(), max_height.unwrap_or(0))?;
+
+    Ok(game
+        .get_stacks()
+        .iter()
+        .map(|stack| values[to_index(stack.0)].clone())
+        .sum())
+}
+
+/// Find the outcome of a position for the player whose turn it is, from its value (see [`value`])
+///
+/// # Errors
+///
+/// Returns the errors of [`value`].
+pub fn solve(game: &NimGame) -> Result<Outcome, SolveError> {
+    Ok(value(game)?.class().outcome(game.get_to_move()))
+}
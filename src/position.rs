@@ -0,0 +1,285 @@
+//! Canonical positions, which identify equivalent positions of Nim games
+//! (e.g. for transposition tables and caches).
+
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{rule_set::fnv1a, Coins, Compound, NimGame, PlayConvention, Player, RuleSet, Stack};
+
+/// The canonical form of a position, see [`NimGame::canonical_position`]
+///
+/// Equivalent positions have the same canonical form:
+/// - Empty stacks are dropped, and the stacks are sorted by their heights
+///   (unless the order of the stacks matters, see [`RuleSet::depends_on_stack_order`];
+///   then only the empty stacks after the last non-empty stack are dropped)
+/// - The rules are in canonical form (see [`RuleSet`])
+/// - The pools are given relative to the player whose turn it is, in the order of the following turns
+///   (as all players play by the same rules, unless the rules are partizan)
+///
+/// The play convention, the compound of the stacks (see [`NimGame::get_compound`]), and the number of players
+/// are part of the position, as they change the outcome of the game,
+/// and so are the coins taken by the previous move if the rules depend on them
+/// (see [`RuleSet::depends_on_last_take`]), and the player whose turn it is if the rules are partizan
+/// (see [`RuleSet::is_partizan`]).
+/// Hashing a canonical position only hashes its [`CanonicalPosition::fingerprint`].
+///
+/// When deserializing, the position is checked to be in canonical form,
+/// and the fingerprint is calculated anew (a serialized fingerprint is ignored).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "PositionData<C>", bound = "C: Coins")]
+#[allow(clippy::module_name_repetitions)]
+pub struct CanonicalPosition<C = u64> {
+    /// The rules of the game
+    rules: RuleSet,
+
+    /// The non-empty stacks, sorted by their heights (or in order, see [`CanonicalPosition::stacks`])
+    stacks: Vec<Stack<C>>,
+
+    /// The coins in the pools of the players, starting with the player whose turn it is
+    pools: Vec<C>,
+
+    /// The play convention
+    convention: PlayConvention,
+
+    /// How the stacks are combined
+    compound: Compound,
+
+    /// The coins taken by the previous move, if the rules depend on them
+    last_take: Option<C>,
+
+    /// The player whose turn it is, if the rules are partizan
+    to_move: Option<Player>,
+
+    /// The stable hash of the position
+    fingerprint: u64,
+}
+
+impl<C: Coins> CanonicalPosition<C> {
+    /// Create the canonical form of the position of a game
+    pub(crate) fn new(game: &NimGame<C>) -> Self {
+        let stacks = if game.get_rules().depends_on_stack_order() {
+            let stacks = game.get_stacks();
+            let end = stacks
+                .iter()
+                .rposition(|stack| !stack.0.is_zero())
+                .map_or(0, |last| last + 1);
+            stacks[..end].to_vec()
+        } else {
+            let mut stacks: Vec<Stack<C>> = game
+                .get_stacks()
+                .iter()
+                .filter(|stack| !stack.0.is_zero())
+                .cloned()
+                .collect();
+            stacks.sort_unstable();
+            stacks
+        };
+
+        let mut pools = game.get_pools().to_vec();
+        pools.rotate_left(game.get_to_move().0);
+        let convention = game.get_convention();
+        let compound = game.get_compound();
+        let last_take = game
+            .get_rules()
+            .depends_on_last_take()
+            .then(|| game.get_last_take())
+            .flatten();
+        let to_move = game.get_rules().is_partizan().then(|| game.get_to_move());
+
+        let mut position = Self {
+            rules: game.get_rules().clone(),
+            stacks,
+            pools,
+            convention,
+            compound,
+            last_take,
+            to_move,
+            fingerprint: 0,
+        };
+        position.fingerprint = position.calculate_fingerprint();
+        position
+    }
+
+    /// Calculate the stable hash of the position, see [`CanonicalPosition::fingerprint`]
+    fn calculate_fingerprint(&self) -> u64 {
+        let mut bytes = Vec::with_capacity(8 * (self.stacks.len() + self.pools.len() + 3) + 1);
+        bytes.extend_from_slice(&self.rules.fingerprint().to_le_bytes());
+        bytes.extend_from_slice(&(self.stacks.len() as u64).to_le_bytes());
+        for stack in &self.stacks {
+            stack.0.encode(&mut bytes);
+        }
+        for coins in &self.pools {
+            coins.encode(&mut bytes);
+        }
+        bytes.push(match self.convention {
+            PlayConvention::Normal => 0,
+            PlayConvention::Misere => 1,
+        });
+        // Only appended after a move took coins, so the fingerprints of other positions stay the same
+        if let Some(last_take) = &self.last_take {
+            last_take.encode(&mut bytes);
+        }
+        // Likewise, only appended for games of more than two players
+        if self.pools.len() != 2 {
+            bytes.extend_from_slice(&(self.pools.len() as u64).to_le_bytes());
+        }
+        // And only for partizan rules
+        if let Some(player) = self.to_move {
+            bytes.extend_from_slice(&(player.0 as u64).to_le_bytes());
+        }
+        // And only for other sums than the disjunctive one
+        match self.compound {
+            Compound::Disjunctive => {}
+            Compound::Conjunctive => bytes.push(1),
+            Compound::Selective => bytes.push(2),
+        }
+
+        fnv1a(&bytes)
+    }
+
+    /// Get the rules of the position (in canonical form)
+    #[must_use]
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Get the non-empty stacks of the position, sorted by their heights
+    /// (or the stacks up to the last non-empty one, if their order matters)
+    #[must_use]
+    pub fn stacks(&self) -> &[Stack<C>] {
+        &self.stacks
+    }
+
+    /// Get the coins in the pools of the players, starting with the player whose turn it is
+    /// and continuing in the order of their turns (e.g. B, C, A if it is the turn of player B of three players)
+    #[must_use]
+    pub fn pool_coins(&self) -> &[C] {
+        &self.pools
+    }
+
+    /// Get the number of players of the position
+    #[must_use]
+    pub fn player_count(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Get the play convention of the position
+    #[must_use]
+    pub fn convention(&self) -> PlayConvention {
+        self.convention
+    }
+
+    /// Get how the stacks of the position are combined (see [`NimGame::get_compound`])
+    #[must_use]
+    pub fn compound(&self) -> Compound {
+        self.compound
+    }
+
+    /// Get the coins taken by the previous move, if the rules depend on them (see [`NimGame::get_last_take`])
+    #[must_use]
+    pub fn last_take(&self) -> Option<C> {
+        self.last_take.clone()
+    }
+
+    /// Get the player whose turn it is, if the rules are partizan (see [`RuleSet::is_partizan`])
+    #[must_use]
+    pub fn to_move(&self) -> Option<Player> {
+        self.to_move
+    }
+
+    /// Get a stable 64-bit hash of the canonical position
+    ///
+    /// The fingerprint is the FNV-1a hash of a fixed encoding of the position,
+    /// which includes the [`RuleSet::fingerprint`] of the rules and is stable in the same way.
+    /// Equal positions have equal fingerprints regardless of the type of [`Coins`] they count with.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
+
+impl<C> Hash for CanonicalPosition<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fingerprint.hash(state);
+    }
+}
+
+/// The serialized form of a [`CanonicalPosition`], from which the fingerprint is calculated
+#[derive(Deserialize)]
+#[serde(bound = "C: Coins")]
+struct PositionData<C> {
+    /// See [`CanonicalPosition::rules`]
+    rules: RuleSet,
+
+    /// See [`CanonicalPosition::stacks`]
+    stacks: Vec<Stack<C>>,
+
+    /// See [`CanonicalPosition::pool_coins`]
+    pools: Vec<C>,
+
+    /// See [`CanonicalPosition::convention`]
+    convention: PlayConvention,
+
+    /// See [`CanonicalPosition::compound`]
+    compound: Compound,
+
+    /// See [`CanonicalPosition::last_take`]
+    last_take: Option<C>,
+
+    /// See [`CanonicalPosition::to_move`]
+    to_move: Option<Player>,
+}
+
+impl<C: Coins> TryFrom<PositionData<C>> for CanonicalPosition<C> {
+    type Error = String;
+
+    fn try_from(data: PositionData<C>) -> Result<Self, Self::Error> {
+        let canonical_stacks = if data.rules.depends_on_stack_order() {
+            data.stacks.last().is_none_or(|stack| !stack.0.is_zero())
+        } else {
+            data.stacks.iter().all(|stack| !stack.0.is_zero())
+                && data.stacks.windows(2).all(|pair| pair[0] <= pair[1])
+        };
+        if !canonical_stacks {
+            return Err("The stacks of the position are not in canonical form".to_string());
+        }
+        if data.pools.len() < 2 {
+            return Err(format!(
+                "A position needs at least 2 players, but it has {}",
+                data.pools.len()
+            ));
+        }
+        if data.last_take.is_some() && !data.rules.depends_on_last_take() {
+            return Err("The rules of the position don't depend on the previous move".to_string());
+        }
+        match data.to_move {
+            None if data.rules.is_partizan() => {
+                return Err("The player whose turn it is is missing".to_string());
+            }
+            Some(_) if !data.rules.is_partizan() => {
+                return Err("The rules of the position are not partizan".to_string());
+            }
+            Some(player) if player.0 >= data.pools.len() => {
+                return Err(format!(
+                    "There is no player {player}, as the position has {} players",
+                    data.pools.len()
+                ));
+            }
+            _ => {}
+        }
+
+        let mut position = CanonicalPosition {
+            rules: data.rules,
+            stacks: data.stacks,
+            pools: data.pools,
+            convention: data.convention,
+            compound: data.compound,
+            last_take: data.last_take,
+            to_move: data.to_move,
+            fingerprint: 0,
+        };
+        position.fingerprint = position.calculate_fingerprint();
+        Ok(position)
+    }
+}
@@ -0,0 +1,758 @@
+//! Canonical sets of [`NimRule`]s
+//!
+//! Different lists of rules can describe the same game,
+//! e.g. `[take 1, take 2]` and `[take 2, take 1]`, or `List(vec![3, 1, 1])` and `List(vec![1, 3])`.
+//! A [`RuleSet`] brings such lists into a single canonical form,
+//! so they compare equal and share the same nimber cache.
+//!
+//! Rule sets are validated when they are created, see [`RuleProblem`] for the rejected rules.
+//!
+//! Some rules may be reserved for single players, which makes the game partizan (see [`RuleSet::partizan`]).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::Display,
+    ops::Deref,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{NimRule, Player, Split, TakeSize};
+
+/// A canonical set of [`NimRule`]s
+///
+/// Two rule sets are equal if (and only if) they allow the same moves,
+/// regardless of the order or the grouping of the rules they were created from.
+///
+/// The canonical form is created as follows:
+/// - The take sizes of all [`TakeSize::List`] rules are merged per [`Split`] mode;
+///   a take size allowed both with and without splitting is merged into [`Split::Optional`],
+///   and taking 0 coins is only allowed with [`Split::Always`] (without splitting, it is not a move)
+/// - [`TakeSize::Any`] rules are merged into a single rule in the same way,
+///   and the take sizes of lists covered by it are removed from the lists
+///   (it covers all take sizes except 0)
+/// - [`TakeSize::AnyFromStacks`] rules are merged into a single rule allowing the most stacks;
+///   it covers all take sizes except 0 without splitting in the same way,
+///   and a rule allowing a single stack is the same as [`TakeSize::Any`] without splitting
+/// - [`TakeSize::SameFromTwoStacks`] rules are dropped if a [`TakeSize::AnyFromStacks`] rule
+///   allows taking coins from two (or more) stacks
+/// - [`TakeSize::UpToMultipleOfLast`] rules are merged per [`Split`] mode into the rules with the largest multiple,
+///   dropping the modes covered by the `Any` rule
+/// - [`TakeSize::TransferToNeighbour`] rules are dropped if there is a [`TakeSize::TransferToLower`] rule
+/// - Duplicate take sizes are removed
+/// - The rules are sorted
+///
+/// The rule set dereferences to a slice of its (canonical) rules,
+/// and is (de)serialized as a list of rules (which is validated when deserializing).
+///
+/// A partizan rule set also has rules which only some players may use (see [`RuleSet::partizan`]).
+/// Then the rules dereferenced to are the rules for all players,
+/// and the rules of a player are those rules together with their own rules (see [`RuleSet::rules_for`]).
+/// Such rule sets are serialized as an object of the `rules` for all players
+/// and the rules of the `players` (each given by the `player` and their `rules`).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{NimRule, RuleSet, Split, TakeSize};
+///
+/// let a = RuleSet::new(vec![
+///     NimRule {
+///         take: TakeSize::List(vec![3, 1, 1]),
+///         split: Split::Never,
+///     },
+///     NimRule {
+///         take: TakeSize::List(vec![3]),
+///         split: Split::Always,
+///     },
+/// ])
+/// .unwrap();
+///
+/// let b = RuleSet::new(vec![
+///     NimRule {
+///         take: TakeSize::List(vec![3]),
+///         split: Split::Optional,
+///     },
+///     NimRule {
+///         take: TakeSize::List(vec![1]),
+///         split: Split::Never,
+///     },
+/// ])
+/// .unwrap();
+///
+/// assert_eq!(a, b);
+/// assert_eq!(a.fingerprint(), b.fingerprint());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RuleSetData", into = "RuleSetData")]
+pub struct RuleSet {
+    /// The rules for all players in canonical form
+    rules: Vec<NimRule>,
+
+    /// The rules of the players with rules of their own, sorted by the players
+    ///
+    /// The rules of a player include the rules for all players and are in canonical form;
+    /// players whose rules are just the rules for all players are left out.
+    player_rules: Vec<(Player, Vec<NimRule>)>,
+
+    /// The fingerprint of the canonical rules, see [`RuleSet::fingerprint`]
+    fingerprint: u64,
+}
+
+impl RuleSet {
+    /// Validate a list of rules and create a canonical rule set from it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimRule, RuleProblem, RuleSet, Split, TakeSize};
+    ///
+    /// let error = RuleSet::new(vec![
+    ///     NimRule {
+    ///         take: TakeSize::List(vec![]),
+    ///         split: Split::Never,
+    ///     },
+    ///     NimRule {
+    ///         take: TakeSize::Place,
+    ///         split: Split::Always,
+    ///     },
+    /// ])
+    /// .unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.problems,
+    ///     vec![
+    ///         RuleProblem::EmptyTakeList { rule: 0 },
+    ///         RuleProblem::PlaceWithSplit { rule: 1 },
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RuleSetError`] listing every problem of the rules (see [`RuleProblem`]).
+    pub fn new(rules: impl IntoIterator<Item = NimRule>) -> Result<Self, RuleSetError> {
+        Self::partizan(rules.into_iter().map(|rule| (None, rule)))
+    }
+
+    /// Validate a list of rules, each for all players ([`None`]) or a single player,
+    /// and create a canonical rule set from it
+    ///
+    /// The players may make different moves in a position, so the game is partizan
+    /// (see [`crate::partizan`] for the values of such games).
+    /// Every player may use the rules for all players and their own rules.
+    /// The rules of a player have to be valid on their own;
+    /// `rule` of a [`RuleProblem`] is the index of the rule in the whole list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimlib::{NimRule, Player, RuleSet, Split, TakeSize};
+    ///
+    /// let take = |list| NimRule {
+    ///     take: TakeSize::List(list),
+    ///     split: Split::Never,
+    /// };
+    ///
+    /// // Both players may take 1 coin, and player B may take 2 coins as well
+    /// let rules = RuleSet::partizan([
+    ///     (None, take(vec![1])),
+    ///     (Some(Player::B), take(vec![2])),
+    /// ])
+    /// .unwrap();
+    ///
+    /// assert!(rules.is_partizan());
+    /// assert_eq!(rules.rules(), &[take(vec![1])]);
+    /// assert_eq!(rules.rules_for(Player::A), &[take(vec![1])]);
+    /// assert_eq!(rules.rules_for(Player::B), &[take(vec![1, 2])]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RuleSetError`] listing every problem of the rules (see [`RuleProblem`]).
+    pub fn partizan(
+        rules: impl IntoIterator<Item = (Option<Player>, NimRule)>,
+    ) -> Result<Self, RuleSetError> {
+        let rules = rules.into_iter().collect::<Vec<_>>();
+
+        let problems = validate(&rules);
+        if !problems.is_empty() {
+            return Err(RuleSetError { problems });
+        }
+
+        let mut shared = Vec::new();
+        let mut own: BTreeMap<Player, Vec<NimRule>> = BTreeMap::new();
+        for (player, rule) in rules {
+            match player {
+                None => shared.push(rule),
+                Some(player) => own.entry(player).or_default().push(rule),
+            }
+        }
+
+        let player_rules = own
+            .into_iter()
+            .map(|(player, mut rules)| {
+                rules.extend(shared.iter().cloned());
+                (player, canonicalize(rules))
+            })
+            .collect::<Vec<_>>();
+        let rules = canonicalize(shared);
+        let player_rules = player_rules
+            .into_iter()
+            .filter(|(_, player_rules)| *player_rules != rules)
+            .collect::<Vec<_>>();
+        let fingerprint = fingerprint(&rules, &player_rules);
+
+        Ok(Self {
+            rules,
+            player_rules,
+            fingerprint,
+        })
+    }
+
+    /// Get the rules for all players in canonical form
+    #[must_use]
+    pub fn rules(&self) -> &[NimRule] {
+        &self.rules
+    }
+
+    /// Get the rules a player may use in canonical form,
+    /// i.e. the rules for all players together with the player's own rules
+    #[must_use]
+    pub fn rules_for(&self, player: Player) -> &[NimRule] {
+        self.player_rules
+            .iter()
+            .find(|(other, _)| *other == player)
+            .map_or(&self.rules, |(_, rules)| rules)
+    }
+
+    /// Get the rules of the players who have rules of their own (see [`RuleSet::rules_for`]), sorted by the players
+    #[must_use]
+    pub fn player_rules(&self) -> &[(Player, Vec<NimRule>)] {
+        &self.player_rules
+    }
+
+    /// Check if some players have rules of their own, so the game is partizan
+    #[must_use]
+    pub fn is_partizan(&self) -> bool {
+        !self.player_rules.is_empty()
+    }
+
+    /// Iterate over the rules of all players, which may contain the same rule several times
+    pub(crate) fn all_rules(&self) -> impl Iterator<Item = &NimRule> {
+        self.rules
+            .iter()
+            .chain(self.player_rules.iter().flat_map(|(_, rules)| rules))
+    }
+
+    /// A stable 64-bit fingerprint of the rule set
+    ///
+    /// Equal rule sets have equal fingerprints.
+    /// The fingerprint is the FNV-1a hash of a fixed encoding of the canonical rules,
+    /// so it does not change between runs, platforms, or Rust versions
+    /// (unlike [`std::hash::Hash`] with the default hasher),
+    /// and can be used to identify a rule set e.g. in file names.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Check if the moves allowed by the rules depend on the previous move
+    /// (i.e. there is a [`TakeSize::UpToMultipleOfLast`] rule, see [`crate::NimGame::get_last_take`])
+    #[must_use]
+    pub fn depends_on_last_take(&self) -> bool {
+        self.all_rules()
+            .any(|rule| matches!(rule.take, TakeSize::UpToMultipleOfLast(_)))
+    }
+
+    /// Check if the moves allowed by the rules depend on the order of the stacks
+    /// (i.e. there is a [`TakeSize::TransferToNeighbour`] or [`TakeSize::TransferToLower`] rule)
+    ///
+    /// The stacks of such games are not sorted when normalizing them (see [`crate::NimGame::normalize`]).
+    #[must_use]
+    pub fn depends_on_stack_order(&self) -> bool {
+        self.all_rules().any(|rule| {
+            matches!(
+                rule.take,
+                TakeSize::TransferToNeighbour | TakeSize::TransferToLower
+            )
+        })
+    }
+}
+
+impl Deref for RuleSet {
+    type Target = [NimRule];
+
+    fn deref(&self) -> &Self::Target {
+        &self.rules
+    }
+}
+
+impl<'a> IntoIterator for &'a RuleSet {
+    type Item = &'a NimRule;
+    type IntoIter = std::slice::Iter<'a, NimRule>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rules.iter()
+    }
+}
+
+impl AsRef<Vec<NimRule>> for RuleSet {
+    fn as_ref(&self) -> &Vec<NimRule> {
+        &self.rules
+    }
+}
+
+impl TryFrom<Vec<NimRule>> for RuleSet {
+    type Error = RuleSetError;
+
+    fn try_from(rules: Vec<NimRule>) -> Result<Self, Self::Error> {
+        Self::new(rules)
+    }
+}
+
+impl From<RuleSet> for Vec<NimRule> {
+    fn from(rules: RuleSet) -> Self {
+        rules.rules
+    }
+}
+
+/// The serialized form of a [`RuleSet`]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RuleSetData {
+    /// The rules of an impartial rule set
+    Impartial(Vec<NimRule>),
+
+    /// The rules of a partizan rule set
+    Partizan {
+        /// The rules for all players
+        rules: Vec<NimRule>,
+
+        /// The rules of single players
+        players: Vec<PlayerRules>,
+    },
+}
+
+/// The rules of a single player in a [`RuleSetData::Partizan`]
+#[derive(Serialize, Deserialize)]
+struct PlayerRules {
+    /// The player
+    player: Player,
+
+    /// The rules only the player may use
+    rules: Vec<NimRule>,
+}
+
+impl TryFrom<RuleSetData> for RuleSet {
+    type Error = RuleSetError;
+
+    fn try_from(data: RuleSetData) -> Result<Self, Self::Error> {
+        match data {
+            RuleSetData::Impartial(rules) => Self::new(rules),
+            RuleSetData::Partizan { rules, players } => Self::partizan(
+                rules
+                    .into_iter()
+                    .map(|rule| (None, rule))
+                    .chain(
+                        players
+                            .into_iter()
+                            .flat_map(|PlayerRules { player, rules }| {
+                                rules.into_iter().map(move |rule| (Some(player), rule))
+                            }),
+                    ),
+            ),
+        }
+    }
+}
+
+impl From<RuleSet> for RuleSetData {
+    fn from(rules: RuleSet) -> Self {
+        if rules.player_rules.is_empty() {
+            return Self::Impartial(rules.rules);
+        }
+
+        Self::Partizan {
+            rules: rules.rules,
+            players: rules
+                .player_rules
+                .into_iter()
+                .map(|(player, rules)| PlayerRules { player, rules })
+                .collect(),
+        }
+    }
+}
+
+/// A problem with a single rule of a rule set, found when validating it
+///
+/// `rule` is the index of the rule in the list the rule set was created from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RuleProblem {
+    /// A [`TakeSize::Place`] rule which allows or requires splitting the stack
+    PlaceWithSplit {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A [`TakeSize::List`] rule without any take sizes
+    EmptyTakeList {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A [`TakeSize::List`] rule which takes 0 coins, but never splits the stack,  
+    /// so it would only allow moves to the same position
+    ///
+    /// With [`Split::Optional`], taking 0 coins is only a move when splitting the stack.
+    ZeroTakeWithoutSplit {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A [`TakeSize::AnyFromStacks`] rule which allows taking coins from 0 stacks
+    NoStacks {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A [`TakeSize::AnyFromStacks`], [`TakeSize::SameFromTwoStacks`], [`TakeSize::TransferToNeighbour`],
+    /// or [`TakeSize::TransferToLower`] rule which allows or requires splitting the stacks
+    SplitAcrossStacks {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A [`TakeSize::UpToMultipleOfLast`] rule with a multiple of 0,
+    /// which wouldn't allow any move after the first one
+    ZeroMultiple {
+        /// The index of the rule
+        rule: usize,
+    },
+
+    /// A rule which is identical to an earlier rule (for the same players)
+    DuplicateRule {
+        /// The index of the rule
+        rule: usize,
+
+        /// The index of the earlier, identical rule
+        first: usize,
+    },
+}
+
+impl Display for RuleProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleProblem::PlaceWithSplit { rule } => {
+                write!(f, "Rule {rule}: Splitting is not allowed with Place")
+            }
+            RuleProblem::EmptyTakeList { rule } => {
+                write!(f, "Rule {rule}: The list of take sizes is empty")
+            }
+            RuleProblem::ZeroTakeWithoutSplit { rule } => write!(
+                f,
+                "Rule {rule}: Taking 0 coins is only a move when splitting the stack, which the rule never does"
+            ),
+            RuleProblem::NoStacks { rule } => {
+                write!(
+                    f,
+                    "Rule {rule}: Coins have to be taken from at least 1 stack"
+                )
+            }
+            RuleProblem::SplitAcrossStacks { rule } => write!(
+                f,
+                "Rule {rule}: Splitting is not allowed when moving coins of several stacks"
+            ),
+            RuleProblem::ZeroMultiple { rule } => write!(
+                f,
+                "Rule {rule}: At least 1 times the coins of the previous move have to be allowed"
+            ),
+            RuleProblem::DuplicateRule { rule, first } => {
+                write!(f, "Rule {rule}: Duplicate of rule {first}")
+            }
+        }
+    }
+}
+
+/// The error returned when creating a [`RuleSet`] from invalid rules
+///
+/// Lists every problem found, not just the first one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RuleSetError {
+    /// The problems of the rules, in the order of the rules
+    pub problems: Vec<RuleProblem>,
+}
+
+impl Display for RuleSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid rules: ")?;
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RuleSetError {}
+
+/// Find all problems of a list of rules (see [`RuleProblem`])
+fn validate(rules: &[(Option<Player>, NimRule)]) -> Vec<RuleProblem> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<&(Option<Player>, NimRule), usize> = HashMap::new();
+
+    for (i, tagged) in rules.iter().enumerate() {
+        let rule = &tagged.1;
+        match &rule.take {
+            TakeSize::Place if rule.split != Split::Never => {
+                problems.push(RuleProblem::PlaceWithSplit { rule: i });
+            }
+            TakeSize::List(list) if list.is_empty() => {
+                problems.push(RuleProblem::EmptyTakeList { rule: i });
+            }
+            TakeSize::List(list) if list.contains(&0) && rule.split == Split::Never => {
+                problems.push(RuleProblem::ZeroTakeWithoutSplit { rule: i });
+            }
+            TakeSize::AnyFromStacks(0) => problems.push(RuleProblem::NoStacks { rule: i }),
+            TakeSize::AnyFromStacks(_)
+            | TakeSize::SameFromTwoStacks
+            | TakeSize::TransferToNeighbour
+            | TakeSize::TransferToLower
+                if rule.split != Split::Never =>
+            {
+                problems.push(RuleProblem::SplitAcrossStacks { rule: i });
+            }
+            TakeSize::UpToMultipleOfLast(0) => {
+                problems.push(RuleProblem::ZeroMultiple { rule: i });
+            }
+            _ => {}
+        }
+
+        if let Some(&first) = seen.get(tagged) {
+            problems.push(RuleProblem::DuplicateRule { rule: i, first });
+        } else {
+            seen.insert(tagged, i);
+        }
+    }
+
+    problems
+}
+
+/// The moves allowed for a take size: (without splitting, with splitting)
+type Modes = (bool, bool);
+
+/// The [`Modes`] allowed by a [`Split`]
+fn modes(split: Split) -> Modes {
+    match split {
+        Split::Never => (true, false),
+        Split::Optional => (true, true),
+        Split::Always => (false, true),
+    }
+}
+
+/// The [`Split`] allowing exactly the given [`Modes`] (if any)
+fn split(modes: Modes) -> Option<Split> {
+    match modes {
+        (true, false) => Some(Split::Never),
+        (true, true) => Some(Split::Optional),
+        (false, true) => Some(Split::Always),
+        (false, false) => None,
+    }
+}
+
+/// Get the canonical rules taking up to the largest multiples of the previous move
+/// (without splitting, with splitting), where a multiple of 0 means there is no such rule
+fn multiple_rules(multiples: (u64, u64)) -> Vec<NimRule> {
+    let rules = match multiples {
+        (0, 0) => vec![],
+        (without, with) if without == with => vec![(without, Split::Optional)],
+        (without, with) => vec![(without, Split::Never), (with, Split::Always)],
+    };
+    rules
+        .into_iter()
+        .filter(|&(multiple, _)| multiple > 0)
+        .map(|(multiple, split)| NimRule {
+            take: TakeSize::UpToMultipleOfLast(multiple),
+            split,
+        })
+        .collect()
+}
+
+/// Bring a (valid) list of rules into canonical form (see [`RuleSet`])
+fn canonicalize(rules: Vec<NimRule>) -> Vec<NimRule> {
+    let mut takes: BTreeMap<u64, Modes> = BTreeMap::new();
+    let mut any: Modes = (false, false);
+    let mut stacks = 0;
+    let mut same_from_two = false;
+    // The largest multiples of the previous move: (without splitting, with splitting)
+    let mut multiples: (u64, u64) = (0, 0);
+    let mut transfer_to_neighbour = false;
+    let mut transfer_to_lower = false;
+    let mut canonical = Vec::new();
+
+    for rule in rules {
+        let (without, with) = modes(rule.split);
+        match rule.take {
+            TakeSize::List(list) => {
+                for take in list {
+                    let entry = takes.entry(take).or_default();
+                    entry.0 |= without;
+                    entry.1 |= with;
+                }
+            }
+            TakeSize::Any => {
+                any.0 |= without;
+                any.1 |= with;
+            }
+            TakeSize::AnyFromStacks(count) => stacks = stacks.max(count),
+            TakeSize::SameFromTwoStacks => same_from_two = true,
+            TakeSize::UpToMultipleOfLast(multiple) => {
+                if without {
+                    multiples.0 = multiples.0.max(multiple);
+                }
+                if with {
+                    multiples.1 = multiples.1.max(multiple);
+                }
+            }
+            TakeSize::TransferToNeighbour => transfer_to_neighbour = true,
+            TakeSize::TransferToLower => transfer_to_lower = true,
+            // Place rules can't be merged with take rules
+            TakeSize::Place => canonical.push(rule),
+        }
+    }
+
+    // Taking coins from a single stack is the same as taking any number of coins without splitting,
+    // which is covered by taking coins from several stacks as well
+    if stacks > 0 {
+        any.0 = true;
+    }
+    let across = stacks > 1;
+
+    // Group the take sizes by their modes, dropping the modes covered by the `Any` rule
+    let mut lists: BTreeMap<Split, Vec<u64>> = BTreeMap::new();
+    for (take, (without, with)) in takes {
+        // Taking 0 coins without splitting the stack is not a move
+        let modes = if take == 0 {
+            (false, with)
+        } else {
+            (without && !any.0, with && !any.1)
+        };
+
+        if let Some(split) = split(modes) {
+            lists.entry(split).or_default().push(take);
+        }
+    }
+
+    canonical.extend(lists.into_iter().map(|(split, list)| NimRule {
+        take: TakeSize::List(list),
+        split,
+    }));
+
+    if let Some(split) = split((any.0 && !across, any.1)) {
+        canonical.push(NimRule {
+            take: TakeSize::Any,
+            split,
+        });
+    }
+
+    if across {
+        canonical.push(NimRule {
+            take: TakeSize::AnyFromStacks(stacks),
+            split: Split::Never,
+        });
+    } else if same_from_two {
+        canonical.push(NimRule {
+            take: TakeSize::SameFromTwoStacks,
+            split: Split::Never,
+        });
+    }
+
+    // Taking any number of coins covers taking up to a multiple of the previous move
+    canonical.extend(multiple_rules((
+        if any.0 { 0 } else { multiples.0 },
+        if any.1 { 0 } else { multiples.1 },
+    )));
+
+    // Moving coins to any stack before covers moving them to the stack right before
+    if transfer_to_lower {
+        canonical.push(NimRule {
+            take: TakeSize::TransferToLower,
+            split: Split::Never,
+        });
+    } else if transfer_to_neighbour {
+        canonical.push(NimRule {
+            take: TakeSize::TransferToNeighbour,
+            split: Split::Never,
+        });
+    }
+
+    canonical.sort();
+    canonical.dedup();
+    canonical
+}
+
+/// Calculate the FNV-1a hash of a fixed encoding of the (canonical) rules for all players and of single players
+fn fingerprint(rules: &[NimRule], player_rules: &[(Player, Vec<NimRule>)]) -> u64 {
+    let mut bytes = Vec::new();
+    encode(rules, &mut bytes);
+
+    // Only appended for partizan rule sets, so the fingerprints of other rule sets stay the same
+    for (player, rules) in player_rules {
+        bytes.push(8);
+        bytes.extend_from_slice(&(player.0 as u64).to_le_bytes());
+        bytes.extend_from_slice(&(rules.len() as u64).to_le_bytes());
+        encode(rules, &mut bytes);
+    }
+
+    fnv1a(&bytes)
+}
+
+/// Append a fixed encoding of (canonical) rules to some bytes
+fn encode(rules: &[NimRule], bytes: &mut Vec<u8>) {
+    for NimRule { take, split } in rules {
+        match take {
+            TakeSize::List(list) => {
+                bytes.push(0);
+                bytes.extend_from_slice(&(list.len() as u64).to_le_bytes());
+                for take in list {
+                    bytes.extend_from_slice(&take.to_le_bytes());
+                }
+            }
+            TakeSize::Any => bytes.push(1),
+            TakeSize::Place => bytes.push(2),
+            TakeSize::AnyFromStacks(stacks) => {
+                bytes.push(3);
+                bytes.extend_from_slice(&(*stacks as u64).to_le_bytes());
+            }
+            TakeSize::SameFromTwoStacks => bytes.push(4),
+            TakeSize::UpToMultipleOfLast(multiple) => {
+                bytes.push(5);
+                bytes.extend_from_slice(&multiple.to_le_bytes());
+            }
+            TakeSize::TransferToNeighbour => bytes.push(6),
+            TakeSize::TransferToLower => bytes.push(7),
+        }
+
+        bytes.push(match split {
+            Split::Never => 0,
+            Split::Optional => 1,
+            Split::Always => 2,
+        });
+    }
+}
+
+/// Calculate the 64-bit FNV-1a hash of some bytes
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    /// The FNV-1a offset basis for 64-bit hashes
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+    /// The FNV-1a prime for 64-bit hashes
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
@@ -0,0 +1,486 @@
+//! A brute-force solver for whole positions, independent of the Sprague-Grundy theory.
+//! This module searches the game tree of a position to find out if the player to move wins,
+//! and can cross-check the nimbers calculated by [`crate::nimbers`] (see [`verify`]).
+
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    moves, CanonicalPosition, Coins, Compound, NimAction, NimGame, Nimber, PlayConvention, Player,
+    RuleSet, Stack, TakeSize,
+};
+
+/// The outcome of a position for the player whose turn it is, assuming perfect play
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Outcome {
+    /// The player whose turn it is wins (an _N-position_)
+    Win,
+
+    /// The player whose turn it is loses (a _P-position_)
+    Loss,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Loss => write!(f, "loss"),
+        }
+    }
+}
+
+/// Errors which may occur when solving a position
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub enum SolveError {
+    /// The rules allow placing coins, so positions may repeat and the game may never end
+    PlaceRule,
+
+    /// The rules are not those of Moore's Nim, see [`crate::moore::solve`]
+    NotMooreNim,
+
+    /// The rules are not those of Wythoff's game, see [`crate::wythoff::solve`]
+    NotWythoff,
+
+    /// The rules are not those of Fibonacci Nim, see [`crate::fibonacci::solve`]
+    NotFibonacciNim,
+
+    /// The rules are not those of Staircase Nim, see [`crate::staircase::solve`]
+    NotStaircaseNim,
+
+    /// The position has more non-empty stacks than the solver can handle
+    TooManyStacks {
+        /// The number of non-empty stacks
+        stack_count: usize,
+
+        /// The largest number of non-empty stacks the solver can handle
+        max_stacks: usize,
+    },
+
+    /// The game has more than two players, so the player to move doesn't simply win or lose
+    /// (see [`crate::multiplayer`] for such games)
+    NotTwoPlayers {
+        /// The number of players
+        player_count: usize,
+    },
+
+    /// Li's ranking of the players by their last moves is only defined under the normal play convention,
+    /// see [`crate::multiplayer::RankingSolver`]
+    MisereRanking,
+
+    /// The player doesn't take part in the game
+    NoSuchPlayer {
+        /// The player
+        player: Player,
+
+        /// The number of players of the game
+        player_count: usize,
+    },
+
+    /// The rules are partizan, so the positions have no nimbers (see [`crate::partizan`] for their values)
+    Partizan,
+
+    /// The rules allow moves which involve several stacks or depend on the previous move,
+    /// so a position is not the sum of its stacks, see [`crate::partizan::value`] and [`verify`]
+    NotStackwise,
+
+    /// The values of combinatorial game theory only describe games under the normal play convention,
+    /// see [`crate::partizan::value`]
+    MisereValue,
+
+    /// The stacks are not a disjunctive sum, so the values (or nimbers) of the stacks don't add up,
+    /// see [`crate::compound`]
+    NotDisjunctive {
+        /// How the stacks are combined
+        compound: Compound,
+    },
+
+    /// The stacks are a disjunctive sum, whose outcome follows from the nimbers of the stacks
+    /// (see [`NimGame::calculate_nimber`]) rather than from [`crate::compound::solve`]
+    Disjunctive,
+
+    /// The outcomes of the single stacks only decide selective sums under the normal play convention,
+    /// see [`crate::compound::solve`]
+    MisereSelective,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::PlaceRule => write!(
+                f,
+                "Can't solve games with rules which allow placing coins, as positions may repeat"
+            ),
+            SolveError::NotMooreNim => write!(
+                f,
+                "Only games of Moore's Nim (taking any number of coins from up to k stacks) can be solved with Moore's theorem"
+            ),
+            SolveError::NotWythoff => write!(
+                f,
+                "Only games of Wythoff's game (taking any number of coins from one stack, or the same number from two stacks) can be solved with Wythoff's theorem"
+            ),
+            SolveError::NotFibonacciNim => write!(
+                f,
+                "Only games of Fibonacci Nim (taking up to twice the coins of the previous move) can be solved with Zeckendorf representations"
+            ),
+            SolveError::NotStaircaseNim => write!(
+                f,
+                "Only games of Staircase Nim (moving coins onto the stack before) can be solved with the odd steps"
+            ),
+            SolveError::TooManyStacks {
+                stack_count,
+                max_stacks,
+            } => write!(
+                f,
+                "Can't solve positions with {stack_count} non-empty stacks, only up to {max_stacks}"
+            ),
+            SolveError::NotTwoPlayers { player_count } => write!(
+                f,
+                "Only games of 2 players can be won or lost by the player to move, not games of {player_count} players"
+            ),
+            SolveError::MisereRanking => write!(
+                f,
+                "Players can only be ranked by their last moves under the normal play convention"
+            ),
+            SolveError::NoSuchPlayer {
+                player,
+                player_count,
+            } => write!(
+                f,
+                "There is no player {player}, as the game has {player_count} players"
+            ),
+            SolveError::Partizan => write!(
+                f,
+                "The players have different rules, so the positions have no nimbers"
+            ),
+            SolveError::NotStackwise => write!(
+                f,
+                "Can't add up the values of the stacks, as the rules allow moves which involve several stacks or depend on the previous move"
+            ),
+            SolveError::MisereValue => write!(
+                f,
+                "Positions only have values under the normal play convention"
+            ),
+            SolveError::NotDisjunctive { compound } => write!(
+                f,
+                "The stacks are a {compound} sum, so their values don't add up"
+            ),
+            SolveError::Disjunctive => write!(
+                f,
+                "The stacks are a disjunctive sum, whose outcome follows from the nimbers of the stacks"
+            ),
+            SolveError::MisereSelective => write!(
+                f,
+                "Selective sums can only be solved from the outcomes of their stacks under the normal play convention"
+            ),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// A brute-force solver, which remembers the outcomes of the positions it has solved
+///
+/// The solver searches the game tree using only the legal moves of the positions
+/// (see [`moves::calculate_legal_moves`]), without any nimbers:
+/// a position is won if there is a move to a lost position,
+/// and a position without moves is lost under the normal play convention (won under misère play).
+/// The outcomes are remembered by the canonical form of the positions (see [`CanonicalPosition`]),
+/// so solving many positions with the same rules reuses the outcomes of earlier searches.
+///
+/// The search is iterative, so deep game trees don't overflow the stack,
+/// but the number of positions grows quickly with the heights and the number of stacks.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{solver::{Outcome, Solver}, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::Any,
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// let mut solver = Solver::new();
+///
+/// let game = NimGame::new(rules.clone(), vec![Stack(1), Stack(2), Stack(3)]);
+/// assert_eq!(solver.solve(&game), Ok(Outcome::Loss));
+///
+/// let game = NimGame::new(rules, vec![Stack(1), Stack(2), Stack(4)]);
+/// assert_eq!(solver.solve(&game), Ok(Outcome::Win));
+/// ```
+///
+/// [`Solver::new`] creates a solver for games counting coins with [u64]s,
+/// use [`Solver::default`] for other types of [`Coins`].
+#[derive(Clone, Debug)]
+pub struct Solver<C = u64> {
+    /// The outcomes of the solved positions
+    outcomes: HashMap<CanonicalPosition<C>, Outcome>,
+}
+
+/// A position in the search of [`Solver::solve`], whose moves are being tried
+struct Frame<C> {
+    /// The position
+    game: NimGame<C>,
+
+    /// The legal moves of the position
+    moves: Vec<NimAction<C>>,
+
+    /// The index of the next move to try
+    next: usize,
+
+    /// Whether a move to a lost position was found
+    won: bool,
+}
+
+impl<C> Default for Solver<C> {
+    fn default() -> Self {
+        Self {
+            outcomes: HashMap::new(),
+        }
+    }
+}
+
+impl Solver {
+    /// Create a solver which doesn't know any outcomes yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C: Coins> Solver<C> {
+    /// Get the number of positions whose outcomes are known
+    #[must_use]
+    pub fn solved_positions(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Find the outcome of a position for the player whose turn it is
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+    /// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a generated legal move can't be applied, as the outcome would be that of a pruned game tree
+    /// (which is what cross-checking with the solver is meant to reveal).
+    pub fn solve(&mut self, game: &NimGame<C>) -> Result<Outcome, SolveError> {
+        check_searchable(game)?;
+        check_two_players(game)?;
+
+        let mut root = game.clone();
+        root.set_stack_ids(false);
+        root.nimbers = None;
+        root.set_auto_normalize(true);
+
+        let root_key = root.canonical_position();
+        if let Some(&outcome) = self.outcomes.get(&root_key) {
+            return Ok(outcome);
+        }
+
+        let mut frames = vec![Frame::new(root)];
+
+        while let Some(frame) = frames.last_mut() {
+            // Try the next move, unless the outcome is known already
+            if !frame.won && frame.next < frame.moves.len() {
+                let mut child = frame.game.clone();
+                // A generated move which can't be applied is a bug, which must not prune the search
+                moves::apply_move(&mut child, &frame.moves[frame.next])
+                    .expect("Legal moves can be applied");
+                frame.next += 1;
+
+                match self.outcomes.get(&child.canonical_position()) {
+                    Some(Outcome::Loss) => frame.won = true,
+                    Some(Outcome::Win) => {}
+                    None => frames.push(Frame::new(child)),
+                }
+                continue;
+            }
+
+            let outcome = match (frame.moves.is_empty(), frame.game.get_convention()) {
+                // The player who can't move wins under misère play, and loses under normal play
+                (true, PlayConvention::Misere) => Outcome::Win,
+                (false, _) if frame.won => Outcome::Win,
+                (true, PlayConvention::Normal) | (false, _) => Outcome::Loss,
+            };
+            self.outcomes
+                .insert(frame.game.canonical_position(), outcome);
+            frames.pop();
+
+            if let (Some(parent), Outcome::Loss) = (frames.last_mut(), outcome) {
+                parent.won = true;
+            }
+        }
+
+        Ok(self.outcomes[&root_key])
+    }
+}
+
+/// Check if the game tree of a position can be searched, i.e. if the rules don't allow placing coins
+pub(crate) fn check_searchable<C: Coins>(game: &NimGame<C>) -> Result<(), SolveError> {
+    if game
+        .get_rules()
+        .all_rules()
+        .any(|rule| rule.take == TakeSize::Place)
+    {
+        Err(SolveError::PlaceRule)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check if the moves of the rules only involve a single stack and don't depend on the previous move,
+/// so a position is the sum of its stacks
+pub(crate) fn check_stackwise(rules: &RuleSet) -> Result<(), SolveError> {
+    for rule in rules.all_rules() {
+        match rule.take {
+            TakeSize::List(_) | TakeSize::Any => {}
+            TakeSize::Place => return Err(SolveError::PlaceRule),
+            TakeSize::AnyFromStacks(_)
+            | TakeSize::SameFromTwoStacks
+            | TakeSize::UpToMultipleOfLast(_)
+            | TakeSize::TransferToNeighbour
+            | TakeSize::TransferToLower => return Err(SolveError::NotStackwise),
+        }
+    }
+    Ok(())
+}
+
+/// Check if a game has two players, so the player to move wins or loses
+pub(crate) fn check_two_players<C: Coins>(game: &NimGame<C>) -> Result<(), SolveError> {
+    match game.get_player_count() {
+        2 => Ok(()),
+        player_count => Err(SolveError::NotTwoPlayers { player_count }),
+    }
+}
+
+impl<C: Coins> Frame<C> {
+    /// Start trying the moves of a position
+    fn new(game: NimGame<C>) -> Self {
+        Self {
+            moves: game.calculate_legal_moves(),
+            game,
+            next: 0,
+            won: false,
+        }
+    }
+}
+
+/// Find the outcome of a position for the player whose turn it is, see [`Solver`]
+///
+/// # Errors
+///
+/// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn solve<C: Coins>(game: &NimGame<C>) -> Result<Outcome, SolveError> {
+    Solver::default().solve(game)
+}
+
+/// A position whose nimber disagrees with its outcome found by the [`Solver`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Discrepancy {
+    /// The stacks of the position
+    pub stacks: Vec<Stack>,
+
+    /// The nimber of the position, see [`NimGame::calculate_nimber`]
+    pub nimber: Nimber,
+
+    /// The outcome of the position for the player whose turn it is, found by the [`Solver`]
+    pub outcome: Outcome,
+}
+
+/// The result of [`verify`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Verification {
+    /// The number of positions which were checked
+    pub positions: usize,
+
+    /// The positions whose nimbers disagree with their outcomes
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Cross-check the nimbers of all positions up to the given bounds with the [`Solver`]
+///
+/// Every position with up to `max_stacks` stacks of heights `1..=max_height` is solved
+/// (under the normal play convention), and its outcome is compared with its nimber:
+/// by the Sprague-Grundy theorem, the player to move wins if and only if the nimber is not 0.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{solver::verify, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Optional,
+/// }])
+/// .unwrap();
+///
+/// let verification = verify(&rules, 6, 3).unwrap();
+///
+/// // The positions with 0 to 3 stacks of heights 1 to 6
+/// assert_eq!(verification.positions, 84);
+/// assert!(verification.discrepancies.is_empty());
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::PlaceRule`] if the rules allow placing coins,
+/// [`SolveError::Partizan`] if the rules are partizan,
+/// or [`SolveError::NotStackwise`] if the rules allow moves which the nimbers don't take into account
+/// (see [`NimGame::calculate_nimber`]).
+pub fn verify(
+    rules: &RuleSet,
+    max_height: u64,
+    max_stacks: usize,
+) -> Result<Verification, SolveError> {
+    if rules.is_partizan() {
+        return Err(SolveError::Partizan);
+    }
+    check_stackwise(rules)?;
+
+    let mut solver = Solver::new();
+    let mut verification = Verification {
+        positions: 0,
+        discrepancies: Vec::new(),
+    };
+
+    // Visit the stacks of every position in non-decreasing order of heights
+    let mut stacks: Vec<Stack> = Vec::with_capacity(max_stacks);
+    loop {
+        let game = NimGame::new(rules.clone(), stacks.clone());
+        let outcome = solver.solve(&game)?;
+        let nimber = game.calculate_nimber();
+
+        verification.positions += 1;
+        if (nimber != Nimber(0)) != (outcome == Outcome::Win) {
+            verification.discrepancies.push(Discrepancy {
+                stacks: stacks.clone(),
+                nimber,
+                outcome,
+            });
+        }
+
+        // Add another stack, or increase the last stack which is not at the maximum height
+        if stacks.len() < max_stacks && max_height > 0 {
+            stacks.push(Stack(stacks.last().map_or(1, |stack| stack.0)));
+            continue;
+        }
+
+        while stacks.last().is_some_and(|stack| stack.0 == max_height) {
+            stacks.pop();
+        }
+
+        let Some(last) = stacks.last_mut() else {
+            break;
+        };
+        last.0 += 1;
+    }
+
+    Ok(verification)
+}
@@ -0,0 +1,227 @@
+//! Staircase Nim, where coins are moved from a stack onto the stack before it.
+//! The stacks are the steps of a staircase, and the first stack is the ground, from which no coins can be moved.
+//! Moves between stacks break the Sprague-Grundy decomposition into single stacks,
+//! so the outcomes of such games are found by reducing them to Nim on the odd steps instead (see [`solve`]).
+
+use crate::{
+    solver::{check_two_players, Outcome, SolveError},
+    Coins, NimAction, NimGame, NimRule, Nimber, PlayConvention, RuleSet, Split, TakeSize,
+    TransferAction,
+};
+
+/// Check if the rules are those of Staircase Nim
+///
+/// The rules of Staircase Nim are a single [`TakeSize::TransferToNeighbour`] rule.
+/// Games with [`TakeSize::TransferToLower`] rules, where coins may skip steps, are not Staircase Nim:
+/// the odd steps don't decide their outcome, so they can only be solved by brute force (see [`crate::solver`]).
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{staircase, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::TransferToNeighbour,
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// assert!(staircase::is_staircase_nim(&rules));
+/// ```
+#[must_use]
+pub fn is_staircase_nim(rules: &RuleSet) -> bool {
+    !rules.is_partizan()
+        && rules.rules()
+            == [NimRule {
+                take: TakeSize::TransferToNeighbour,
+                split: Split::Never,
+            }]
+}
+
+/// Find the outcome of a position of Staircase Nim for the player whose turn it is
+///
+/// Moving coins from an even step onto an odd step can be undone by the opponent,
+/// who moves the same coins on to the next even step.
+/// So only the odd steps (the second, fourth, ... stack) count, and moving coins from an odd step onto the even step
+/// before it is the same as taking them from a Nim stack:
+/// the position is lost under normal play if and only if the XOR of the heights of the odd steps is 0.
+///
+/// Under misère play, the odd steps are played like misère Nim:
+/// if every odd step holds at most 1 coin, the position is lost if and only if the number of such steps is odd;
+/// all other positions have the same outcome as under normal play.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{solver::Outcome, staircase, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::TransferToNeighbour,
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// // The odd steps hold 3 and 3 coins
+/// let game = NimGame::new(rules.clone(), vec![Stack(0), Stack(3), Stack(5), Stack(3)]);
+/// assert_eq!(staircase::solve(&game), Ok(Outcome::Loss));
+///
+/// // The player to move moves 1 coin from the fourth stack onto the third
+/// let game = NimGame::new(rules, vec![Stack(0), Stack(2), Stack(5), Stack(3)]);
+/// assert_eq!(staircase::solve(&game), Ok(Outcome::Win));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotStaircaseNim`] if the rules of the game are not those of Staircase Nim,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn solve<C: Coins>(game: &NimGame<C>) -> Result<Outcome, SolveError> {
+    let steps = odd_steps(game)?;
+
+    let lost = if misere_ending(game, &steps) {
+        steps.iter().filter(|(_, height)| !height.is_zero()).count() % 2 == 1
+    } else {
+        xor(&steps).is_zero()
+    };
+
+    Ok(if lost { Outcome::Loss } else { Outcome::Win })
+}
+
+/// Find a winning move of a position of Staircase Nim for the player whose turn it is, if there is one
+///
+/// The move takes coins from an odd step like a winning move of (misère) Nim (see [`solve`]),
+/// except under misère play when no odd step holds a coin: then a single coin is moved from an even step onto an odd step.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{staircase, NimAction, NimGame, NimRule, RuleSet, Split, Stack, TakeSize, TransferAction};
+///
+/// let rules = RuleSet::new([NimRule {
+///     take: TakeSize::TransferToNeighbour,
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// let game = NimGame::new(rules, vec![Stack(0), Stack(2), Stack(5), Stack(3)]);
+/// assert_eq!(
+///     staircase::winning_move(&game),
+///     Ok(Some(NimAction::Transfer(TransferAction {
+///         stack_index: 3,
+///         target_index: 2,
+///         amount: 1,
+///         stack_id: None,
+///         target_id: None,
+///     })))
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotStaircaseNim`] if the rules of the game are not those of Staircase Nim,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn winning_move<C: Coins>(game: &NimGame<C>) -> Result<Option<NimAction<C>>, SolveError> {
+    let steps = odd_steps(game)?;
+
+    let transfer = if misere_ending(game, &steps) {
+        let ones = steps.iter().filter(|(_, height)| !height.is_zero()).count();
+        match steps.iter().find(|(_, height)| !height.is_zero()) {
+            // Leave an odd number of odd steps with a coin
+            Some(&(stack_index, _)) if ones % 2 == 0 => Some((stack_index, C::from(1))),
+            Some(_) => None,
+            // Move a coin onto an odd step, if there is an even step (above the ground) with coins
+            None => game
+                .get_stacks()
+                .iter()
+                .enumerate()
+                .skip(2)
+                .step_by(2)
+                .find(|(_, stack)| !stack.0.is_zero())
+                .map(|(stack_index, _)| (stack_index, C::from(1))),
+        }
+    } else {
+        nim_take(game, &steps)
+    };
+
+    Ok(transfer.map(|(stack_index, amount)| {
+        let ids = game.get_stack_ids();
+        NimAction::Transfer(TransferAction {
+            stack_index,
+            target_index: stack_index - 1,
+            amount,
+            stack_id: ids.and_then(|ids| ids.get(stack_index)).copied(),
+            target_id: ids.and_then(|ids| ids.get(stack_index - 1)).copied(),
+        })
+    }))
+}
+
+/// Calculate the nimber of a position of Staircase Nim (under normal play)
+///
+/// The nimber is the XOR of the heights of the odd steps, see [`solve`].
+/// Unlike [`NimGame::calculate_nimber`], this takes the moves between the stacks into account.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotStaircaseNim`] if the rules of the game are not those of Staircase Nim,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn nimber<C: Coins>(game: &NimGame<C>) -> Result<Nimber<C>, SolveError> {
+    Ok(Nimber(xor(&odd_steps(game)?)))
+}
+
+/// Get the indices and heights of the odd steps (the second, fourth, ... stack) of a game of Staircase Nim
+fn odd_steps<C: Coins>(game: &NimGame<C>) -> Result<Vec<(usize, C)>, SolveError> {
+    if !is_staircase_nim(game.get_rules()) {
+        return Err(SolveError::NotStaircaseNim);
+    }
+    check_two_players(game)?;
+
+    Ok(game
+        .get_stacks()
+        .iter()
+        .enumerate()
+        .skip(1)
+        .step_by(2)
+        .map(|(stack_index, stack)| (stack_index, stack.0.clone()))
+        .collect())
+}
+
+/// Check if a position is played like the end of misère Nim, i.e. every odd step holds at most 1 coin
+fn misere_ending<C: Coins>(game: &NimGame<C>, steps: &[(usize, C)]) -> bool {
+    game.get_convention() == PlayConvention::Misere
+        && steps.iter().all(|(_, height)| height.bit_len() <= 1)
+}
+
+/// Calculate the XOR of the heights of the odd steps
+fn xor<C: Coins>(steps: &[(usize, C)]) -> C {
+    steps
+        .iter()
+        .fold(C::from(0), |xor, (_, height)| xor.xor(height))
+}
+
+/// Find the odd step and the number of coins to move from it to win like in Nim
+/// (unless every odd step holds at most 1 coin under misère play, see [`misere_ending`])
+fn nim_take<C: Coins>(game: &NimGame<C>, steps: &[(usize, C)]) -> Option<(usize, C)> {
+    let large: Vec<&(usize, C)> = steps
+        .iter()
+        .filter(|(_, height)| height.bit_len() > 1)
+        .collect();
+
+    // With a single step of more than 1 coin under misère play, leave an odd number of steps with a single coin
+    if let (PlayConvention::Misere, [(stack_index, height)]) = (game.get_convention(), &large[..]) {
+        let ones = steps
+            .iter()
+            .filter(|(_, height)| height.bit_len() == 1)
+            .count();
+        let keep = C::from(u64::from(ones % 2 == 0));
+        return Some((*stack_index, height.try_sub(&keep)?));
+    }
+
+    let sum = xor(steps);
+    steps.iter().find_map(|(stack_index, height)| {
+        let target = height.xor(&sum);
+        if target < *height {
+            Some((*stack_index, height.try_sub(&target)?))
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,343 @@
+//! Sums of games, whose components each have their own rules.
+//! A move is made in exactly one component, and the nimber of the sum is the XOR of the nimbers of its components.
+
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    moves::{self, MoveError, StackChanges, ValidatedMove},
+    Coins, GameError, NimAction, NimGame, Nimber, Player,
+};
+
+/// # A sum of games
+///
+/// Every component is a [`NimGame`] with its own rules (e.g. a row of Kayles and a heap of a subtraction game),
+/// and the player whose turn it is makes a move in one of the components.
+/// The components share the player whose turn it is (see [`GameSum::get_to_move`]);
+/// everything else (the stacks, the pools, stack IDs, ...) is kept by the components.
+/// The components are meant to have the same number of players;
+/// otherwise the players of the sum are those taking part in every component (see [`GameSum::get_player_count`]).
+///
+/// By the Sprague-Grundy theorem, the nimber of the sum is the XOR of the nimbers of its components
+/// (see [`GameSum::try_calculate_nimber`]), each calculated with the nimber cache of its rules.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{GameSum, NimGame, NimRule, Nimber, RuleSet, Split, Stack, TakeSize};
+///
+/// let kayles = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 2]),
+///     split: Split::Optional,
+/// }])
+/// .unwrap();
+/// let subtraction = RuleSet::new([NimRule {
+///     take: TakeSize::List(vec![1, 3]),
+///     split: Split::Never,
+/// }])
+/// .unwrap();
+///
+/// let sum = GameSum::new(vec![
+///     NimGame::new(kayles, vec![Stack(5)]),
+///     NimGame::new(subtraction, vec![Stack(6)]),
+/// ]);
+///
+/// // *4 (Kayles) + *0 (subtraction game)
+/// assert_eq!(sum.calculate_nimber(), Nimber(4));
+/// assert_eq!(sum.calculate_legal_moves().len(), 7);
+/// ```
+///
+/// Sums are compared (and hashed) by their components and the player whose turn it is,
+/// not by the moves which led to them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "SumData<C>", bound = "C: Coins")]
+pub struct GameSum<C = u64> {
+    /// The component games
+    components: Vec<NimGame<C>>,
+
+    /// The player whose turn it is (in every component)
+    to_move: Player,
+
+    /// The components of the moves applied to the sum, in the order they were applied
+    /// (so only the last move of the sum is undone, see [`GameSum::undo_move`]),
+    /// together with the player the component passed the turn to
+    /// (which differs from the next player of the sum if the component has more players)
+    #[serde(skip)]
+    history: Vec<(usize, Player)>,
+}
+
+impl<C: PartialEq> PartialEq for GameSum<C> {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.components, self.to_move) == (&other.components, other.to_move)
+    }
+}
+
+impl<C: Eq> Eq for GameSum<C> {}
+
+impl<C: PartialOrd> PartialOrd for GameSum<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (&self.components, self.to_move).partial_cmp(&(&other.components, other.to_move))
+    }
+}
+
+impl<C: Ord> Ord for GameSum<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.components, self.to_move).cmp(&(&other.components, other.to_move))
+    }
+}
+
+impl<C: Hash> Hash for GameSum<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (&self.components, self.to_move).hash(state);
+    }
+}
+
+/// The serialized form of a [`GameSum`], whose components are brought in line with the player to move
+#[derive(Deserialize)]
+#[serde(bound = "C: Coins")]
+struct SumData<C> {
+    /// See [`GameSum::components`]
+    components: Vec<NimGame<C>>,
+
+    /// See [`GameSum::to_move`]
+    #[serde(default)]
+    to_move: Player,
+}
+
+impl<C: Coins> TryFrom<SumData<C>> for GameSum<C> {
+    type Error = GameError<C>;
+
+    fn try_from(data: SumData<C>) -> Result<Self, Self::Error> {
+        let mut sum = GameSum {
+            components: data.components,
+            to_move: data.to_move,
+            history: Vec::new(),
+        };
+        sum.set_to_move(data.to_move)?;
+        Ok(sum)
+    }
+}
+
+/// A move in a [`GameSum`]: a move in one of its components
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SumAction<C = u64> {
+    /// The index of the component to move in
+    pub component: usize,
+
+    /// The move within the component
+    pub action: NimAction<C>,
+}
+
+impl<C: Coins> GameSum<C> {
+    /// Create the sum of the given games
+    ///
+    /// Player A moves first in the sum, regardless of the player to move in the components.
+    #[must_use]
+    pub fn new(components: Vec<NimGame<C>>) -> Self {
+        let mut sum = Self {
+            components,
+            to_move: Player::A,
+            history: Vec::new(),
+        };
+        sum.pass_turn(Player::A);
+        sum
+    }
+
+    /// Get the component games
+    #[must_use]
+    pub fn get_components(&self) -> &[NimGame<C>] {
+        &self.components
+    }
+
+    /// Get the player whose turn it is
+    #[must_use]
+    pub fn get_to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Get the number of players of the sum, i.e. the smallest number of players of a component
+    /// (or 2 if the sum has no components)
+    #[must_use]
+    pub fn get_player_count(&self) -> usize {
+        self.components
+            .iter()
+            .map(NimGame::get_player_count)
+            .min()
+            .unwrap_or(2)
+    }
+
+    /// Set the player whose turn it is (in every component)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::NoSuchPlayer`] if the player doesn't take part in the sum.
+    /// The sum is not changed if an error is returned.
+    pub fn set_to_move(&mut self, player: Player) -> Result<(), GameError<C>> {
+        let player_count = self.get_player_count();
+        if player.0 >= player_count {
+            return Err(GameError::NoSuchPlayer {
+                player,
+                player_count,
+            });
+        }
+
+        self.pass_turn(player);
+        Ok(())
+    }
+
+    /// Pass the turn to a player of the sum (in every component)
+    fn pass_turn(&mut self, player: Player) {
+        self.to_move = player;
+        for component in &mut self.components {
+            component.to_move = player;
+        }
+    }
+
+    /// Get a component, or a [`MoveError::NoSuchComponent`] error if it doesn't exist
+    fn get_component(&self, component: usize) -> Result<&NimGame<C>, MoveError<C>> {
+        self.components
+            .get(component)
+            .ok_or(MoveError::NoSuchComponent {
+                component,
+                component_count: self.components.len(),
+            })
+    }
+
+    /// Generate all legal moves of the player whose turn it is, in all components
+    ///
+    /// See [`NimGame::calculate_legal_moves`] for the moves within a component.
+    #[must_use]
+    pub fn calculate_legal_moves(&self) -> Vec<SumAction<C>> {
+        self.components
+            .iter()
+            .enumerate()
+            .flat_map(|(component, game)| {
+                game.calculate_legal_moves()
+                    .into_iter()
+                    .map(move |action| SumAction { component, action })
+            })
+            .collect()
+    }
+
+    /// Determine if a move is valid for the sum, see [`moves::check_move`]
+    ///
+    /// The returned [`ValidatedMove`] was validated for the component the move refers to,
+    /// and can be applied with [`GameSum::apply_validated_move`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MoveError::NoSuchComponent`] if there is no such component,
+    /// or an error if the move is invalid within the component (see [`MoveError`]).
+    pub fn check_move(&self, mov: &SumAction<C>) -> Result<ValidatedMove<C>, MoveError<C>> {
+        moves::check_move(self.get_component(mov.component)?, &mov.action)
+    }
+
+    /// Applies a move to the sum, if the move is valid
+    ///
+    /// The move is applied to its component, and the turn passes to the next player in every component.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MoveError::NoSuchComponent`] if there is no such component,
+    /// or an error if the move is invalid within the component (see [`moves::apply_move`]).
+    /// The sum is not changed if an error is returned.
+    pub fn apply_move(&mut self, mov: &SumAction<C>) -> Result<StackChanges<C>, MoveError<C>> {
+        let validated = self.check_move(mov)?;
+        self.apply_validated_move(mov.component, validated)
+    }
+
+    /// Applies a move which was already checked with [`GameSum::check_move`] to the given component,
+    /// without checking it again (see [`moves::apply_validated_move`])
+    ///
+    /// Just like [`GameSum::apply_move`], the turn passes to the next player in every component.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MoveError::NoSuchComponent`] if there is no such component,
+    /// or [`MoveError::StaleMove`] if the move was checked for a different component or position
+    /// (see [`moves::apply_validated_move`]).
+    /// The sum is not changed if an error is returned.
+    pub fn apply_validated_move(
+        &mut self,
+        component: usize,
+        mov: ValidatedMove<C>,
+    ) -> Result<StackChanges<C>, MoveError<C>> {
+        self.get_component(component)?;
+
+        let changes = moves::apply_validated_move(&mut self.components[component], mov)?;
+        self.history
+            .push((component, self.components[component].get_to_move()));
+        self.pass_turn(self.to_move.next(self.get_player_count()));
+
+        Ok(changes)
+    }
+
+    /// Reverts the last move of the sum, which was made in the given component (see [`moves::undo_move`])
+    ///
+    /// Only the last move of the sum can be undone, so the moves are undone in the reverse order
+    /// in which they were applied (deserialized sums have no moves to undo).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MoveError::NoSuchComponent`] if there is no such component,
+    /// or [`MoveError::StaleMove`] if the changes don't belong to the last move of the sum.
+    /// The sum is not changed if an error is returned.
+    pub fn undo_move(
+        &mut self,
+        component: usize,
+        changes: StackChanges<C>,
+    ) -> Result<(), MoveError<C>> {
+        self.get_component(component)?;
+
+        // The last move in the component has to be the last move of the sum as well
+        let Some(&(last, to_move)) = self.history.last() else {
+            return Err(MoveError::StaleMove);
+        };
+        if last != component {
+            return Err(MoveError::StaleMove);
+        }
+
+        // The move is undone in the component as it was left by the move
+        let game = &mut self.components[component];
+        let sum_to_move = game.to_move;
+        game.to_move = to_move;
+        if let Err(error) = moves::undo_move(game, changes) {
+            game.to_move = sum_to_move;
+            return Err(error);
+        }
+        self.history.pop();
+        self.pass_turn(self.components[component].get_to_move());
+
+        Ok(())
+    }
+
+    /// Calculate the nimber of the sum, the XOR of the nimbers of its components
+    ///
+    /// See [`NimGame::try_calculate_nimber`] for the nimbers of the components
+    /// (under the normal play convention).  
+    /// Sums of games counting coins with [u64]s can use [`GameSum::calculate_nimber`], which can't fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GameError::StackTooHigh`] if a stack is higher than [`u64::MAX`].
+    pub fn try_calculate_nimber(&self) -> Result<Nimber<C>, GameError<C>> {
+        self.components
+            .iter()
+            .map(NimGame::try_calculate_nimber)
+            .sum()
+    }
+}
+
+impl GameSum {
+    /// Calculate the nimber of the sum, the XOR of the nimbers of its components
+    ///
+    /// See [`GameSum::try_calculate_nimber`], which can't fail for stacks counted with [u64]s.
+    #[must_use]
+    pub fn calculate_nimber(&self) -> Nimber {
+        self.components.iter().map(NimGame::calculate_nimber).sum()
+    }
+}
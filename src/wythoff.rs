@@ -0,0 +1,290 @@
+//! Wythoff's game, where any number of coins may be taken from one stack,
+//! or the same number of coins from two stacks in a single move.
+//! The lost positions are the pairs of Beatty sequences found by Wythoff (see [`p_positions`] and [`solve`]),
+//! and the nimbers of small boards are calculated by brute force (see [`nimbers`]).
+
+use std::collections::VecDeque;
+
+use crate::{
+    nimbers::to_index,
+    solver::{check_two_players, Outcome, SolveError},
+    Coins, NimGame, NimRule, Nimber, PlayConvention, RuleSet, Split, TakeSize,
+};
+
+/// Check if the rules are those of Wythoff's game
+///
+/// The rules of Wythoff's game are a [`TakeSize::Any`] rule and a [`TakeSize::SameFromTwoStacks`] rule,
+/// both without splitting.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{wythoff, NimRule, RuleSet, Split, TakeSize};
+///
+/// let rules = RuleSet::new([
+///     NimRule {
+///         take: TakeSize::SameFromTwoStacks,
+///         split: Split::Never,
+///     },
+///     NimRule {
+///         take: TakeSize::Any,
+///         split: Split::Never,
+///     },
+/// ])
+/// .unwrap();
+///
+/// assert!(wythoff::is_wythoff(&rules));
+/// ```
+#[must_use]
+pub fn is_wythoff(rules: &RuleSet) -> bool {
+    !rules.is_partizan()
+        && rules.rules()
+            == [
+                NimRule {
+                    take: TakeSize::Any,
+                    split: Split::Never,
+                },
+                NimRule {
+                    take: TakeSize::SameFromTwoStacks,
+                    split: Split::Never,
+                },
+            ]
+}
+
+/// Iterate over the lost positions of Wythoff's game with two stacks (under normal play)
+///
+/// The `n`-th pair is `(⌊nφ⌋, ⌊nφ²⌋)`, where `φ` is the golden ratio.
+/// By Beatty's theorem, these two sequences are complementary: every positive number appears in exactly one of them.
+/// The pairs are calculated exactly with integers, using that `⌊nφ⌋` is the smallest number which doesn't appear
+/// in an earlier pair, and `⌊nφ²⌋ = ⌊nφ⌋ + n`.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::wythoff;
+///
+/// let pairs: Vec<(u64, u64)> = wythoff::p_positions().take(5).collect();
+/// assert_eq!(pairs, vec![(0, 0), (1, 2), (3, 5), (4, 7), (6, 10)]);
+/// ```
+pub fn p_positions() -> impl Iterator<Item = (u64, u64)> {
+    // The upper numbers of the pairs which may still be reached by the lower numbers
+    let mut uppers: VecDeque<u64> = VecDeque::new();
+
+    (0_u64..).scan(0_u64, move |lower, n| {
+        if n > 0 {
+            *lower += 1;
+            while uppers.front().is_some_and(|&upper| upper < *lower) {
+                uppers.pop_front();
+            }
+            // Consecutive upper numbers are at least 2 apart
+            if uppers.front() == Some(lower) {
+                uppers.pop_front();
+                *lower += 1;
+            }
+        }
+
+        let upper = *lower + n;
+        uppers.push_back(upper);
+        Some((*lower, upper))
+    })
+}
+
+/// Check if a position with two stacks of Wythoff's game is lost for the player to move (under normal play)
+///
+/// The position is lost if and only if it is one of the pairs of [`p_positions`] (in either order).
+/// This is checked without enumerating the pairs, using the Zeckendorf representations of the heights
+/// (as sums of non-consecutive Fibonacci numbers 1, 2, 3, 5, 8, ...):
+/// `⌊nφ⌋` are the numbers whose representation doesn't use an odd number of the smallest Fibonacci numbers
+/// (i.e. ends in an even number of zeros), and `⌊nφ²⌋` is `⌊nφ⌋` with every Fibonacci number replaced by the next one.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::wythoff;
+///
+/// assert!(wythoff::is_p_position(&7_u64, &4));
+/// assert!(!wythoff::is_p_position(&7_u64, &5));
+/// assert!(wythoff::is_p_position(&10_610_209_857_723_u64, &17_167_680_177_565));
+/// ```
+pub fn is_p_position<C: Coins>(a: &C, b: &C) -> bool {
+    let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+
+    // The Fibonacci numbers up to the first one above the lower height (if it can be counted)
+    let mut fibonacci = vec![C::from(1), C::from(2)];
+    while fibonacci[fibonacci.len() - 1] <= *lower {
+        let len = fibonacci.len();
+        match fibonacci[len - 2].try_add(&fibonacci[len - 1]) {
+            Some(next) => fibonacci.push(next),
+            None => break,
+        }
+    }
+
+    // Find the Zeckendorf representation greedily, and shift it by one place
+    let mut rest = lower.clone();
+    let mut shifted = Some(C::from(0));
+    let mut smallest = None;
+    for (i, number) in fibonacci.iter().enumerate().rev() {
+        if let Some(remainder) = rest.try_sub(number) {
+            rest = remainder;
+            smallest = Some(i);
+            shifted = shifted.and_then(|shifted| shifted.try_add(fibonacci.get(i + 1)?));
+        }
+    }
+
+    match smallest {
+        None => upper.is_zero(),
+        Some(smallest) => smallest % 2 == 0 && shifted.as_ref() == Some(upper),
+    }
+}
+
+/// Find the outcome of a position of Wythoff's game for the player whose turn it is
+///
+/// Positions with up to two non-empty stacks are lost under normal play if and only if they are one of the
+/// [`p_positions`] (see [`is_p_position`]).
+/// Under misère play, the lost positions are the same, except that `(0, 1)` and `(2, 2)`
+/// are lost instead of `(0, 0)` and `(1, 2)`.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{solver::Outcome, wythoff, NimGame, NimRule, RuleSet, Split, Stack, TakeSize};
+///
+/// let rules = RuleSet::new([
+///     NimRule {
+///         take: TakeSize::Any,
+///         split: Split::Never,
+///     },
+///     NimRule {
+///         take: TakeSize::SameFromTwoStacks,
+///         split: Split::Never,
+///     },
+/// ])
+/// .unwrap();
+///
+/// let game = NimGame::new(rules.clone(), vec![Stack(3), Stack(5)]);
+/// assert_eq!(wythoff::solve(&game), Ok(Outcome::Loss));
+///
+/// // The player to move takes 2 coins from both stacks, leaving (1, 2)
+/// let game = NimGame::new(rules, vec![Stack(3), Stack(4)]);
+/// assert_eq!(wythoff::solve(&game), Ok(Outcome::Win));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotWythoff`] if the rules of the game are not those of Wythoff's game,
+/// [`SolveError::TooManyStacks`] if more than two stacks are non-empty,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn solve<C: Coins>(game: &NimGame<C>) -> Result<Outcome, SolveError> {
+    let (a, b) = two_stacks(game)?;
+
+    let lost = match game.get_convention() {
+        PlayConvention::Normal => is_p_position(&a, &b),
+        PlayConvention::Misere => {
+            let (lower, upper) = if a <= b { (&a, &b) } else { (&b, &a) };
+            match (lower.to_u64(), upper.to_u64()) {
+                (Some(0), Some(1)) | (Some(2), Some(2)) => true,
+                (Some(0), Some(0)) | (Some(1), Some(2)) => false,
+                _ => is_p_position(lower, upper),
+            }
+        }
+    };
+
+    Ok(if lost { Outcome::Loss } else { Outcome::Win })
+}
+
+/// Get the heights of the (up to) two non-empty stacks of a game of Wythoff's game
+fn two_stacks<C: Coins>(game: &NimGame<C>) -> Result<(C, C), SolveError> {
+    if !is_wythoff(game.get_rules()) {
+        return Err(SolveError::NotWythoff);
+    }
+    check_two_players(game)?;
+
+    let stacks: Vec<&C> = game
+        .get_stacks()
+        .iter()
+        .map(|stack| &stack.0)
+        .filter(|height| !height.is_zero())
+        .collect();
+
+    match stacks.as_slice() {
+        [] => Ok((C::from(0), C::from(0))),
+        [a] => Ok((C::from(0), (*a).clone())),
+        [a, b] => Ok(((*a).clone(), (*b).clone())),
+        _ => Err(SolveError::TooManyStacks {
+            stack_count: stacks.len(),
+            max_stacks: 2,
+        }),
+    }
+}
+
+/// Calculate the nimbers of all boards of Wythoff's game with two stacks up to (and including) `max_height`
+///
+/// The nimber of the board with stacks of `a` and `b` coins is `nimbers[a][b]` (under normal play).
+/// Unlike the lost positions, the nimbers don't follow a known formula,
+/// so they are calculated with the MEX rule, which takes `O(max_height³)` time.
+/// They are needed for sums of several boards, whose nimber is the XOR of the nimbers of the boards.
+///
+/// # Examples
+///
+/// ```
+/// use nimlib::{wythoff, Nimber};
+///
+/// let nimbers = wythoff::nimbers(3);
+///
+/// assert_eq!(nimbers[0][3], Nimber(3));
+/// assert_eq!(nimbers[1][2], Nimber(0));
+/// assert_eq!(nimbers[2][2], Nimber(1));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the table doesn't fit into the addressable memory.
+#[must_use]
+pub fn nimbers(max_height: u64) -> Vec<Vec<Nimber>> {
+    let size = to_index(max_height) + 1;
+
+    let mut nimbers = vec![vec![Nimber(0); size]; size];
+    let mut reachable = Vec::new();
+
+    for a in 0..size {
+        for b in a..size {
+            // The options of a board are found in the rows and columns of its stacks, and on its diagonal
+            let options = (0..a)
+                .map(|lower| nimbers[lower][b])
+                .chain((0..b).map(|lower| nimbers[a.min(lower)][a.max(lower)]))
+                .chain((1..=a).map(|taken| nimbers[a - taken][b - taken]));
+
+            reachable.clear();
+            reachable.resize(a + b + 1, false);
+            // Every option is below the number of options
+            for Nimber(option) in options {
+                reachable[to_index(option)] = true;
+            }
+
+            let mex = reachable
+                .iter()
+                .position(|&seen| !seen)
+                .unwrap_or(a + b + 1) as u64;
+            nimbers[a][b] = Nimber(mex);
+            nimbers[b][a] = Nimber(mex);
+        }
+    }
+
+    nimbers
+}
+
+/// Calculate the nimber of a position of Wythoff's game (under normal play), see [`nimbers`]
+///
+/// Unlike [`NimGame::calculate_nimber`], this takes the moves across both stacks into account.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotWythoff`] if the rules of the game are not those of Wythoff's game,
+/// [`SolveError::TooManyStacks`] if more than two stacks are non-empty,
+/// or [`SolveError::NotTwoPlayers`] if the game has more than two players.
+pub fn nimber(game: &NimGame) -> Result<Nimber, SolveError> {
+    let (a, b) = two_stacks(game)?;
+    let nimbers = nimbers(a.max(b));
+
+    Ok(nimbers[to_index(a)][to_index(b)])
+}
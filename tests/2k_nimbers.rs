@@ -21,7 +21,7 @@ fn extended_nimber_test() {
 
     for (h, nimber) in TEST_NIMBERS.iter().enumerate() {
         assert_eq!(
-            Stack(h as u64).calculate_nimber(&fancy_rules, 0),
+            Stack(h as u64).calculate_nimber(&fancy_rules, 0).unwrap(),
             Nimber(*nimber as u64),
             "Nimber for height {h} is wrong"
         );
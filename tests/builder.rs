@@ -0,0 +1,177 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use nimlib::{
+    moves, GameError, NimAction, NimGame, NimRule, NimSplit, PlayConvention, Player, RuleSet,
+    Split, Stack, TakeAction, TakeSize,
+};
+
+fn take_one() -> NimRule {
+    NimRule {
+        take: TakeSize::List(vec![1]),
+        split: Split::Never,
+    }
+}
+
+fn place() -> NimRule {
+    NimRule {
+        take: TakeSize::Place,
+        split: Split::Never,
+    }
+}
+
+#[test]
+fn builder_sets_every_part() {
+    let game = NimGame::builder()
+        .rules([place(), take_one()])
+        .stacks(vec![Stack(3), Stack(4)])
+        .pool_coins(Player::A, 1)
+        .pool_coins(Player::B, 2)
+        .to_move(Player::B)
+        .convention(PlayConvention::Misere)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        game.get_rules(),
+        &RuleSet::new([take_one(), place()]).unwrap()
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(3), Stack(4)]);
+    assert_eq!(game.get_pool_coins(Player::A), 1);
+    assert_eq!(game.get_pool_coins(Player::B), 2);
+    assert_eq!(game.get_to_move(), Player::B);
+    assert_eq!(game.get_convention(), PlayConvention::Misere);
+}
+
+#[test]
+fn moves_keep_the_pools_valid() {
+    let mut game = NimGame::builder()
+        .rules([take_one()])
+        .stacks(vec![Stack(5)])
+        .build()
+        .unwrap();
+
+    // Without a place rule, the taken coins don't go to the pool named by the move
+    let take = NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: 1,
+        split: NimSplit::No,
+        from: Some(Player::A),
+        stack_id: None,
+    });
+    moves::apply_move(&mut game, &take).unwrap();
+    assert_eq!(game.get_pools(), &[0, 0]);
+
+    let json = serde_json::to_string(&game).unwrap();
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), game);
+}
+
+#[test]
+fn builder_defaults_match_new() {
+    let rules = RuleSet::new([take_one()]).unwrap();
+    let built = NimGame::builder()
+        .rules([take_one()])
+        .stacks(vec![Stack(5)])
+        .build()
+        .unwrap();
+
+    assert_eq!(built, NimGame::new(rules, vec![Stack(5)]));
+    assert_eq!(built.get_to_move(), Player::A);
+    assert_eq!(built.get_convention(), PlayConvention::Normal);
+}
+
+#[test]
+fn builder_validates() {
+    assert_eq!(NimGame::builder().build(), Err(GameError::MissingRules));
+
+    assert!(matches!(
+        NimGame::builder()
+            .rules([NimRule {
+                take: TakeSize::List(vec![]),
+                split: Split::Never,
+            }])
+            .build(),
+        Err(GameError::InvalidRules(_))
+    ));
+
+    assert_eq!(
+        NimGame::builder()
+            .rules([take_one()])
+            .pool_coins(Player::B, 3)
+            .build(),
+        Err(GameError::PoolsWithoutPlaceRule { pools: vec![0, 3] })
+    );
+}
+
+#[test]
+fn mutators_keep_the_game_valid() {
+    let mut game = NimGame::builder()
+        .rules([take_one(), place()])
+        .pool_coins(Player::A, 2)
+        .build()
+        .unwrap();
+
+    // Pools with coins need a place rule
+    let take_only = RuleSet::new([take_one()]).unwrap();
+    assert!(game.set_rules(take_only.clone()).is_err());
+    assert_eq!(game.get_rules().len(), 2);
+
+    game.set_pool_coins(Player::A, 0).unwrap();
+    game.set_rules(take_only).unwrap();
+    assert_eq!(
+        game.set_pool_coins(Player::B, 1),
+        Err(GameError::PoolsWithoutPlaceRule { pools: vec![0, 1] })
+    );
+    assert_eq!(game.get_pool_coins(Player::B), 0);
+
+    game.set_stacks(vec![Stack(7)]).unwrap();
+    game.set_to_move(Player::B).unwrap();
+    game.set_convention(PlayConvention::Misere);
+    assert_eq!(game.get_stacks(), &vec![Stack(7)]);
+    assert_eq!(game.get_to_move(), Player::B);
+    assert_eq!(game.get_convention(), PlayConvention::Misere);
+}
+
+#[test]
+fn bookkeeping_does_not_make_games_differ() {
+    let plain = NimGame::builder()
+        .rules([take_one()])
+        .stacks(vec![Stack(1), Stack(3)])
+        .build()
+        .unwrap();
+    let tracked = NimGame::builder()
+        .rules([take_one()])
+        .stacks(vec![Stack(1), Stack(3)])
+        .stack_ids(true)
+        .auto_normalize(true)
+        .track_nimber(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(plain, tracked);
+    assert_eq!(plain.cmp(&tracked), std::cmp::Ordering::Equal);
+
+    let hash = |game: &NimGame| {
+        let mut hasher = DefaultHasher::new();
+        game.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&plain), hash(&tracked));
+}
+
+#[test]
+fn deserializing_validates_like_the_builder() {
+    // Pools with coins need a place rule
+    let json =
+        r#"{"rules":[{"rule":{"TakeList":[1]},"split":"Never"}],"stacks":[3],"pools":[0,2]}"#;
+    assert!(serde_json::from_str::<NimGame>(json)
+        .unwrap_err()
+        .to_string()
+        .contains("the rules don't allow placing coins"));
+
+    // Rules of a player who doesn't take part
+    let json = r#"{"rules":{"rules":[],"players":[{"player":"C","rules":[{"rule":{"TakeList":[1]},"split":"Never"}]}]},"stacks":[3]}"#;
+    assert!(serde_json::from_str::<NimGame>(json)
+        .unwrap_err()
+        .to_string()
+        .contains("There is no player C"));
+}
@@ -0,0 +1,192 @@
+use nimlib::{
+    moves::{calculate_legal_moves, check_move, MoveError},
+    NimAction, NimGame, NimRule, NimSplit, PlaceAction, Player, RuleSet, Split, Stack, TakeAction,
+    TakeSize,
+};
+
+fn game(rules: Vec<NimRule>, stacks: Vec<Stack>) -> NimGame {
+    NimGame::new(RuleSet::new(rules).unwrap(), stacks)
+}
+
+fn take(amount: u64, split: NimSplit) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount,
+        split,
+        from: None,
+        stack_id: None,
+    })
+}
+
+fn justify(game: &NimGame, mov: &NimAction) -> Result<NimRule, MoveError> {
+    check_move(game, mov).map(|mov| mov.rule().clone())
+}
+
+fn rule(take: TakeSize, split: Split) -> NimRule {
+    NimRule { take, split }
+}
+
+#[test]
+fn coins_are_conserved() {
+    let game = game(
+        vec![rule(TakeSize::List(vec![1]), Split::Optional)],
+        vec![Stack(7)],
+    );
+
+    assert!(justify(&game, &take(1, NimSplit::Yes(Stack(2), Stack(4)))).is_ok());
+
+    // Coins must neither vanish nor appear
+    for (a, b) in [(2, 3), (1, 1), (3, 4), (0, 6)] {
+        assert_eq!(
+            justify(&game, &take(1, NimSplit::Yes(Stack(a), Stack(b)))),
+            Err(MoveError::InvalidSplit {
+                stack_index: 0,
+                remainder: 6,
+                split: (Stack(a), Stack(b))
+            }),
+            "Split into {a} and {b} must be invalid"
+        );
+    }
+
+    assert_eq!(
+        justify(&game, &take(1, NimSplit::Yes(Stack(u64::MAX), Stack(8)))),
+        Err(MoveError::InvalidSplit {
+            stack_index: 0,
+            remainder: 6,
+            split: (Stack(u64::MAX), Stack(8))
+        })
+    );
+}
+
+#[test]
+fn split_is_checked_against_the_same_rule() {
+    let game = game(
+        vec![
+            rule(TakeSize::List(vec![1, 2]), Split::Never),
+            rule(TakeSize::List(vec![2]), Split::Always),
+        ],
+        vec![Stack(6)],
+    );
+
+    // Taking 1 coin is only allowed without a split
+    assert_eq!(
+        justify(&game, &take(1, NimSplit::Yes(Stack(2), Stack(3)))),
+        Err(MoveError::SplitNotAllowed { amount: 1 })
+    );
+
+    // Taking 2 coins is allowed with and without a split (merged into a single canonical rule)
+    for split in [NimSplit::No, NimSplit::Yes(Stack(1), Stack(3))] {
+        assert_eq!(
+            justify(&game, &take(2, split)),
+            Ok(rule(TakeSize::List(vec![2]), Split::Optional))
+        );
+    }
+
+    assert_eq!(
+        justify(&game, &take(3, NimSplit::No)),
+        Err(MoveError::NoSuchRule { amount: 3 })
+    );
+}
+
+#[test]
+fn later_rules_are_considered() {
+    let game = game(
+        vec![
+            rule(TakeSize::List(vec![1]), Split::Never),
+            rule(TakeSize::Any, Split::Always),
+        ],
+        vec![Stack(5)],
+    );
+
+    assert_eq!(
+        justify(&game, &take(1, NimSplit::No)),
+        Ok(rule(TakeSize::List(vec![1]), Split::Never))
+    );
+    assert_eq!(
+        justify(&game, &take(1, NimSplit::Yes(Stack(2), Stack(2)))),
+        Ok(rule(TakeSize::Any, Split::Always))
+    );
+}
+
+#[test]
+fn take_any_requires_coins() {
+    let game = game(vec![rule(TakeSize::Any, Split::Never)], vec![Stack(3)]);
+
+    assert_eq!(
+        justify(&game, &take(0, NimSplit::No)),
+        Err(MoveError::NoSuchRule { amount: 0 })
+    );
+    assert_eq!(
+        justify(&game, &take(4, NimSplit::No)),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 0,
+            amount: 4,
+            available: 3
+        })
+    );
+    assert_eq!(
+        justify(&game, &take(3, NimSplit::No)),
+        Ok(rule(TakeSize::Any, Split::Never))
+    );
+}
+
+#[test]
+fn legal_moves_are_valid() {
+    let rules = vec![
+        rule(TakeSize::List(vec![1, 3]), Split::Never),
+        rule(TakeSize::List(vec![0, 2]), Split::Always),
+        rule(TakeSize::List(vec![4]), Split::Optional),
+    ];
+    let stacks = vec![Stack(3), Stack(8), Stack(1)];
+
+    let moves = calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
+    let game = game(rules, stacks);
+
+    assert!(!moves.is_empty());
+    for mov in moves {
+        assert!(justify(&game, &mov).is_ok(), "{mov:?} must be valid");
+    }
+}
+
+#[test]
+fn errors_have_context() {
+    let game = game(
+        vec![rule(TakeSize::List(vec![2]), Split::Always)],
+        vec![Stack(5), Stack(1)],
+    );
+
+    let no_stack = NimAction::Take(TakeAction {
+        stack_index: 2,
+        amount: 2,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    });
+    let error = justify(&game, &no_stack).unwrap_err();
+    assert_eq!(
+        error,
+        MoveError::NoSuchStack {
+            stack_index: 2,
+            stack_count: 2
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "There is no stack 2; the position has 2 stack(s)"
+    );
+
+    let error = justify(&game, &take(2, NimSplit::No)).unwrap_err();
+    assert_eq!(error, MoveError::SplitRequired { amount: 2 });
+    assert_eq!(
+        error.to_string(),
+        "The rules require splitting the stack after taking 2 coin(s)"
+    );
+
+    let place = NimAction::Place(PlaceAction {
+        stack_index: 0,
+        amount: 1,
+        from: Player::B,
+        stack_id: None,
+    });
+    assert_eq!(justify(&game, &place), Err(MoveError::NoPlaceRule));
+}
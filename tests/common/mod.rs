@@ -0,0 +1,43 @@
+//! Fixtures shared by the integration tests
+// Every test crate compiles this module, but not every test uses every fixture
+#![allow(dead_code)]
+
+use nimlib::Stack;
+
+/// All positions with up to `max_stacks` stacks of up to `max_height` coins, in non-decreasing order
+pub fn positions(max_stacks: usize, max_height: u64) -> Vec<Vec<Stack>> {
+    let mut positions = vec![vec![]];
+    let mut last = vec![vec![]];
+    for _ in 0..max_stacks {
+        last = last
+            .into_iter()
+            .flat_map(|stacks: Vec<Stack>| {
+                let lowest = stacks.last().map_or(1, |stack| stack.0);
+                (lowest..=max_height).map(move |height| {
+                    let mut stacks = stacks.clone();
+                    stacks.push(Stack(height));
+                    stacks
+                })
+            })
+            .collect();
+        positions.extend(last.iter().cloned());
+    }
+    positions
+}
+
+/// All positions with exactly `stack_count` stacks of up to `max_height` coins (including empty stacks),
+/// in every order
+pub fn sequences(stack_count: usize, max_height: u64) -> Vec<Vec<Stack>> {
+    (0..stack_count).fold(vec![vec![]], |positions, _| {
+        positions
+            .into_iter()
+            .flat_map(|stacks| {
+                (0..=max_height).map(move |height| {
+                    let mut stacks = stacks.clone();
+                    stacks.push(Stack(height));
+                    stacks
+                })
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,270 @@
+mod common;
+
+use common::positions;
+use nimlib::{
+    compound,
+    moves::{self, MoveError},
+    partizan,
+    solver::{SolveError, Solver},
+    Compound, CompoundAction, GameError, NimAction, NimGame, NimRule, NimSplit, PlayConvention,
+    Player, RuleSet, Split, Stack, TakeAction, TakeSize,
+};
+
+fn rule(list: &[u64], split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::List(list.to_vec()),
+        split,
+    }
+}
+
+fn take(stack_index: usize, amount: u64) -> TakeAction {
+    TakeAction {
+        stack_index,
+        amount,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    }
+}
+
+fn game(rules: Vec<NimRule>, compound: Compound, stacks: Vec<Stack>) -> NimGame {
+    NimGame::builder()
+        .rules(rules)
+        .compound(compound)
+        .stacks(stacks)
+        .build()
+        .unwrap()
+}
+
+fn rule_sets() -> Vec<Vec<NimRule>> {
+    vec![
+        vec![rule(&[1, 2], Split::Never)],
+        vec![rule(&[2, 3], Split::Never)],
+        vec![rule(&[1], Split::Never), rule(&[2, 3], Split::Always)],
+        vec![rule(&[1, 3], Split::Optional)],
+        vec![NimRule {
+            take: TakeSize::Any,
+            split: Split::Never,
+        }],
+    ]
+}
+
+#[test]
+fn conjunctive_moves_involve_every_stack() {
+    let rules = vec![rule(&[1, 2], Split::Never)];
+    let mut game = game(
+        rules,
+        Compound::Conjunctive,
+        vec![Stack(1), Stack(0), Stack(2)],
+    );
+
+    // The empty stack doesn't allow a move
+    let compound = |takes| NimAction::Compound(CompoundAction { takes });
+    assert_eq!(
+        game.calculate_legal_moves(),
+        vec![
+            compound(vec![take(0, 1), take(2, 1)]),
+            compound(vec![take(0, 1), take(2, 2)]),
+        ]
+    );
+
+    assert_eq!(
+        moves::check_move(&game, &NimAction::Take(take(2, 1))),
+        Err(MoveError::MissingStack { stack_index: 0 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &compound(vec![take(0, 1), take(0, 1)])),
+        Err(MoveError::DuplicateStack { stack_index: 0 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &compound(vec![take(0, 2), take(2, 2)])),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 0,
+            amount: 2,
+            available: 1
+        })
+    );
+
+    let changes = moves::apply_move(&mut game, &compound(vec![take(2, 1), take(0, 1)])).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(0), Stack(0), Stack(1)]);
+    // The rules don't depend on the previous move, so the coins taken aren't recorded
+    assert_eq!(game.get_last_take(), None);
+
+    // A single stack is left, which is moved in by a simple take
+    assert_eq!(
+        game.calculate_legal_moves(),
+        vec![NimAction::Take(take(2, 1))]
+    );
+
+    moves::undo_move(&mut game, changes).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(0), Stack(2)]);
+}
+
+#[test]
+fn selective_moves_involve_any_stacks() {
+    let rules = vec![rule(&[1], Split::Never), rule(&[1], Split::Always)];
+    let mut game = game(rules, Compound::Selective, vec![Stack(1), Stack(3)]);
+
+    let moves = game.calculate_legal_moves();
+    // From the first stack, from the second stack (with or without a split), or from both
+    assert_eq!(moves.len(), 5);
+    assert_eq!(
+        moves
+            .iter()
+            .filter(|mov| matches!(mov, NimAction::Take(_)))
+            .count(),
+        3
+    );
+
+    let split = NimAction::Compound(CompoundAction {
+        takes: vec![
+            take(0, 1),
+            TakeAction {
+                split: NimSplit::Yes(Stack(1), Stack(1)),
+                ..take(1, 1)
+            },
+        ],
+    });
+    assert!(moves.contains(&split));
+    assert!(moves::check_move(&game, &NimAction::Take(take(1, 1))).is_ok());
+
+    game.set_stack_ids(true);
+    let changes = moves::apply_move(&mut game, &split).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(0), Stack(1), Stack(1)]);
+    assert_eq!(changes.created.len(), 2);
+
+    // Moves in several stacks are only allowed if the stacks are not a disjunctive sum
+    game.set_compound(Compound::Disjunctive).unwrap();
+    assert_eq!(
+        moves::apply_move(&mut game, &split),
+        Err(MoveError::NoSuchStackRule { stack_count: 2 })
+    );
+}
+
+#[test]
+fn compound_rules_take_from_single_stacks() {
+    let wythoff = vec![
+        NimRule {
+            take: TakeSize::Any,
+            split: Split::Never,
+        },
+        NimRule {
+            take: TakeSize::SameFromTwoStacks,
+            split: Split::Never,
+        },
+    ];
+
+    assert_eq!(
+        NimGame::builder()
+            .rules(wythoff.clone())
+            .compound(Compound::Selective)
+            .build(),
+        Err(GameError::CompoundRules {
+            compound: Compound::Selective
+        })
+    );
+
+    let mut game = NimGame::new(RuleSet::new(wythoff.clone()).unwrap(), vec![Stack(3)]);
+    assert!(game.set_compound(Compound::Conjunctive).is_err());
+    assert_eq!(game.get_compound(), Compound::Disjunctive);
+
+    let mut game = NimGame::default();
+    game.set_compound(Compound::Conjunctive).unwrap();
+    assert!(game.set_rules(RuleSet::new(wythoff).unwrap()).is_err());
+}
+
+#[test]
+fn compound_is_part_of_the_position() {
+    let disjunctive = NimGame::default();
+    let mut conjunctive = disjunctive.clone();
+    conjunctive.set_compound(Compound::Conjunctive).unwrap();
+
+    assert_ne!(
+        disjunctive.canonical_position(),
+        conjunctive.canonical_position()
+    );
+    assert_eq!(
+        conjunctive.canonical_position().compound(),
+        Compound::Conjunctive
+    );
+    // The fingerprints of disjunctive sums stay the same
+    assert_eq!(
+        disjunctive.canonical_position().fingerprint(),
+        17_026_919_640_025_319_834
+    );
+
+    // Only other sums than the disjunctive one are serialized
+    let json = serde_json::to_string(&disjunctive).unwrap();
+    assert!(!json.contains("compound"));
+    let json = serde_json::to_string(&conjunctive).unwrap();
+    assert!(json.contains(r#""compound":"Conjunctive""#));
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), conjunctive);
+}
+
+#[test]
+fn conjunctive_outcomes_agree_with_solver() {
+    for rules in rule_sets() {
+        for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+            let mut solver = Solver::new();
+
+            for stacks in positions(3, 6) {
+                let mut game = game(rules.clone(), Compound::Conjunctive, stacks.clone());
+                game.set_convention(convention);
+
+                assert_eq!(
+                    compound::solve(&game),
+                    solver.solve(&game),
+                    "{rules:?}, {convention:?}: {stacks:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn selective_outcomes_agree_with_solver() {
+    for rules in rule_sets() {
+        let mut solver = Solver::new();
+
+        for stacks in positions(3, 6) {
+            let game = game(rules.clone(), Compound::Selective, stacks.clone());
+
+            assert_eq!(
+                compound::solve(&game),
+                solver.solve(&game),
+                "{rules:?}: {stacks:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn compounds_are_solved_by_their_own_theory() {
+    let rules = vec![rule(&[1, 2], Split::Never)];
+
+    let disjunctive = game(rules.clone(), Compound::Disjunctive, vec![Stack(3)]);
+    assert_eq!(compound::solve(&disjunctive), Err(SolveError::Disjunctive));
+
+    let mut selective = game(rules.clone(), Compound::Selective, vec![Stack(3)]);
+    selective.set_convention(PlayConvention::Misere);
+    assert_eq!(
+        compound::solve(&selective),
+        Err(SolveError::MisereSelective)
+    );
+
+    let conjunctive = game(rules, Compound::Conjunctive, vec![Stack(3)]);
+    assert_eq!(
+        partizan::value(&conjunctive),
+        Err(SolveError::NotDisjunctive {
+            compound: Compound::Conjunctive
+        })
+    );
+
+    let partizan = NimGame::builder()
+        .player_rules(Player::A, [rule(&[1], Split::Never)])
+        .compound(Compound::Conjunctive)
+        .stacks(vec![Stack(3)])
+        .build()
+        .unwrap();
+    assert_eq!(compound::solve(&partizan), Err(SolveError::Partizan));
+}
@@ -0,0 +1,271 @@
+use nimlib::{
+    fibonacci,
+    moves::{self, MoveError},
+    solver::{Outcome, SolveError, Solver},
+    NimAction, NimGame, NimGameBuilder, NimRule, NimSplit, PlayConvention, RuleProblem, RuleSet,
+    Split, Stack, TakeAction, TakeSize,
+};
+
+fn up_to_multiple(multiple: u64, split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::UpToMultipleOfLast(multiple),
+        split,
+    }
+}
+
+fn fibonacci_nim() -> RuleSet {
+    RuleSet::new([up_to_multiple(2, Split::Never)]).unwrap()
+}
+
+fn take(amount: u64) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    })
+}
+
+#[test]
+fn canonical_rules() {
+    // The largest multiple covers the smaller ones
+    let rules = RuleSet::new([
+        up_to_multiple(2, Split::Never),
+        up_to_multiple(3, Split::Never),
+        up_to_multiple(3, Split::Always),
+    ])
+    .unwrap();
+    assert_eq!(rules.rules(), &[up_to_multiple(3, Split::Optional)]);
+    assert!(rules.depends_on_last_take());
+
+    let rules = RuleSet::new([
+        up_to_multiple(4, Split::Never),
+        up_to_multiple(2, Split::Optional),
+    ])
+    .unwrap();
+    assert_eq!(
+        rules.rules(),
+        &[
+            up_to_multiple(2, Split::Always),
+            up_to_multiple(4, Split::Never),
+        ]
+    );
+    assert_ne!(rules.fingerprint(), fibonacci_nim().fingerprint());
+
+    // Taking any number of coins covers the limited takes
+    let rules = RuleSet::new([
+        up_to_multiple(2, Split::Optional),
+        NimRule {
+            take: TakeSize::Any,
+            split: Split::Never,
+        },
+    ])
+    .unwrap();
+    assert_eq!(
+        rules.rules(),
+        &[
+            NimRule {
+                take: TakeSize::Any,
+                split: Split::Never,
+            },
+            up_to_multiple(2, Split::Always),
+        ]
+    );
+    assert!(!NimGame::default().get_rules().depends_on_last_take());
+
+    assert_eq!(
+        RuleSet::new([up_to_multiple(0, Split::Never)])
+            .unwrap_err()
+            .problems,
+        vec![RuleProblem::ZeroMultiple { rule: 0 }]
+    );
+}
+
+#[test]
+fn moves_depend_on_last_take() {
+    let mut game = NimGame::new(fibonacci_nim(), vec![Stack(10)]);
+
+    // The first move may take all coins but one
+    assert_eq!(game.calculate_legal_moves().len(), 9);
+    assert_eq!(
+        moves::check_move(&game, &take(10)),
+        Err(MoveError::TakeLimitExceeded {
+            amount: 10,
+            limit: 9,
+        })
+    );
+
+    moves::apply_move(&mut game, &take(2)).unwrap();
+    assert_eq!(game.get_last_take(), Some(2));
+    assert_eq!(
+        game.calculate_legal_moves(),
+        (1..=4).map(take).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        moves::check_move(&game, &take(5)),
+        Err(MoveError::TakeLimitExceeded {
+            amount: 5,
+            limit: 4,
+        })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take(0)),
+        Err(MoveError::NoSuchRule { amount: 0 })
+    );
+
+    // The stateless move generation takes the previous move as an argument
+    assert_eq!(
+        moves::calculate_legal_moves(
+            game.get_stacks(),
+            game.get_rules(),
+            &[0, 0],
+            game.get_to_move(),
+            Some(2)
+        )
+        .unwrap(),
+        game.calculate_legal_moves()
+    );
+
+    // A move checked before the previous move changed has to be checked again
+    let checked = moves::check_move(&game, &take(3)).unwrap();
+    game.set_last_take(Some(1));
+    assert_eq!(
+        moves::apply_validated_move(&mut game, checked),
+        Err(MoveError::StaleMove)
+    );
+}
+
+#[test]
+fn apply_and_undo_restore_last_take() {
+    let mut game = NimGameBuilder::new()
+        .rules([up_to_multiple(2, Split::Never)])
+        .stacks(vec![Stack(20)])
+        .last_take(Some(5))
+        .build()
+        .unwrap();
+    let before = game.clone();
+
+    let changes = moves::apply_move(&mut game, &take(7)).unwrap();
+    assert_eq!(game.get_last_take(), Some(7));
+    assert_eq!(game.get_stacks(), &vec![Stack(13)]);
+
+    moves::undo_move(&mut game, changes).unwrap();
+    assert_eq!(game, before);
+
+    // The previous move is part of the state, and of the canonical position
+    let json = serde_json::to_string(&game).unwrap();
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), game);
+    let mut other = game.clone();
+    other.set_last_take(Some(6));
+    assert_ne!(other.canonical_position(), game.canonical_position());
+}
+
+#[test]
+fn last_take_only_matters_to_rules_depending_on_it() {
+    let rules = RuleSet::new([NimRule {
+        take: TakeSize::List(vec![1, 2]),
+        split: Split::Never,
+    }])
+    .unwrap();
+    let mut game = NimGame::new(rules.clone(), vec![Stack(5)]);
+    moves::apply_move(&mut game, &take(2)).unwrap();
+    assert_eq!(game.get_last_take(), None);
+
+    // The game doesn't differ from one which got to the same stacks without a move
+    let mut fresh = NimGame::new(rules.clone(), vec![Stack(3)]);
+    fresh.set_to_move(game.get_to_move()).unwrap();
+    assert_eq!(game, fresh);
+
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(!json.contains("last_take"));
+
+    let built = NimGame::builder()
+        .rules([NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Never,
+        }])
+        .stacks(vec![Stack(3)])
+        .last_take(Some(2))
+        .build()
+        .unwrap();
+    assert_eq!(built.get_last_take(), None);
+
+    // Rules which don't depend on the previous move forget it
+    let mut fibonacci = NimGame::new(fibonacci_nim(), vec![Stack(5)]);
+    fibonacci.set_last_take(Some(2));
+    fibonacci.set_rules(rules).unwrap();
+    assert_eq!(fibonacci.get_last_take(), None);
+}
+
+#[test]
+fn zeckendorf_representations() {
+    assert_eq!(fibonacci::zeckendorf(&100_u64), vec![89, 8, 3]);
+    assert_eq!(fibonacci::zeckendorf(&1_u64), vec![1]);
+
+    // The largest Fibonacci number below u64::MAX
+    let largest = 12_200_160_415_121_876_738_u64;
+    assert_eq!(fibonacci::zeckendorf(&largest), vec![largest]);
+    assert_eq!(
+        fibonacci::zeckendorf(&u64::MAX).iter().sum::<u64>(),
+        u64::MAX
+    );
+}
+
+#[test]
+fn strategy_agrees_with_solver() {
+    for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+        let mut solver = Solver::new();
+
+        for height in 0..=30 {
+            for last_take in std::iter::once(None).chain((1..=16).map(Some)) {
+                let mut game = NimGame::new(fibonacci_nim(), vec![Stack(height)]);
+                game.set_convention(convention);
+                game.set_last_take(last_take);
+
+                let outcome = solver.solve(&game);
+                assert_eq!(
+                    fibonacci::solve(&game),
+                    outcome,
+                    "{convention:?}: {height} coin(s) after taking {last_take:?}"
+                );
+
+                // A player who wins by moving has a winning move, which leads to a lost position
+                let mov = fibonacci::winning_move(&game).unwrap();
+                let can_move = !game.calculate_legal_moves().is_empty();
+                assert_eq!(mov.is_some(), can_move && outcome == Ok(Outcome::Win));
+                if let Some(mov) = mov {
+                    let mut next = game.clone();
+                    moves::apply_move(&mut next, &mov).unwrap();
+                    assert_eq!(solver.solve(&next), Ok(Outcome::Loss));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn only_fibonacci_nim() {
+    assert_eq!(
+        fibonacci::solve(&NimGame::default()),
+        Err(SolveError::NotFibonacciNim)
+    );
+
+    let game = NimGame::new(fibonacci_nim(), vec![Stack(3), Stack(5)]);
+    assert_eq!(
+        fibonacci::solve(&game),
+        Err(SolveError::TooManyStacks {
+            stack_count: 2,
+            max_stacks: 1,
+        })
+    );
+
+    // Stacks beyond u64::MAX
+    let game = NimGameBuilder::<u128>::new()
+        .rules([up_to_multiple(2, Split::Never)])
+        .stacks(vec![Stack(u128::from(u64::MAX) * 2)])
+        .last_take(Some(u128::MAX))
+        .build()
+        .unwrap();
+    assert_eq!(fibonacci::solve(&game), Ok(Outcome::Win));
+}
@@ -0,0 +1,218 @@
+use nimlib::{
+    moves::MoveError, GameSum, NimAction, NimGame, NimRule, NimSplit, Nimber, Player, RuleSet,
+    Split, Stack, SumAction, TakeAction, TakeSize,
+};
+
+fn kayles() -> RuleSet {
+    RuleSet::new([NimRule {
+        take: TakeSize::List(vec![1, 2]),
+        split: Split::Optional,
+    }])
+    .unwrap()
+}
+
+fn subtraction() -> RuleSet {
+    RuleSet::new([NimRule {
+        take: TakeSize::List(vec![1, 3]),
+        split: Split::Never,
+    }])
+    .unwrap()
+}
+
+fn sum() -> GameSum {
+    GameSum::new(vec![
+        NimGame::new(kayles(), vec![Stack(5), Stack(2)]),
+        NimGame::new(subtraction(), vec![Stack(7)]),
+    ])
+}
+
+fn take(component: usize, stack_index: usize, amount: u64) -> SumAction {
+    SumAction {
+        component,
+        action: NimAction::Take(TakeAction {
+            stack_index,
+            amount,
+            split: NimSplit::No,
+            from: None,
+            stack_id: None,
+        }),
+    }
+}
+
+#[test]
+fn nimber_is_xor_of_components() {
+    let sum = sum();
+
+    let expected = sum
+        .get_components()
+        .iter()
+        .fold(Nimber(0), |nimber, game| nimber ^ game.calculate_nimber());
+    assert_eq!(sum.calculate_nimber(), expected);
+
+    // Kayles: *4 + *2, subtraction game: *1
+    assert_eq!(sum.calculate_nimber(), Nimber(7));
+}
+
+#[test]
+fn moves_of_all_components() {
+    let sum = sum();
+    let moves = sum.calculate_legal_moves();
+
+    for (component, game) in sum.get_components().iter().enumerate() {
+        let component_moves: Vec<NimAction> = moves
+            .iter()
+            .filter(|mov| mov.component == component)
+            .map(|mov| mov.action.clone())
+            .collect();
+        assert_eq!(component_moves, game.calculate_legal_moves());
+    }
+
+    for mov in &moves {
+        assert!(sum.check_move(mov).is_ok(), "{mov:?}");
+    }
+}
+
+#[test]
+fn moves_follow_the_rules_of_their_component() {
+    let mut sum = sum();
+
+    // Taking 3 coins is allowed in the subtraction game, but not in Kayles
+    assert_eq!(
+        sum.check_move(&take(0, 0, 3)),
+        Err(MoveError::NoSuchRule { amount: 3 })
+    );
+    assert!(sum.check_move(&take(1, 0, 3)).is_ok());
+
+    assert_eq!(
+        sum.apply_move(&take(2, 0, 1)),
+        Err(MoveError::NoSuchComponent {
+            component: 2,
+            component_count: 2,
+        })
+    );
+    assert_eq!(sum, self::sum());
+}
+
+#[test]
+fn turns_pass_in_every_component() {
+    let mut sum = sum();
+
+    sum.apply_move(&take(1, 0, 3)).unwrap();
+    assert_eq!(sum.get_to_move(), Player::B);
+    assert!(sum
+        .get_components()
+        .iter()
+        .all(|game| game.get_to_move() == Player::B));
+    assert_eq!(sum.get_components()[1].get_stacks(), &vec![Stack(4)]);
+
+    let changes = sum.apply_move(&take(0, 1, 2)).unwrap();
+    assert_eq!(sum.get_to_move(), Player::A);
+
+    sum.undo_move(0, changes).unwrap();
+    assert_eq!(sum.get_to_move(), Player::B);
+    assert_eq!(
+        sum.get_components()[0].get_stacks(),
+        &vec![Stack(5), Stack(2)]
+    );
+}
+
+#[test]
+fn zero_positions_only_move_to_nonzero_positions() {
+    // *4 (Kayles) + *0 + *1 (subtraction game)
+    let sum = GameSum::new(vec![
+        NimGame::new(kayles(), vec![Stack(5)]),
+        NimGame::new(subtraction(), vec![Stack(4), Stack(5)]),
+    ]);
+    assert_eq!(sum.calculate_nimber(), Nimber(4 ^ 1));
+
+    // *1 (Kayles) + *1 (subtraction game)
+    let sum = GameSum::new(vec![
+        NimGame::new(kayles(), vec![Stack(1)]),
+        NimGame::new(subtraction(), vec![Stack(5)]),
+    ]);
+    assert_eq!(sum.calculate_nimber(), Nimber(0));
+
+    for mov in sum.calculate_legal_moves() {
+        let mut next = sum.clone();
+        next.apply_move(&mov).unwrap();
+        assert_ne!(next.calculate_nimber(), Nimber(0), "{mov:?}");
+    }
+}
+
+#[test]
+fn serde_round_trip() {
+    let mut sum = sum();
+    sum.apply_move(&take(1, 0, 1)).unwrap();
+
+    let json = serde_json::to_string(&sum).unwrap();
+    assert_eq!(serde_json::from_str::<GameSum>(&json).unwrap(), sum);
+}
+
+#[test]
+fn only_the_last_move_of_the_sum_can_be_undone() {
+    let mut sum = sum();
+
+    let first = sum.apply_move(&take(0, 0, 1)).unwrap();
+    sum.apply_move(&take(1, 0, 1)).unwrap();
+    let third = sum.apply_move(&take(1, 0, 1)).unwrap();
+
+    // The move in component 0 is the last move of the component, but not of the sum
+    let after = sum.clone();
+    assert_eq!(sum.undo_move(0, first), Err(MoveError::StaleMove));
+    assert_eq!(sum, after);
+    assert_eq!(sum.get_to_move(), Player::B);
+
+    sum.undo_move(1, third).unwrap();
+    assert_eq!(sum.get_to_move(), Player::A);
+    assert_eq!(sum.get_components()[1].get_stacks(), &vec![Stack(6)]);
+}
+
+#[test]
+fn validated_moves_pass_the_turn() {
+    let mut sum = sum();
+
+    let mov = sum.check_move(&take(1, 0, 3)).unwrap();
+    let changes = sum.apply_validated_move(1, mov).unwrap();
+    assert_eq!(sum.get_to_move(), Player::B);
+    assert!(sum
+        .get_components()
+        .iter()
+        .all(|game| game.get_to_move() == Player::B));
+    assert_eq!(sum.get_components()[1].get_stacks(), &vec![Stack(4)]);
+
+    // The move was checked for component 1
+    let mov = sum.check_move(&take(1, 0, 1)).unwrap();
+    assert_eq!(sum.apply_validated_move(0, mov), Err(MoveError::StaleMove));
+
+    sum.undo_move(1, changes).unwrap();
+    assert_eq!(sum.get_to_move(), Player::A);
+}
+
+#[test]
+fn moves_in_components_with_more_players_can_be_undone() {
+    let three_players = NimGame::builder()
+        .rules([NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Optional,
+        }])
+        .players(3)
+        .stacks(vec![Stack(5)])
+        .build()
+        .unwrap();
+    let mut sum = GameSum::new(vec![
+        three_players,
+        NimGame::new(subtraction(), vec![Stack(7)]),
+    ]);
+    assert_eq!(sum.get_player_count(), 2);
+
+    sum.apply_move(&take(1, 0, 1)).unwrap();
+    let before = sum.clone();
+
+    // Player C of the first component doesn't take part in the sum, so the turn passes back to A
+    let changes = sum.apply_move(&take(0, 0, 2)).unwrap();
+    assert_eq!(sum.get_to_move(), Player::A);
+
+    sum.undo_move(0, changes).unwrap();
+    assert_eq!(sum, before);
+    assert_eq!(sum.get_to_move(), Player::B);
+}
@@ -0,0 +1,175 @@
+use nimlib::{
+    moves::{self, MoveError},
+    NimAction, NimGame, NimRule, NimSplit, Nimber, Split, Stack, TakeAction, TakeSize,
+};
+
+fn game(stack_ids: bool, auto_normalize: bool) -> NimGame {
+    NimGame::builder()
+        .rules([
+            NimRule {
+                take: TakeSize::List(vec![1, 2]),
+                split: Split::Optional,
+            },
+            NimRule {
+                take: TakeSize::List(vec![3]),
+                split: Split::Never,
+            },
+        ])
+        .stacks(vec![Stack(9), Stack(0), Stack(4), Stack(12), Stack(7)])
+        .stack_ids(stack_ids)
+        .auto_normalize(auto_normalize)
+        .track_nimber(true)
+        .build()
+        .unwrap()
+}
+
+/// Calculate the nimber of a position from scratch
+fn recalculate(game: &NimGame) -> Nimber {
+    game.get_stacks().iter().fold(Nimber(0), |nimber, stack| {
+        nimber ^ stack.calculate_nimber(game.get_rules(), 0).unwrap()
+    })
+}
+
+#[test]
+fn nimber_is_tracked_through_moves_and_undo() {
+    for (stack_ids, auto_normalize) in [(false, false), (true, false), (false, true), (true, true)]
+    {
+        let mut game = game(stack_ids, auto_normalize);
+        let mut history = Vec::new();
+
+        // Play a deterministic sequence of moves, including splits
+        let mut seed = 7_usize;
+        loop {
+            assert_eq!(game.get_nimber(), Some(recalculate(&game)));
+
+            let legal_moves = game.calculate_legal_moves();
+            if legal_moves.is_empty() {
+                break;
+            }
+
+            seed = (seed * 31 + 17) % 1009;
+            let mov = &legal_moves[seed % legal_moves.len()];
+
+            let before = game.clone();
+            let changes = moves::apply_move(&mut game, mov).unwrap();
+            history.push((before, changes));
+        }
+
+        // Undo every move, restoring every position exactly
+        while let Some((before, changes)) = history.pop() {
+            moves::undo_move(&mut game, changes).unwrap();
+            assert_eq!(game, before);
+            assert_eq!(game.get_stack_ids(), before.get_stack_ids());
+            assert_eq!(game.get_stack_nimbers(), before.get_stack_nimbers());
+        }
+    }
+}
+
+#[test]
+fn stack_nimbers_follow_the_stacks() {
+    let game = game(false, true);
+
+    assert_eq!(game.get_stacks().len(), 4);
+    assert_eq!(
+        game.get_stack_nimbers().unwrap(),
+        game.get_stacks()
+            .iter()
+            .map(|stack| stack.calculate_nimber(game.get_rules(), 0).unwrap())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn tracking_is_optional() {
+    let mut game = game(false, false);
+    let nimber = game.get_nimber();
+
+    game.set_track_nimber(false).unwrap();
+    assert_eq!(game.get_nimber(), None);
+    assert_eq!(Some(game.calculate_nimber()), nimber);
+
+    game.set_track_nimber(true).unwrap();
+    game.set_stacks(vec![Stack(1), Stack(2)]).unwrap();
+    assert_eq!(game.get_nimber(), Some(recalculate(&game)));
+}
+
+#[test]
+fn tracking_survives_serde() {
+    let game = game(true, false);
+    let json = serde_json::to_string(&game).unwrap();
+
+    assert!(json.contains(r#""track_nimber":true"#));
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), game);
+}
+
+#[test]
+fn stale_undo_is_an_error() {
+    let mut game = game(false, false);
+
+    let mov = game.calculate_legal_moves()[0].clone();
+    let first = moves::apply_move(&mut game, &mov).unwrap();
+    let mov = game.calculate_legal_moves()[0].clone();
+    let second = moves::apply_move(&mut game, &mov).unwrap();
+
+    // Moves have to be undone in reverse order
+    let after = game.clone();
+    assert_eq!(
+        moves::undo_move(&mut game, first),
+        Err(MoveError::StaleMove)
+    );
+    assert_eq!(game, after);
+
+    moves::undo_move(&mut game, second).unwrap();
+}
+
+#[test]
+fn only_the_last_move_can_be_undone() {
+    let take = |stack_index, amount| {
+        NimAction::Take(TakeAction {
+            stack_index,
+            amount,
+            split: NimSplit::No,
+            from: None,
+            stack_id: None,
+        })
+    };
+    let rules = [NimRule {
+        take: TakeSize::Any,
+        split: Split::Never,
+    }];
+
+    // Takes which keep the number of stacks and the turn order the same
+    let mut game = NimGame::builder()
+        .rules(rules.clone())
+        .stacks(vec![Stack(3), Stack(4)])
+        .build()
+        .unwrap();
+    let first = moves::apply_move(&mut game, &take(1, 1)).unwrap();
+    let second = moves::apply_move(&mut game, &take(1, 1)).unwrap();
+    moves::apply_move(&mut game, &take(0, 1)).unwrap();
+
+    let after = game.clone();
+    assert_eq!(
+        moves::undo_move(&mut game, first),
+        Err(MoveError::StaleMove)
+    );
+    assert_eq!(
+        moves::undo_move(&mut game, second),
+        Err(MoveError::StaleMove)
+    );
+    assert_eq!(game, after);
+
+    // Replacing the stacks after a move
+    let mut game = NimGame::builder()
+        .rules(rules)
+        .stacks(vec![Stack(3), Stack(4)])
+        .build()
+        .unwrap();
+    let changes = moves::apply_move(&mut game, &take(1, 1)).unwrap();
+    game.set_stacks(vec![Stack(9), Stack(9)]).unwrap();
+    assert_eq!(
+        moves::undo_move(&mut game, changes),
+        Err(MoveError::StaleMove)
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(9), Stack(9)]);
+}
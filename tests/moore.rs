@@ -0,0 +1,222 @@
+mod common;
+
+use common::sequences;
+use nimlib::{
+    moore,
+    moves::{self, MoveError},
+    solver::{Outcome, SolveError, Solver},
+    NimAction, NimGame, NimGameBuilder, NimRule, PlayConvention, RuleProblem, RuleSet, Split,
+    Stack, StackId, StackTake, TakeManyAction, TakeSize,
+};
+
+fn across(stacks: usize) -> NimRule {
+    NimRule {
+        take: TakeSize::AnyFromStacks(stacks),
+        split: Split::Never,
+    }
+}
+
+fn any(split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::Any,
+        split,
+    }
+}
+
+fn moore_nim(stacks: usize) -> RuleSet {
+    RuleSet::new([across(stacks)]).unwrap()
+}
+
+fn take_many(takes: &[(usize, u64)]) -> NimAction {
+    NimAction::TakeMany(TakeManyAction {
+        takes: takes
+            .iter()
+            .map(|&(stack_index, amount)| StackTake {
+                stack_index,
+                amount,
+                stack_id: None,
+            })
+            .collect(),
+        from: None,
+    })
+}
+
+#[test]
+fn canonical_rules() {
+    // Taking coins from a single stack is ordinary Nim
+    assert_eq!(moore_nim(1), RuleSet::new([any(Split::Never)]).unwrap());
+
+    // The rule allowing the most stacks covers the others, and all takes without splitting
+    let rules = RuleSet::new([
+        across(2),
+        across(3),
+        any(Split::Never),
+        NimRule {
+            take: TakeSize::List(vec![2]),
+            split: Split::Optional,
+        },
+    ])
+    .unwrap();
+    assert_eq!(
+        rules.rules(),
+        &[
+            NimRule {
+                take: TakeSize::List(vec![2]),
+                split: Split::Always,
+            },
+            across(3),
+        ]
+    );
+    assert_ne!(moore_nim(2).fingerprint(), moore_nim(3).fingerprint());
+
+    assert_eq!(
+        RuleSet::new([
+            across(0),
+            NimRule {
+                take: TakeSize::AnyFromStacks(2),
+                split: Split::Optional,
+            },
+        ])
+        .unwrap_err()
+        .problems,
+        vec![
+            RuleProblem::NoStacks { rule: 0 },
+            RuleProblem::SplitAcrossStacks { rule: 1 },
+        ]
+    );
+}
+
+#[test]
+fn moves_across_stacks() {
+    let game = NimGame::new(moore_nim(2), vec![Stack(1), Stack(2), Stack(1)]);
+    let moves = game.calculate_legal_moves();
+
+    // 4 moves from single stacks, and 2 + 1 + 2 moves from pairs of stacks
+    assert_eq!(moves.len(), 9);
+    assert!(moves.contains(&take_many(&[(0, 1), (1, 2)])));
+    assert!(moves.contains(&take_many(&[(0, 1), (2, 1)])));
+    for mov in &moves {
+        assert!(moves::check_move(&game, mov).is_ok(), "{mov:?}");
+    }
+
+    // Taking from 3 stacks at once is allowed with a rule for (at least) 3 stacks
+    let game = NimGame::new(moore_nim(3), vec![Stack(1), Stack(2), Stack(1)]);
+    assert_eq!(game.calculate_legal_moves().len(), 4 + 5 + 2);
+}
+
+#[test]
+fn invalid_moves_across_stacks() {
+    let game = NimGame::new(moore_nim(2), vec![Stack(2), Stack(3), Stack(4)]);
+
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(0, 1), (1, 1), (2, 1)])),
+        Err(MoveError::NoSuchStackRule { stack_count: 3 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[])),
+        Err(MoveError::NoSuchStackRule { stack_count: 0 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(1, 1), (1, 2)])),
+        Err(MoveError::DuplicateStack { stack_index: 1 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(0, 0), (1, 2)])),
+        Err(MoveError::NoSuchRule { amount: 0 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(0, 3), (1, 2)])),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 0,
+            amount: 3,
+            available: 2,
+        })
+    );
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(0, 1), (3, 2)])),
+        Err(MoveError::NoSuchStack {
+            stack_index: 3,
+            stack_count: 3,
+        })
+    );
+
+    // Ordinary Nim doesn't allow moves across stacks
+    let game = NimGame::new(moore_nim(1), vec![Stack(2), Stack(3)]);
+    assert_eq!(
+        moves::check_move(&game, &take_many(&[(0, 1), (1, 1)])),
+        Err(MoveError::NoSuchStackRule { stack_count: 2 })
+    );
+}
+
+#[test]
+fn apply_and_undo_across_stacks() {
+    let mut game = NimGame::builder()
+        .rules([across(3)])
+        .stacks(vec![Stack(2), Stack(3), Stack(5)])
+        .stack_ids(true)
+        .auto_normalize(true)
+        .build()
+        .unwrap();
+    let before = game.clone();
+
+    let mov = NimAction::TakeMany(TakeManyAction {
+        takes: vec![
+            StackTake {
+                stack_index: 0,
+                amount: 4,
+                stack_id: Some(StackId(2)),
+            },
+            StackTake {
+                stack_index: 0,
+                amount: 2,
+                stack_id: None,
+            },
+        ],
+        from: None,
+    });
+    let changes = moves::apply_move(&mut game, &mov).unwrap();
+
+    // The emptied stack is dropped, and the changed stack moves into place
+    assert_eq!(changes.removed, vec![StackId(0)]);
+    assert!(changes.created.is_empty());
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(3)]);
+    assert_eq!(game.get_stack_ids(), Some(&[StackId(2), StackId(1)][..]));
+
+    moves::undo_move(&mut game, changes).unwrap();
+    assert_eq!(game, before);
+}
+
+#[test]
+fn theorem_agrees_with_solver() {
+    for stacks_per_move in 1..=3 {
+        for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+            let mut solver = Solver::new();
+
+            for stacks in sequences(4, 4).into_iter().filter(|s| s.is_sorted()) {
+                let mut game = NimGame::new(moore_nim(stacks_per_move), stacks.clone());
+                game.set_convention(convention);
+
+                assert_eq!(
+                    moore::solve(&game),
+                    solver.solve(&game),
+                    "{stacks_per_move} stack(s), {convention:?}: {stacks:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn only_moore_nim() {
+    let game = NimGame::default();
+    assert_eq!(moore::max_stacks(game.get_rules()), None);
+    assert_eq!(moore::solve(&game), Err(SolveError::NotMooreNim));
+
+    // Stacks beyond u64::MAX
+    let game = NimGameBuilder::<u128>::new()
+        .rules([across(2)])
+        .stacks(vec![Stack(1 << 100), Stack(1 << 100), Stack(1 << 100)])
+        .build()
+        .unwrap();
+    assert_eq!(moore::solve(&game), Ok(Outcome::Loss));
+}
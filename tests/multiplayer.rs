@@ -0,0 +1,354 @@
+mod common;
+
+use common::positions;
+use nimlib::{
+    moore,
+    moves::{self, MoveError},
+    multiplayer::{CoalitionSolver, RankingSolver},
+    solver::{Outcome, SolveError, Solver},
+    GameError, GameSum, NimAction, NimGame, NimGameBuilder, NimRule, NimSplit, PlaceAction,
+    PlayConvention, Player, RuleSet, Split, Stack, TakeAction, TakeSize,
+};
+
+fn rule(take: TakeSize) -> NimRule {
+    NimRule {
+        take,
+        split: Split::Never,
+    }
+}
+
+fn game(players: usize, rules: &[NimRule], stacks: Vec<Stack>) -> NimGame {
+    NimGame::builder()
+        .rules(rules.to_vec())
+        .stacks(stacks)
+        .players(players)
+        .build()
+        .unwrap()
+}
+
+fn take(stack_index: usize, amount: u64) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index,
+        amount,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    })
+}
+
+#[test]
+fn players_take_turns_with_their_own_pools() {
+    let mut game = NimGame::builder()
+        .rules([rule(TakeSize::List(vec![1, 2])), rule(TakeSize::Place)])
+        .stacks(vec![Stack(4)])
+        .players(3)
+        .pool_coins(Player(2), 5)
+        .build()
+        .unwrap();
+    assert_eq!(game.get_player_count(), 3);
+    assert_eq!(game.get_pools(), &[0, 0, 5]);
+    let before = game.clone();
+
+    // The taken coins go to the pool of the player whose turn it is
+    let first = moves::apply_move(&mut game, &take(0, 2)).unwrap();
+    let second = moves::apply_move(&mut game, &take(0, 1)).unwrap();
+    assert_eq!(game.get_to_move(), Player(2));
+    assert_eq!(game.get_pools(), &[2, 1, 5]);
+
+    // Only the player whose turn it is may use their pool
+    let place = |from| {
+        NimAction::Place(PlaceAction {
+            stack_index: 0,
+            amount: 3,
+            from,
+            stack_id: None,
+        })
+    };
+    assert_eq!(
+        moves::check_move(&game, &place(Player::A)),
+        Err(MoveError::NotYourTurn {
+            player: Player::A,
+            to_move: Player(2),
+        })
+    );
+    let third = moves::apply_move(&mut game, &place(Player(2))).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(4)]);
+    assert_eq!(game.get_pools(), &[2, 1, 2]);
+
+    // The first player follows the last one
+    assert_eq!(game.get_to_move(), Player::A);
+
+    moves::undo_move(&mut game, third).unwrap();
+    moves::undo_move(&mut game, second).unwrap();
+    moves::undo_move(&mut game, first).unwrap();
+    assert_eq!(game, before);
+}
+
+#[test]
+fn players_have_to_take_part() {
+    let builder = || NimGame::builder().rules([rule(TakeSize::Any)]);
+
+    assert_eq!(
+        builder().players(1).build(),
+        Err(GameError::TooFewPlayers { player_count: 1 })
+    );
+    assert_eq!(
+        builder().to_move(Player(2)).build(),
+        Err(GameError::NoSuchPlayer {
+            player: Player(2),
+            player_count: 2,
+        })
+    );
+    assert_eq!(
+        builder().players(3).pool_coins(Player(3), 0).build(),
+        Err(GameError::NoSuchPlayer {
+            player: Player(3),
+            player_count: 3,
+        })
+    );
+
+    let mut game = builder().players(3).build().unwrap();
+    game.set_to_move(Player(2)).unwrap();
+    assert_eq!(
+        game.set_to_move(Player(3)),
+        Err(GameError::NoSuchPlayer {
+            player: Player(3),
+            player_count: 3,
+        })
+    );
+    assert_eq!(game.get_to_move(), Player(2));
+    assert_eq!(
+        game.set_pool_coins(Player(4), 1).unwrap_err().to_string(),
+        "There is no player E, as the game has 3 players"
+    );
+    assert_eq!(
+        game.set_pool_coins(Player(2), 1).unwrap_err().to_string(),
+        "The pools hold coins (A: 0, B: 0, C: 1), but the rules don't allow placing coins"
+    );
+}
+
+#[test]
+fn players_are_serialized_by_their_letters() {
+    assert_eq!(serde_json::to_string(&Player(2)).unwrap(), "\"C\"");
+    assert_eq!(serde_json::to_string(&Player(30)).unwrap(), "30");
+    assert_eq!(serde_json::from_str::<Player>("\"B\"").unwrap(), Player::B);
+    assert_eq!(serde_json::from_str::<Player>("30").unwrap(), Player(30));
+    assert!(serde_json::from_str::<Player>("\"AB\"").is_err());
+    assert_eq!(Player(25).to_string(), "Z");
+    assert_eq!(Player(26).to_string(), "26");
+
+    let mut game = game(4, &[rule(TakeSize::Any)], vec![Stack(3)]);
+    game.set_to_move(Player(3)).unwrap();
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(json.contains("\"to_move\":\"D\""), "{json}");
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), game);
+
+    // Games of two players serialized with the pools of players A and B
+    let json = json
+        .replace("\"pools\":[0,0,0,0]", "\"coins_a\":0,\"coins_b\":0")
+        .replace("\"D\"", "\"B\"");
+    let game: NimGame = serde_json::from_str(&json).unwrap();
+    assert_eq!(game.get_player_count(), 2);
+    assert_eq!(game.get_to_move(), Player::B);
+
+    // The player to move has to take part in the game
+    assert!(serde_json::from_str::<NimGame>(&json.replace("\"B\"", "\"C\"")).is_err());
+}
+
+#[test]
+fn pools_are_relative_to_the_player_to_move() {
+    let position = |to_move, pools: [u64; 3]| {
+        let mut builder = NimGameBuilder::new()
+            .rules([rule(TakeSize::Any), rule(TakeSize::Place)])
+            .stacks(vec![Stack(3)])
+            .players(3)
+            .to_move(to_move);
+        for (player, coins) in pools.into_iter().enumerate() {
+            builder = builder.pool_coins(Player(player), coins);
+        }
+        builder.build().unwrap().canonical_position()
+    };
+
+    assert_eq!(
+        position(Player::A, [1, 2, 3]),
+        position(Player::B, [3, 1, 2])
+    );
+    assert_ne!(
+        position(Player::A, [1, 2, 3]),
+        position(Player::B, [1, 2, 3])
+    );
+    assert_eq!(position(Player(2), [1, 2, 3]).pool_coins(), &[3, 1, 2]);
+    assert_eq!(position(Player(2), [1, 2, 3]).player_count(), 3);
+
+    // The number of players is part of the position
+    let rules = [rule(TakeSize::Any)];
+    let two = game(2, &rules, vec![Stack(3)]).canonical_position();
+    let three = game(3, &rules, vec![Stack(3)]).canonical_position();
+    assert_ne!(two, three);
+    assert_ne!(two.fingerprint(), three.fingerprint());
+    assert_eq!(
+        two,
+        NimGame::new(RuleSet::new(rules).unwrap(), vec![Stack(3)]).canonical_position()
+    );
+}
+
+#[test]
+fn sums_pass_the_turn_to_the_next_player() {
+    let rules = [rule(TakeSize::Any)];
+    let mut sum = GameSum::new(vec![
+        game(3, &rules, vec![Stack(2)]),
+        game(3, &rules, vec![Stack(1)]),
+    ]);
+    assert_eq!(sum.get_player_count(), 3);
+
+    let mov = sum.calculate_legal_moves().remove(0);
+    let changes = sum.apply_move(&mov).unwrap();
+    assert_eq!(sum.get_to_move(), Player::B);
+    assert!(sum
+        .get_components()
+        .iter()
+        .all(|component| component.get_to_move() == Player::B));
+
+    sum.undo_move(mov.component, changes).unwrap();
+    assert_eq!(sum.get_to_move(), Player::A);
+    assert!(sum.set_to_move(Player(3)).is_err());
+}
+
+#[test]
+fn ranking_of_two_players_agrees_with_solver() {
+    let rules = [NimRule {
+        take: TakeSize::List(vec![1, 2]),
+        split: Split::Optional,
+    }];
+    let mut solver = Solver::new();
+    let mut ranking = RankingSolver::new();
+
+    for stacks in positions(3, 4) {
+        let game = game(2, &rules, stacks.clone());
+        let outcome = solver.solve(&game).unwrap();
+        let last_mover = ranking.last_mover(&game).unwrap();
+        assert_eq!(
+            last_mover == game.get_to_move(),
+            outcome == Outcome::Win,
+            "{stacks:?}"
+        );
+    }
+}
+
+#[test]
+fn ranking_by_last_moves() {
+    // Taking single coins, the players take turns until the stack is empty
+    for players in 2..=5 {
+        let mut solver = RankingSolver::new();
+        for height in 0..12 {
+            let game = game(
+                players,
+                &[rule(TakeSize::List(vec![1]))],
+                vec![Stack(height)],
+            );
+            let last_mover = (height as usize + players - 1) % players;
+            assert_eq!(solver.last_mover(&game), Ok(Player(last_mover)));
+        }
+    }
+
+    // The player to move takes the whole stack, or else lets the player after them take it, ranking second
+    let rules = [rule(TakeSize::List(vec![1, 2]))];
+    let mut solver = RankingSolver::new();
+    assert_eq!(
+        solver.ranking(&game(3, &rules, vec![Stack(2)])),
+        Ok(vec![Player::A, Player(2), Player::B])
+    );
+    assert_eq!(
+        solver.ranking(&game(3, &rules, vec![Stack(4)])),
+        Ok(vec![Player::B, Player::A, Player(2)])
+    );
+    assert!(solver.solved_positions() > 0);
+}
+
+#[test]
+fn coalitions_play_against_the_other_players() {
+    let rules = [NimRule {
+        take: TakeSize::List(vec![1, 2, 3]),
+        split: Split::Optional,
+    }];
+
+    for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+        let mut solver = CoalitionSolver::new();
+        let mut two_players = Solver::new();
+
+        for stacks in positions(2, 5) {
+            // Exactly one of a coalition and the other players wins
+            let mut game = game(3, &rules, stacks.clone());
+            game.set_convention(convention);
+            let winning = solver.winning_coalitions(&game).unwrap();
+            assert_eq!(winning.len(), 4, "{convention:?}: {stacks:?}");
+            for coalition in &winning {
+                let others: Vec<Player> = (0..3)
+                    .map(Player)
+                    .filter(|player| !coalition.contains(player))
+                    .collect();
+                assert!(!winning.contains(&others), "{stacks:?}: {coalition:?}");
+            }
+
+            // With two players, a coalition of the player to move wins if the player wins
+            let mut game = NimGame::new(RuleSet::new(rules.clone()).unwrap(), stacks.clone());
+            game.set_convention(convention);
+            assert_eq!(
+                solver.solve(&game, &[Player::A]),
+                two_players.solve(&game),
+                "{convention:?}: {stacks:?}"
+            );
+        }
+    }
+
+    // All players together make the last move under normal play, and avoid it under misère play
+    let mut game = game(3, &rules, vec![Stack(7)]);
+    let mut solver = CoalitionSolver::new();
+    let all = [Player::A, Player::B, Player(2)];
+    assert_eq!(solver.solve(&game, &all), Ok(Outcome::Win));
+    game.set_convention(PlayConvention::Misere);
+    assert_eq!(solver.solve(&game, &all), Ok(Outcome::Loss));
+}
+
+#[test]
+fn analysis_errors() {
+    let rules = [rule(TakeSize::AnyFromStacks(2))];
+    let game = game(3, &rules, vec![Stack(1), Stack(2)]);
+
+    assert_eq!(
+        Solver::new().solve(&game),
+        Err(SolveError::NotTwoPlayers { player_count: 3 })
+    );
+    assert_eq!(
+        moore::solve(&game),
+        Err(SolveError::NotTwoPlayers { player_count: 3 })
+    );
+    assert_eq!(
+        CoalitionSolver::new().solve(&game, &[Player(3)]),
+        Err(SolveError::NoSuchPlayer {
+            player: Player(3),
+            player_count: 3,
+        })
+    );
+
+    let mut misere = game.clone();
+    misere.set_convention(PlayConvention::Misere);
+    assert_eq!(
+        RankingSolver::new().last_mover(&misere),
+        Err(SolveError::MisereRanking)
+    );
+
+    let place = NimGame::builder()
+        .rules([rule(TakeSize::Any), rule(TakeSize::Place)])
+        .players(3)
+        .build()
+        .unwrap();
+    assert_eq!(
+        RankingSolver::new().last_mover(&place),
+        Err(SolveError::PlaceRule)
+    );
+    assert_eq!(
+        CoalitionSolver::new().solve(&place, &[Player::A]),
+        Err(SolveError::PlaceRule)
+    );
+}
@@ -0,0 +1,160 @@
+use nimlib::Nimber;
+
+/// The nim-multiplication table of the nimbers below 16 (the field of order 16)
+const TABLE: [[u64; 16]; 16] = [
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [0, 2, 3, 1, 8, 10, 11, 9, 12, 14, 15, 13, 4, 6, 7, 5],
+    [0, 3, 1, 2, 12, 15, 13, 14, 4, 7, 5, 6, 8, 11, 9, 10],
+    [0, 4, 8, 12, 6, 2, 14, 10, 11, 15, 3, 7, 13, 9, 5, 1],
+    [0, 5, 10, 15, 2, 7, 8, 13, 3, 6, 9, 12, 1, 4, 11, 14],
+    [0, 6, 11, 13, 14, 8, 5, 3, 7, 1, 12, 10, 9, 15, 2, 4],
+    [0, 7, 9, 14, 10, 13, 3, 4, 15, 8, 6, 1, 5, 2, 12, 11],
+    [0, 8, 12, 4, 11, 3, 7, 15, 13, 5, 1, 9, 6, 14, 10, 2],
+    [0, 9, 14, 7, 15, 6, 1, 8, 5, 12, 11, 2, 10, 3, 4, 13],
+    [0, 10, 15, 5, 3, 9, 12, 6, 1, 11, 14, 4, 2, 8, 13, 7],
+    [0, 11, 13, 6, 7, 12, 10, 1, 9, 2, 4, 15, 14, 5, 3, 8],
+    [0, 12, 4, 8, 13, 1, 9, 5, 6, 10, 2, 14, 11, 7, 15, 3],
+    [0, 13, 6, 11, 9, 4, 15, 2, 14, 3, 8, 5, 7, 10, 1, 12],
+    [0, 14, 7, 9, 5, 11, 2, 12, 10, 4, 13, 3, 15, 1, 8, 6],
+    [0, 15, 5, 10, 1, 14, 4, 11, 2, 13, 7, 8, 3, 12, 6, 9],
+];
+
+/// Some nimbers spread over the whole range of [u64]
+fn samples() -> Vec<Nimber> {
+    let mut samples: Vec<Nimber> = (0..20).map(Nimber).collect();
+    let mut x = 0x9e37_79b9_7f4a_7c15_u64;
+    for _ in 0..20 {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        samples.push(Nimber(x));
+        samples.push(Nimber(x >> 32));
+    }
+    samples.extend([Nimber(u64::MAX), Nimber(1 << 63), Nimber(1 << 32)]);
+    samples
+}
+
+#[test]
+fn nim_addition() {
+    let nimbers = [Nimber(1), Nimber(2), Nimber(3), Nimber(7)];
+
+    assert_eq!(Nimber(5_u64) + Nimber(3), Nimber(6));
+    assert_eq!(Nimber(5_u64) - Nimber(3), Nimber(6));
+    assert_eq!(nimbers.iter().sum::<Nimber>(), Nimber(7));
+    assert_eq!(nimbers.into_iter().sum::<Nimber>(), Nimber(7));
+
+    let mut nimber = Nimber(12_u64);
+    nimber += Nimber(10);
+    assert_eq!(nimber, Nimber(6));
+    nimber -= Nimber(6);
+    assert_eq!(nimber, Nimber(0));
+}
+
+#[test]
+fn nim_multiplication_table() {
+    for (a, row) in TABLE.iter().enumerate() {
+        for (b, &product) in row.iter().enumerate() {
+            assert_eq!(Nimber(a as u64) * Nimber(b as u64), Nimber(product));
+        }
+    }
+}
+
+#[test]
+fn field_axioms() {
+    let samples = samples();
+
+    for &a in &samples {
+        assert_eq!(a * Nimber(1), a);
+        assert_eq!(a * Nimber(0), Nimber(0));
+
+        for &b in &samples {
+            assert_eq!(a * b, b * a);
+
+            for &c in samples.iter().step_by(7) {
+                assert_eq!((a * b) * c, a * (b * c));
+                assert_eq!(a * (b + c), a * b + a * c);
+            }
+        }
+    }
+}
+
+#[test]
+fn fermat_powers() {
+    // A Fermat 2-power multiplied with a smaller nimber is the ordinary product
+    assert_eq!(Nimber(1_u64 << 32) * Nimber(12345), Nimber(12345 << 32));
+
+    // And its square is its sesquimultiple
+    for k in 0..6 {
+        let fermat = Nimber(1_u64 << (1 << k));
+        assert_eq!(fermat * fermat, Nimber(fermat.0 | fermat.0 >> 1));
+    }
+}
+
+#[test]
+fn inverses_powers_and_roots() {
+    assert_eq!(Nimber(0_u64).inverse(), None);
+
+    for a in samples() {
+        if a != Nimber(0) {
+            assert_eq!(a * a.inverse().unwrap(), Nimber(1), "{a:?}");
+        }
+
+        assert_eq!(a.sqrt() * a.sqrt(), a);
+        assert_eq!(a.pow(0), Nimber(1));
+        assert_eq!(a.pow(3), a * a * a);
+        assert_eq!([a, a, a, a, a].iter().product::<Nimber>(), a.pow(5));
+    }
+
+    // The multiplicative group of the field of order 2^64 has order 2^64 - 1
+    assert_eq!(Nimber(0xdead_beef_u64).pow(u64::MAX), Nimber(1));
+}
+
+#[test]
+fn wider_nimbers() {
+    // Nimbers below 2^64 multiply the same way as u64 and as u128
+    for a in samples() {
+        for b in samples().into_iter().step_by(5) {
+            assert_eq!(
+                Nimber(u128::from(a.0)) * Nimber(u128::from(b.0)),
+                Nimber(u128::from((a * b).0))
+            );
+        }
+    }
+
+    // The Fermat 2-power 2^64 squares to its sesquimultiple, which needs more than 64 bits
+    let fermat = Nimber(1_u128 << 64);
+    assert_eq!(fermat * fermat, Nimber(3 << 63));
+    assert_eq!(
+        fermat * Nimber(u128::from(u64::MAX)),
+        Nimber(u128::from(u64::MAX) << 64)
+    );
+
+    // The nimbers below 2^128 form a field as well
+    let a = Nimber(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210_u128);
+    let b = Nimber(u128::MAX / 3);
+    assert_eq!(a * a.inverse().unwrap(), Nimber(1));
+    assert_eq!(a.sqrt() * a.sqrt(), a);
+    assert_eq!(a * (b + fermat), a * b + a * fermat);
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn big_nimbers() {
+    use num_bigint::BigUint;
+
+    // The Fermat 2-power 2^128 squares to its sesquimultiple
+    let fermat = Nimber(BigUint::from(1_u64) << 128);
+    assert_eq!(
+        fermat.clone() * fermat.clone(),
+        Nimber(BigUint::from(3_u64) << 127)
+    );
+
+    // Nimbers which fit into a u128 multiply the same way
+    let a = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210_u128;
+    let b = u128::MAX / 3;
+    assert_eq!(
+        Nimber(BigUint::from(a)) * Nimber(BigUint::from(b)),
+        Nimber(BigUint::from((Nimber(a) * Nimber(b)).0))
+    );
+}
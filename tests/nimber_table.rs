@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use nimlib::{
+    moves, nimbers::calculate_nimbers_up_to, NimAction, NimRule, NimSplit, Nimber, Player, Split,
+    Stack, TakeSize,
+};
+
+/// A straightforward (slow) MEX implementation based on the legal moves of a single stack
+fn reference_nimber(height: u64, rules: &[NimRule], cache: &mut HashMap<u64, u64>) -> u64 {
+    if let Some(nimber) = cache.get(&height) {
+        return *nimber;
+    }
+
+    let mut options = Vec::new();
+    for mov in
+        moves::calculate_legal_moves(&[Stack(height)], rules, &[0, 0], Player::A, None).unwrap()
+    {
+        if let NimAction::Take(take) = mov {
+            match take.split {
+                NimSplit::Yes(a, b) => options.push(
+                    reference_nimber(a.0, rules, cache) ^ reference_nimber(b.0, rules, cache),
+                ),
+                NimSplit::No if take.amount > 0 => {
+                    options.push(reference_nimber(height - take.amount, rules, cache));
+                }
+                NimSplit::No => {}
+            }
+        }
+    }
+
+    let mut nimber = 0;
+    while options.contains(&nimber) {
+        nimber += 1;
+    }
+
+    cache.insert(height, nimber);
+    nimber
+}
+
+fn check_against_reference(rules: &[NimRule], max_height: u64) {
+    let mut cache = HashMap::new();
+    let nimbers = calculate_nimbers_up_to(max_height, rules).unwrap();
+
+    assert_eq!(nimbers.len() as u64, max_height + 1);
+
+    for (h, nimber) in nimbers.into_iter().enumerate() {
+        assert_eq!(
+            nimber,
+            Nimber(reference_nimber(h as u64, rules, &mut cache)),
+            "Nimber for height {h} is wrong for rules {rules:?}"
+        );
+    }
+}
+
+#[test]
+fn kayles() {
+    check_against_reference(
+        &[NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Optional,
+        }],
+        200,
+    );
+}
+
+#[test]
+fn take_any_split_always() {
+    check_against_reference(
+        &[NimRule {
+            take: TakeSize::Any,
+            split: Split::Always,
+        }],
+        80,
+    );
+}
+
+#[test]
+fn mixed_rules() {
+    check_against_reference(
+        &[
+            NimRule {
+                take: TakeSize::List(vec![2, 5]),
+                split: Split::Never,
+            },
+            NimRule {
+                take: TakeSize::List(vec![0, 3]),
+                split: Split::Always,
+            },
+            NimRule {
+                take: TakeSize::Any,
+                split: Split::Never,
+            },
+        ],
+        120,
+    );
+}
+
+#[test]
+fn table_matches_single_heights() {
+    let rules = vec![NimRule {
+        take: TakeSize::List(vec![1, 4]),
+        split: Split::Optional,
+    }];
+
+    // Query single heights first, then extend the table past them
+    let single = (0..50)
+        .map(|h| Stack(h).calculate_nimber(&rules, 0).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        calculate_nimbers_up_to(300, &rules).unwrap()[..50],
+        single[..]
+    );
+}
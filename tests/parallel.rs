@@ -0,0 +1,86 @@
+#![cfg(feature = "parallel")]
+
+use nimlib::{
+    nimbers::{self, parallel},
+    NimRule, Nimber, Split, Stack, TakeSize,
+};
+
+fn subtraction_game(max: u64) -> Vec<NimRule> {
+    vec![NimRule {
+        take: TakeSize::List((1..=max).collect()),
+        split: Split::Never,
+    }]
+}
+
+#[test]
+fn tables_of_rule_sets() {
+    let rule_sets = vec![
+        subtraction_game(2),
+        subtraction_game(5),
+        subtraction_game(2),
+        vec![NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Optional,
+        }],
+    ];
+
+    let tables =
+        parallel::calculate_nimber_tables(&rule_sets, 500, Some(3), &Default::default()).unwrap();
+
+    assert_eq!(tables.len(), rule_sets.len());
+    for (rules, table) in rule_sets.iter().zip(tables) {
+        assert_eq!(table, nimbers::calculate_nimbers_up_to(500, rules).unwrap());
+    }
+}
+
+#[test]
+fn large_table() {
+    let rules = vec![
+        NimRule {
+            take: TakeSize::List(vec![1, 3]),
+            split: Split::Never,
+        },
+        NimRule {
+            take: TakeSize::List(vec![6]),
+            split: Split::Always,
+        },
+        NimRule {
+            take: TakeSize::List(vec![9]),
+            split: Split::Optional,
+        },
+    ];
+
+    let table =
+        parallel::calculate_nimbers_up_to(17_000, &rules, Some(4), &Default::default()).unwrap();
+
+    // Known values of this game, see `2k_nimbers.rs`
+    assert_eq!(table[..8], [0, 1, 0, 1, 0, 1, 0, 1].map(Nimber));
+    assert_eq!(table.len(), 17_001);
+}
+
+#[test]
+fn position_nimbers() {
+    let rules = subtraction_game(3);
+
+    let positions = (0..100)
+        .map(|h| vec![Stack(h), Stack(2 * h + 1), Stack(7)])
+        .collect::<Vec<_>>();
+
+    let expected = positions
+        .iter()
+        .map(|stacks| {
+            stacks.iter().fold(Nimber(0), |nimber, stack| {
+                nimber ^ stack.calculate_nimber(&rules, 0).unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        parallel::calculate_position_nimbers(&positions, &rules, None, &Default::default()),
+        Ok(expected)
+    );
+    assert_eq!(
+        parallel::calculate_position_nimbers(&[], &rules, None, &Default::default()),
+        Ok(vec![])
+    );
+}
@@ -0,0 +1,347 @@
+mod common;
+
+use common::positions;
+use nimlib::{
+    fibonacci,
+    moves::{self, MoveError},
+    nimbers,
+    partizan::{self, Dyadic, OutcomeClass, Value},
+    solver::{self, Outcome, SolveError, Solver},
+    NimAction, NimGame, NimRule, NimSplit, PlayConvention, Player, RuleProblem, RuleSet, Split,
+    Stack, TakeAction, TakeSize,
+};
+
+fn rule(list: &[u64], split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::List(list.to_vec()),
+        split,
+    }
+}
+
+fn take(stack_index: usize, amount: u64) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index,
+        amount,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    })
+}
+
+#[test]
+fn canonical_partizan_rules() {
+    let rules = RuleSet::partizan([
+        (None, rule(&[1], Split::Never)),
+        (Some(Player::B), rule(&[2], Split::Never)),
+        (Some(Player::B), rule(&[1], Split::Always)),
+        // Player A's own rule is one of the rules for all players
+        (Some(Player::A), rule(&[1], Split::Never)),
+    ])
+    .unwrap();
+
+    assert!(rules.is_partizan());
+    assert_eq!(rules.rules(), &[rule(&[1], Split::Never)]);
+    assert_eq!(rules.rules_for(Player::A), &[rule(&[1], Split::Never)]);
+    assert_eq!(
+        rules.rules_for(Player::B),
+        &[rule(&[1], Split::Optional), rule(&[2], Split::Never)]
+    );
+    assert_eq!(
+        rules.player_rules(),
+        &[(Player::B, rules.rules_for(Player::B).to_vec())]
+    );
+
+    // Rule sets without rules of single players are impartial, and keep their fingerprints
+    let impartial = RuleSet::partizan([
+        (None, rule(&[1], Split::Never)),
+        (Some(Player::A), rule(&[1], Split::Never)),
+    ])
+    .unwrap();
+    assert!(!impartial.is_partizan());
+    assert_eq!(impartial, RuleSet::new([rule(&[1], Split::Never)]).unwrap());
+    assert_eq!(
+        impartial.fingerprint(),
+        RuleSet::new([rule(&[1], Split::Never)])
+            .unwrap()
+            .fingerprint()
+    );
+    assert_ne!(rules.fingerprint(), impartial.fingerprint());
+
+    // The same rule may be given for different players, but only once for each
+    assert_eq!(
+        RuleSet::partizan([
+            (Some(Player::A), rule(&[1], Split::Never)),
+            (Some(Player::B), rule(&[1], Split::Never)),
+            (Some(Player::A), rule(&[1], Split::Never)),
+            (Some(Player::B), rule(&[0], Split::Never)),
+        ])
+        .unwrap_err()
+        .problems,
+        vec![
+            RuleProblem::DuplicateRule { rule: 2, first: 0 },
+            RuleProblem::ZeroTakeWithoutSplit { rule: 3 },
+        ]
+    );
+}
+
+#[test]
+fn partizan_rules_are_serialized_by_player() {
+    let rules = RuleSet::partizan([
+        (None, rule(&[1], Split::Never)),
+        (Some(Player::B), rule(&[2], Split::Never)),
+    ])
+    .unwrap();
+
+    let json = serde_json::to_string(&rules).unwrap();
+    assert_eq!(
+        json,
+        r#"{"rules":[{"rule":{"TakeList":[1]},"split":"Never"}],"players":[{"player":"B","rules":[{"rule":{"TakeList":[1,2]},"split":"Never"}]}]}"#
+    );
+    assert_eq!(serde_json::from_str::<RuleSet>(&json).unwrap(), rules);
+
+    // Impartial rule sets are still lists of rules
+    let impartial = RuleSet::new([rule(&[1], Split::Never)]).unwrap();
+    let json = serde_json::to_string(&impartial).unwrap();
+    assert_eq!(json, r#"[{"rule":{"TakeList":[1]},"split":"Never"}]"#);
+    assert_eq!(serde_json::from_str::<RuleSet>(&json).unwrap(), impartial);
+}
+
+#[test]
+fn players_move_by_their_own_rules() {
+    let mut game = NimGame::builder()
+        .rules([rule(&[1], Split::Never)])
+        .player_rules(Player::B, [rule(&[2], Split::Never)])
+        .stacks(vec![Stack(3)])
+        .build()
+        .unwrap();
+
+    assert_eq!(game.calculate_legal_moves(), vec![take(0, 1)]);
+    assert_eq!(
+        moves::check_move(&game, &take(0, 2)),
+        Err(MoveError::NoSuchRule { amount: 2 })
+    );
+
+    moves::apply_move(&mut game, &take(0, 1)).unwrap();
+    assert_eq!(game.get_to_move(), Player::B);
+    assert_eq!(game.calculate_legal_moves(), vec![take(0, 1), take(0, 2)]);
+    assert!(moves::check_move(&game, &take(0, 2)).is_ok());
+
+    // The player to move is part of the canonical position of partizan games only
+    let mut other = game.clone();
+    other.set_to_move(Player::A).unwrap();
+    assert_ne!(game.canonical_position(), other.canonical_position());
+    assert_eq!(game.canonical_position().to_move(), Some(Player::B));
+    assert_eq!(NimGame::default().canonical_position().to_move(), None);
+
+    // Rules can only be given to players who take part in the game
+    assert!(NimGame::builder()
+        .player_rules(Player(2), [rule(&[1], Split::Never)])
+        .build()
+        .is_err());
+}
+
+#[test]
+fn dyadics_with_large_exponents() {
+    let tiny = Dyadic::new(1, 200);
+    assert!(tiny > Dyadic::integer(0));
+    assert!(tiny < Dyadic::new(1, 199));
+    assert!(-tiny < Dyadic::integer(0));
+    assert!(Dyadic::new(i64::MAX, 70) > Dyadic::new(1, 10));
+    assert!(Dyadic::new(-i64::MAX, 70) < Dyadic::new(-1, 10));
+    assert_eq!(Dyadic::new(1, 130).to_string(), "1/2^130");
+    assert_eq!(Dyadic::new(1, 63).to_string(), "1/9223372036854775808");
+    assert_eq!(-Dyadic::integer(i64::MIN), Dyadic::integer(i64::MAX));
+    assert_eq!(
+        Value::number(Dyadic::new(i64::MAX, 63)).as_number(),
+        Some(Dyadic::new(i64::MAX, 63))
+    );
+
+    // Values whose mean or radius doesn't fit are displayed by their options
+    let switch = Value::new(
+        vec![Value::number(Dyadic::new(1, 70))],
+        vec![Value::integer(-1)],
+    );
+    assert_eq!(switch.to_string(), "{1/2^70 | -1}");
+
+    // Deserialized numbers are in lowest terms
+    let two_halves: Dyadic = serde_json::from_str(r#"{"numerator":2,"exponent":1}"#).unwrap();
+    assert_eq!(two_halves, Dyadic::integer(1));
+    assert_eq!(
+        serde_json::from_str::<Dyadic>(&serde_json::to_string(&tiny).unwrap()).unwrap(),
+        tiny
+    );
+}
+
+#[test]
+fn value_arithmetic() {
+    let half = Value::number(Dyadic::new(1, 1));
+    assert_eq!(half.to_string(), "1/2");
+    assert_eq!(half.clone() + half.clone(), Value::integer(1));
+    assert_eq!(
+        (Value::integer(2) - Value::number(Dyadic::new(3, 2))).to_string(),
+        "5/4"
+    );
+    assert_eq!(Value::integer(-3).as_number(), Some(Dyadic::integer(-3)));
+    assert_eq!(Value::star().as_number(), None);
+
+    // Nimbers are added like Nim stacks
+    assert_eq!(Value::nimber(3) + Value::nimber(5), Value::nimber(6));
+    assert_eq!((Value::nimber(2) + Value::integer(1)).to_string(), "1*2");
+
+    let up = Value::up();
+    let down = -up.clone();
+    assert_eq!(down.to_string(), "↓");
+    assert_eq!(up.clone() + down.clone(), Value::zero());
+    assert_eq!(
+        [up.clone(), up.clone(), up.clone(), Value::star()]
+            .into_iter()
+            .sum::<Value>()
+            .to_string(),
+        "↑3*"
+    );
+    assert_eq!((down.clone() + down + Value::star()).to_string(), "⇓*");
+    assert_eq!((up.clone() + half.clone()).to_string(), "1/2↑");
+
+    // Switches
+    let switch = Value::new(vec![Value::integer(3)], vec![Value::integer(1)]);
+    assert_eq!(switch.to_string(), "2±1");
+    assert_eq!(switch.class(), OutcomeClass::Left);
+    let hot = Value::new(vec![Value::integer(1)], vec![Value::integer(-1)]);
+    assert_eq!(hot.class(), OutcomeClass::Next);
+    assert_eq!((hot.clone() + Value::integer(1)).to_string(), "1±1");
+    assert_eq!(hot.clone() + hot, Value::zero());
+
+    // Dominated and reversible options are removed
+    assert_eq!(
+        Value::new(vec![Value::zero(), Value::integer(1)], vec![]),
+        Value::integer(2)
+    );
+    assert_eq!(
+        Value::new(vec![Value::zero()], vec![Value::integer(2)]),
+        Value::integer(1)
+    );
+    assert_eq!(
+        Value::new(vec![Value::zero(), Value::star()], vec![Value::zero()]).to_string(),
+        "↑*"
+    );
+
+    // Outcome classes
+    assert_eq!(Value::zero().class(), OutcomeClass::Previous);
+    assert_eq!(up.class(), OutcomeClass::Left);
+    assert_eq!(Value::integer(-1).class(), OutcomeClass::Right);
+    assert_eq!(OutcomeClass::Left.outcome(Player::B), Outcome::Loss);
+    assert_eq!(OutcomeClass::Next.outcome(Player::B), Outcome::Win);
+    assert!(half > up);
+    assert_eq!(Value::star().partial_cmp(&Value::zero()), None);
+}
+
+#[test]
+fn impartial_values_are_nimbers() {
+    for rules in [
+        vec![rule(&[1, 2], Split::Optional)],
+        vec![rule(&[1], Split::Never), rule(&[2, 3], Split::Always)],
+        vec![NimRule {
+            take: TakeSize::Any,
+            split: Split::Optional,
+        }],
+    ] {
+        let nimbers = nimbers::calculate_nimbers_up_to(16, &rules).unwrap();
+        let rules = RuleSet::new(rules).unwrap();
+        let values = partizan::stack_values(&rules, 16).unwrap();
+
+        for (height, (value, nimber)) in values.into_iter().zip(nimbers).enumerate() {
+            assert_eq!(value, Value::nimber(nimber.0), "{rules:?}: {height}");
+        }
+    }
+}
+
+#[test]
+fn values_agree_with_solver() {
+    let rule_sets = [
+        vec![
+            (Some(Player::A), rule(&[1], Split::Never)),
+            (Some(Player::B), rule(&[1, 2], Split::Never)),
+        ],
+        vec![
+            (
+                Some(Player::A),
+                NimRule {
+                    take: TakeSize::Any,
+                    split: Split::Never,
+                },
+            ),
+            (Some(Player::B), rule(&[1], Split::Never)),
+        ],
+        vec![
+            (None, rule(&[1], Split::Never)),
+            (Some(Player::A), rule(&[2], Split::Always)),
+            (Some(Player::B), rule(&[0], Split::Always)),
+        ],
+        vec![
+            (Some(Player::A), rule(&[1, 2], Split::Optional)),
+            (Some(Player::B), rule(&[2, 3], Split::Never)),
+        ],
+    ];
+
+    for rules in rule_sets {
+        let rules = RuleSet::partizan(rules).unwrap();
+        let mut solver = Solver::new();
+
+        for stacks in positions(3, 5) {
+            let mut game = NimGame::new(rules.clone(), stacks.clone());
+            let value = partizan::value(&game).unwrap();
+
+            for to_move in [Player::A, Player::B] {
+                game.set_to_move(to_move).unwrap();
+                let outcome = solver.solve(&game).unwrap();
+                assert_eq!(
+                    value.class().outcome(to_move),
+                    outcome,
+                    "{rules:?}: {stacks:?}, {to_move} to move, value {value}"
+                );
+                assert_eq!(partizan::solve(&game), Ok(outcome));
+            }
+        }
+    }
+}
+
+#[test]
+fn values_of_partizan_games_only() {
+    let rules = RuleSet::partizan([
+        (Some(Player::A), rule(&[1], Split::Never)),
+        (Some(Player::B), rule(&[2], Split::Never)),
+    ])
+    .unwrap();
+
+    let mut game = NimGame::new(rules.clone(), vec![Stack(3)]);
+    game.set_convention(PlayConvention::Misere);
+    assert_eq!(partizan::value(&game), Err(SolveError::MisereValue));
+
+    let game = NimGame::builder()
+        .player_rules(Player::A, [rule(&[1], Split::Never)])
+        .stacks(vec![Stack(3)])
+        .players(3)
+        .build()
+        .unwrap();
+    assert_eq!(
+        partizan::value(&game),
+        Err(SolveError::NotTwoPlayers { player_count: 3 })
+    );
+
+    let fibonacci_nim = RuleSet::partizan([(
+        Some(Player::A),
+        NimRule {
+            take: TakeSize::UpToMultipleOfLast(2),
+            split: Split::Never,
+        },
+    )])
+    .unwrap();
+    assert_eq!(
+        partizan::stack_values(&fibonacci_nim, 3),
+        Err(SolveError::NotStackwise)
+    );
+    assert!(!fibonacci::is_fibonacci_nim(&fibonacci_nim));
+
+    // Partizan positions have no nimbers
+    assert_eq!(solver::verify(&rules, 3, 2), Err(SolveError::Partizan));
+}
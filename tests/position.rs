@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use nimlib::{
+    moves, CanonicalPosition, NimAction, NimGame, NimRule, NimSplit, PlaceAction, PlayConvention,
+    Player, Split, Stack, StackId, TakeAction, TakeSize,
+};
+
+fn take_any() -> NimRule {
+    NimRule {
+        take: TakeSize::Any,
+        split: Split::Optional,
+    }
+}
+
+fn take(stack_index: usize, amount: u64, split: NimSplit) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index,
+        amount,
+        split,
+        from: None,
+        stack_id: None,
+    })
+}
+
+#[test]
+fn normalize_drops_empty_stacks_and_sorts() {
+    let mut game = NimGame::builder()
+        .rules([take_any()])
+        .stacks(vec![Stack(4), Stack(0), Stack(2), Stack(4), Stack(0)])
+        .stack_ids(true)
+        .build()
+        .unwrap();
+
+    let changes = game.normalize();
+
+    assert_eq!(game.get_stacks(), &vec![Stack(2), Stack(4), Stack(4)]);
+    assert_eq!(
+        game.get_stack_ids(),
+        Some(&[StackId(2), StackId(0), StackId(3)][..])
+    );
+    assert_eq!(changes.removed, vec![StackId(1), StackId(4)]);
+    assert!(changes.created.is_empty());
+}
+
+#[test]
+fn auto_normalize_after_moves() {
+    let mut game = NimGame::builder()
+        .rules([take_any()])
+        .stacks(vec![Stack(5), Stack(3), Stack(1)])
+        .stack_ids(true)
+        .auto_normalize(true)
+        .build()
+        .unwrap();
+
+    assert!(game.get_auto_normalize());
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(3), Stack(5)]);
+
+    // Emptying a stack removes it
+    let changes = moves::apply_move(&mut game, &take(0, 1, NimSplit::No)).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(3), Stack(5)]);
+    assert_eq!(changes.removed, vec![StackId(2)]);
+    assert!(changes.created.is_empty());
+
+    // Splitting a stack sorts the new stacks into place
+    let changes =
+        moves::apply_move(&mut game, &take(1, 1, NimSplit::Yes(Stack(1), Stack(3)))).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(3), Stack(3)]);
+    assert_eq!(changes.removed, vec![StackId(0)]);
+    assert_eq!(changes.created, vec![StackId(3), StackId(4)]);
+    assert_eq!(game.find_stack(StackId(3)), Some(0));
+
+    // Without normalization, empty stacks are kept
+    game.set_auto_normalize(false);
+    moves::apply_move(&mut game, &take(0, 1, NimSplit::No)).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(0), Stack(3), Stack(3)]);
+}
+
+#[test]
+fn equivalent_positions_are_equal() {
+    let rules = [take_any()];
+    let a = NimGame::builder()
+        .rules(rules.clone())
+        .stacks(vec![Stack(7), Stack(0), Stack(2)])
+        .build()
+        .unwrap();
+    let b = NimGame::builder()
+        .rules(rules.clone())
+        .stacks(vec![Stack(2), Stack(7)])
+        .to_move(Player::B)
+        .build()
+        .unwrap();
+    let misere = NimGame::builder()
+        .rules(rules)
+        .stacks(vec![Stack(2), Stack(7)])
+        .convention(PlayConvention::Misere)
+        .build()
+        .unwrap();
+
+    assert_eq!(a.canonical_position(), b.canonical_position());
+    assert_eq!(
+        a.canonical_position().fingerprint(),
+        b.canonical_position().fingerprint()
+    );
+    assert_ne!(a.canonical_position(), misere.canonical_position());
+
+    let table: HashSet<_> = [a, b, misere]
+        .iter()
+        .map(NimGame::canonical_position)
+        .collect();
+    assert_eq!(table.len(), 2);
+}
+
+#[test]
+fn pools_are_relative_to_the_player_to_move() {
+    let rules = [
+        take_any(),
+        NimRule {
+            take: TakeSize::Place,
+            split: Split::Never,
+        },
+    ];
+    let game = |to_move, coins_a, coins_b| {
+        NimGame::builder()
+            .rules(rules.clone())
+            .stacks(vec![Stack(3)])
+            .pool_coins(Player::A, coins_a)
+            .pool_coins(Player::B, coins_b)
+            .to_move(to_move)
+            .build()
+            .unwrap()
+            .canonical_position()
+    };
+
+    assert_eq!(game(Player::A, 1, 2), game(Player::B, 2, 1));
+    assert_ne!(game(Player::A, 1, 2), game(Player::B, 1, 2));
+    assert_eq!(game(Player::A, 1, 2).pool_coins(), &[1, 2]);
+
+    // Placing coins changes the position
+    let mut placed = NimGame::builder()
+        .rules(rules.clone())
+        .stacks(vec![Stack(3)])
+        .pool_coins(Player::A, 1)
+        .build()
+        .unwrap();
+    let before = placed.canonical_position();
+    moves::apply_move(
+        &mut placed,
+        &NimAction::Place(PlaceAction {
+            stack_index: 0,
+            amount: 1,
+            from: Player::A,
+            stack_id: None,
+        }),
+    )
+    .unwrap();
+    assert_ne!(placed.canonical_position(), before);
+}
+
+#[test]
+fn fingerprints_are_stable() {
+    let game = NimGame::default();
+
+    assert_eq!(
+        game.canonical_position().fingerprint(),
+        17_026_919_640_025_319_834
+    );
+}
+
+#[test]
+fn deserializing_recalculates_the_fingerprint() {
+    let position = NimGame::default().canonical_position();
+    let json = serde_json::to_string(&position).unwrap();
+    assert_eq!(
+        serde_json::from_str::<CanonicalPosition>(&json).unwrap(),
+        position
+    );
+
+    // A forged fingerprint is ignored
+    let fingerprint = position.fingerprint().to_string();
+    let forged = json.replace(&fingerprint, "42");
+    assert_ne!(forged, json);
+    let deserialized = serde_json::from_str::<CanonicalPosition>(&forged).unwrap();
+    assert_eq!(deserialized.fingerprint(), position.fingerprint());
+
+    // Positions which aren't in canonical form are rejected
+    let unsorted = NimGame::new(
+        NimGame::default().get_rules().clone(),
+        vec![Stack(3), Stack(1)],
+    )
+    .canonical_position();
+    let json = serde_json::to_string(&unsorted)
+        .unwrap()
+        .replace("[1,3]", "[3,1]");
+    assert!(serde_json::from_str::<CanonicalPosition>(&json)
+        .unwrap_err()
+        .to_string()
+        .contains("not in canonical form"));
+}
@@ -0,0 +1,90 @@
+use std::sync::Mutex;
+
+use nimlib::{
+    nimbers::{
+        calculate_nimber_for_height, calculate_nimber_for_height_with, calculate_nimbers_up_to,
+        calculate_nimbers_up_to_with, CalculationOptions, CancellationToken, NimberError, Progress,
+    },
+    NimRule, Split, TakeSize,
+};
+
+#[test]
+fn progress_is_reported() {
+    let rules = vec![NimRule {
+        take: TakeSize::List(vec![1, 2, 5]),
+        split: Split::Optional,
+    }];
+
+    let reports = Mutex::new(Vec::new());
+    let record = |progress: Progress| reports.lock().unwrap().push(progress);
+
+    let options = CalculationOptions {
+        progress: Some(&record),
+        ..Default::default()
+    };
+
+    calculate_nimbers_up_to_with(2000, &rules, &options).unwrap();
+
+    let reports = reports.into_inner().unwrap();
+    assert!(reports.len() > 2);
+    assert!(reports.windows(2).all(|w| w[0].done <= w[1].done));
+    assert!(reports.iter().all(|p| p.total == 2001));
+    assert_eq!(reports.last().unwrap().done, 2001);
+}
+
+#[test]
+fn cancellation() {
+    let rules = vec![NimRule {
+        take: TakeSize::List(vec![2, 3]),
+        split: Split::Always,
+    }];
+
+    // Cancel the calculation as soon as some progress was made
+    let token = CancellationToken::new();
+    let cancel = |progress: Progress| {
+        if progress.done >= 512 {
+            token.cancel();
+        }
+    };
+
+    let options = CalculationOptions {
+        progress: Some(&cancel),
+        cancellation: Some(&token),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        calculate_nimbers_up_to_with(100_000, &rules, &options),
+        Err(NimberError::Cancelled)
+    );
+    assert!(token.is_cancelled());
+
+    // The heights calculated before the cancellation are kept
+    assert_eq!(
+        calculate_nimbers_up_to(1000, &rules).unwrap(),
+        calculate_nimbers_up_to_with(1000, &rules, &Default::default()).unwrap()
+    );
+}
+
+#[test]
+fn single_heights_report_progress() {
+    let rules = vec![NimRule {
+        take: TakeSize::List(vec![1, 3]),
+        split: Split::Always,
+    }];
+
+    let reports = Mutex::new(Vec::new());
+    let record = |progress: Progress| reports.lock().unwrap().push(progress);
+    let options = CalculationOptions {
+        progress: Some(&record),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        calculate_nimber_for_height_with(3000, &rules, &options),
+        calculate_nimber_for_height(3000, &rules, 0)
+    );
+
+    let reports = reports.into_inner().unwrap();
+    assert_eq!(reports.last().unwrap().done, 3001);
+}
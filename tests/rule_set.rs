@@ -0,0 +1,225 @@
+use nimlib::{
+    moves::calculate_legal_moves,
+    nimbers::{calculate_nimber_for_height, calculate_nimbers_up_to, NimberError},
+    NimAction, NimGame, NimRule, NimSplit, Player, RuleProblem, RuleSet, RuleSetError, Split,
+    Stack, TakeSize,
+};
+fn list(takes: &[u64], split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::List(takes.to_vec()),
+        split,
+    }
+}
+
+fn rule_set<const N: usize>(rules: [NimRule; N]) -> RuleSet {
+    RuleSet::new(rules).unwrap()
+}
+
+fn any(split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::Any,
+        split,
+    }
+}
+
+#[test]
+fn order_and_duplicates() {
+    let a = rule_set([list(&[1], Split::Never), list(&[2], Split::Never)]);
+    let b = rule_set([list(&[2], Split::Never), list(&[1], Split::Never)]);
+    let c = rule_set([list(&[2, 1, 1], Split::Never)]);
+
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+    assert_eq!(a.rules(), &[list(&[1, 2], Split::Never)]);
+}
+
+#[test]
+fn merge_split_modes() {
+    let rules = rule_set([
+        list(&[3], Split::Never),
+        list(&[3, 4], Split::Always),
+        list(&[5], Split::Optional),
+    ]);
+
+    assert_eq!(
+        rules.rules(),
+        &[list(&[3, 5], Split::Optional), list(&[4], Split::Always),]
+    );
+}
+
+#[test]
+fn fold_into_any() {
+    // Taking 0 coins is not covered by `Any`
+    let rules = rule_set([
+        list(&[1, 2], Split::Never),
+        list(&[0], Split::Always),
+        list(&[3], Split::Optional),
+        any(Split::Never),
+    ]);
+
+    assert_eq!(
+        rules.rules(),
+        &[list(&[0, 3], Split::Always), any(Split::Never),]
+    );
+
+    assert_eq!(
+        rule_set([
+            any(Split::Never),
+            any(Split::Always),
+            list(&[7], Split::Optional)
+        ]),
+        rule_set([any(Split::Optional)])
+    );
+}
+
+#[test]
+fn stable_fingerprint() {
+    let kayles = rule_set([list(&[2, 1], Split::Optional)]);
+
+    assert_eq!(kayles.fingerprint(), 13_715_820_252_630_367_373);
+    assert_ne!(
+        kayles.fingerprint(),
+        rule_set([list(&[1, 2], Split::Never)]).fingerprint()
+    );
+}
+
+#[test]
+fn equivalent_games() {
+    let a = NimGame::new(
+        rule_set([list(&[1, 2], Split::Never), list(&[2], Split::Never)]),
+        vec![Stack(5)],
+    );
+    let b = NimGame::new(rule_set([list(&[2, 1], Split::Never)]), vec![Stack(5)]);
+
+    assert_eq!(a, b);
+    assert_eq!(a.calculate_nimber(), b.calculate_nimber());
+}
+
+#[test]
+fn equivalent_rules_share_nimbers() {
+    let a = vec![list(&[1], Split::Optional), list(&[2], Split::Optional)];
+    let b = vec![list(&[2, 1], Split::Never), list(&[1, 2], Split::Always)];
+
+    assert_eq!(
+        calculate_nimbers_up_to(100, &a).unwrap(),
+        calculate_nimbers_up_to(100, &b).unwrap()
+    );
+}
+
+#[test]
+fn serde_round_trip() {
+    let rules = rule_set([list(&[3, 1], Split::Never), list(&[1], Split::Never)]);
+
+    let json = serde_json::to_string(&rules).unwrap();
+    assert_eq!(json, r#"[{"rule":{"TakeList":[1,3]},"split":"Never"}]"#);
+
+    let parsed: RuleSet =
+        serde_json::from_str(r#"[{"rule":{"TakeList":[3,1,3]},"split":"Never"}]"#).unwrap();
+    assert_eq!(parsed, rules);
+    assert_eq!(parsed.fingerprint(), rules.fingerprint());
+}
+
+#[test]
+fn every_problem_is_listed() {
+    let rules = vec![
+        list(&[1], Split::Never),
+        NimRule {
+            take: TakeSize::Place,
+            split: Split::Optional,
+        },
+        list(&[], Split::Always),
+        list(&[0, 2], Split::Never),
+        list(&[1], Split::Never),
+    ];
+
+    assert_eq!(
+        RuleSet::new(rules),
+        Err(RuleSetError {
+            problems: vec![
+                RuleProblem::PlaceWithSplit { rule: 1 },
+                RuleProblem::EmptyTakeList { rule: 2 },
+                RuleProblem::ZeroTakeWithoutSplit { rule: 3 },
+                RuleProblem::DuplicateRule { rule: 4, first: 0 },
+            ]
+        })
+    );
+
+    // Taking 0 coins is fine if the stack is always split
+    assert!(RuleSet::new([list(&[0], Split::Always)]).is_ok());
+}
+
+#[test]
+fn optional_zero_take_only_splits() {
+    // Taking 0 coins without splitting would be a pass, so only the splits are moves
+    let optional = rule_set([list(&[0, 2], Split::Optional)]);
+    assert_eq!(
+        optional,
+        rule_set([list(&[0], Split::Always), list(&[2], Split::Optional)])
+    );
+
+    let moves = calculate_legal_moves(
+        &[Stack(3)],
+        &[list(&[0], Split::Optional)],
+        &[0, 0],
+        Player::A,
+        None,
+    )
+    .unwrap();
+    assert_eq!(moves.len(), 1);
+
+    let game = NimGame::new(optional, vec![Stack(3)]);
+    assert!(game.calculate_legal_moves().iter().all(
+        |mov| matches!(mov, NimAction::Take(take) if take.amount > 0 || take.split != NimSplit::No)
+    ));
+}
+
+#[test]
+fn invalid_rules_are_errors() {
+    let place_with_split = vec![NimRule {
+        take: TakeSize::Place,
+        split: Split::Always,
+    }];
+    let error = RuleSetError {
+        problems: vec![RuleProblem::PlaceWithSplit { rule: 0 }],
+    };
+
+    assert_eq!(
+        calculate_legal_moves(&[Stack(3)], &place_with_split, &[2, 2], Player::A, None),
+        Err(error.clone())
+    );
+    assert_eq!(
+        calculate_nimber_for_height(3, &place_with_split, 0),
+        Err(NimberError::InvalidRules(error.clone()))
+    );
+    assert_eq!(
+        Stack(3).calculate_nimber(&place_with_split, 0),
+        Err(NimberError::InvalidRules(error))
+    );
+}
+
+#[test]
+fn pool_coins_are_errors() {
+    let rules = vec![NimRule {
+        take: TakeSize::List(vec![1, 2]),
+        split: Split::Never,
+    }];
+
+    assert_eq!(
+        calculate_nimber_for_height(3, &rules, 2),
+        Err(NimberError::PoolCoinsUnsupported { pool_coins: 2 })
+    );
+    assert_eq!(
+        Stack(3).calculate_nimber(&rules, 1),
+        Err(NimberError::PoolCoinsUnsupported { pool_coins: 1 })
+    );
+}
+
+#[test]
+fn deserializing_validates() {
+    let result = serde_json::from_str::<RuleSet>(r#"[{"rule":{"TakeList":[]},"split":"Never"}]"#);
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Rule 0: The list of take sizes is empty"));
+}
@@ -8,14 +8,14 @@ fn simple_123_game() {
     }];
 
     // Hand-verified nimbers
-    assert_eq!(Stack(0).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(1).calculate_nimber(&simple_rules, 0), Nimber(1));
-    assert_eq!(Stack(2).calculate_nimber(&simple_rules, 0), Nimber(2));
-    assert_eq!(Stack(3).calculate_nimber(&simple_rules, 0), Nimber(3));
-    assert_eq!(Stack(4).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(5).calculate_nimber(&simple_rules, 0), Nimber(1));
-    assert_eq!(Stack(6).calculate_nimber(&simple_rules, 0), Nimber(2));
-    assert_eq!(Stack(7).calculate_nimber(&simple_rules, 0), Nimber(3));
+    assert_eq!(Stack(0).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(1).calculate_nimber(&simple_rules, 0), Ok(Nimber(1)));
+    assert_eq!(Stack(2).calculate_nimber(&simple_rules, 0), Ok(Nimber(2)));
+    assert_eq!(Stack(3).calculate_nimber(&simple_rules, 0), Ok(Nimber(3)));
+    assert_eq!(Stack(4).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(5).calculate_nimber(&simple_rules, 0), Ok(Nimber(1)));
+    assert_eq!(Stack(6).calculate_nimber(&simple_rules, 0), Ok(Nimber(2)));
+    assert_eq!(Stack(7).calculate_nimber(&simple_rules, 0), Ok(Nimber(3)));
 }
 
 #[test]
@@ -26,12 +26,12 @@ fn advanced_23_game() {
     }];
 
     // Hand-verified nimbers
-    assert_eq!(Stack(0).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(1).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(2).calculate_nimber(&simple_rules, 0), Nimber(1));
-    assert_eq!(Stack(3).calculate_nimber(&simple_rules, 0), Nimber(1));
-    assert_eq!(Stack(4).calculate_nimber(&simple_rules, 0), Nimber(2));
-    assert_eq!(Stack(5).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(6).calculate_nimber(&simple_rules, 0), Nimber(0));
-    assert_eq!(Stack(7).calculate_nimber(&simple_rules, 0), Nimber(1));
+    assert_eq!(Stack(0).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(1).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(2).calculate_nimber(&simple_rules, 0), Ok(Nimber(1)));
+    assert_eq!(Stack(3).calculate_nimber(&simple_rules, 0), Ok(Nimber(1)));
+    assert_eq!(Stack(4).calculate_nimber(&simple_rules, 0), Ok(Nimber(2)));
+    assert_eq!(Stack(5).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(6).calculate_nimber(&simple_rules, 0), Ok(Nimber(0)));
+    assert_eq!(Stack(7).calculate_nimber(&simple_rules, 0), Ok(Nimber(1)));
 }
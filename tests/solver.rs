@@ -0,0 +1,122 @@
+use nimlib::{
+    solver::{self, Outcome, SolveError, Solver},
+    NimGame, NimRule, PlayConvention, RuleSet, Split, Stack, TakeSize,
+};
+
+fn rules(rules: impl IntoIterator<Item = NimRule>) -> RuleSet {
+    RuleSet::new(rules).unwrap()
+}
+
+#[test]
+fn nimbers_agree_with_the_solver() {
+    let rule_sets = [
+        // Kayles
+        rules([NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Optional,
+        }]),
+        // Grundy's game
+        rules([NimRule {
+            take: TakeSize::List(vec![0]),
+            split: Split::Always,
+        }]),
+        rules([NimRule {
+            take: TakeSize::Any,
+            split: Split::Optional,
+        }]),
+        rules([
+            NimRule {
+                take: TakeSize::List(vec![1, 3]),
+                split: Split::Never,
+            },
+            NimRule {
+                take: TakeSize::List(vec![2]),
+                split: Split::Always,
+            },
+        ]),
+    ];
+
+    for rules in &rule_sets {
+        let verification = solver::verify(rules, 7, 3).unwrap();
+
+        assert_eq!(verification.positions, 120);
+        assert_eq!(verification.discrepancies, vec![], "{rules:?}");
+    }
+}
+
+#[test]
+fn misere_nim() {
+    let rules = rules([NimRule {
+        take: TakeSize::Any,
+        split: Split::Never,
+    }]);
+    let mut solver = Solver::new();
+    let mut solve = |stacks: Vec<u64>| {
+        let game = NimGame::builder()
+            .rules(rules.to_vec())
+            .stacks(stacks.into_iter().map(Stack).collect())
+            .convention(PlayConvention::Misere)
+            .build()
+            .unwrap();
+        solver.solve(&game).unwrap()
+    };
+
+    // With stacks of height 1 only, the player to move wins with an even number of stacks
+    assert_eq!(solve(vec![]), Outcome::Win);
+    assert_eq!(solve(vec![1]), Outcome::Loss);
+    assert_eq!(solve(vec![1, 1]), Outcome::Win);
+    assert_eq!(solve(vec![1, 1, 1]), Outcome::Loss);
+
+    // Otherwise, the player to move wins if the nimber is not 0, just like under normal play
+    assert_eq!(solve(vec![2, 2]), Outcome::Loss);
+    assert_eq!(solve(vec![1, 2, 3]), Outcome::Loss);
+    assert_eq!(solve(vec![1, 2, 4]), Outcome::Win);
+}
+
+#[test]
+fn outcomes_are_remembered() {
+    let game = NimGame::default();
+    let mut solver = Solver::new();
+
+    assert_eq!(solver.solve(&game), Ok(Outcome::Win));
+    let solved = solver.solved_positions();
+    assert_eq!(solved, 11);
+
+    assert_eq!(solver.solve(&game), Ok(Outcome::Win));
+    assert_eq!(solver.solved_positions(), solved);
+}
+
+#[test]
+fn place_rules_are_rejected() {
+    let game = NimGame::new(
+        rules([NimRule {
+            take: TakeSize::Place,
+            split: Split::Never,
+        }]),
+        vec![Stack(3)],
+    );
+
+    assert_eq!(solver::solve(&game), Err(SolveError::PlaceRule));
+}
+
+#[test]
+fn verify_rejects_rules_beyond_single_stacks() {
+    for take in [
+        TakeSize::AnyFromStacks(2),
+        TakeSize::SameFromTwoStacks,
+        TakeSize::UpToMultipleOfLast(2),
+        TakeSize::TransferToNeighbour,
+        TakeSize::TransferToLower,
+    ] {
+        let rules = rules([NimRule {
+            take: take.clone(),
+            split: Split::Never,
+        }]);
+
+        assert_eq!(
+            solver::verify(&rules, 4, 2),
+            Err(SolveError::NotStackwise),
+            "{take:?}"
+        );
+    }
+}
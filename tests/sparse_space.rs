@@ -0,0 +1,154 @@
+use nimlib::{
+    nimbers::{
+        calculate_nimbers_up_to, calculate_nimbers_up_to_with, Algorithm, CalculationOptions,
+    },
+    NimRule, Split, TakeSize,
+};
+
+/// A straightforward MEX implementation over all options of every height
+fn reference_nimbers(rules: &[NimRule], max_height: usize) -> Vec<u64> {
+    let mut nimbers: Vec<u64> = Vec::with_capacity(max_height + 1);
+
+    for height in 0..=max_height {
+        let mut options = Vec::new();
+
+        for NimRule { take, split } in rules {
+            let takes = match take {
+                TakeSize::List(list) => list.iter().map(|&t| t as usize).collect(),
+                TakeSize::Any | TakeSize::AnyFromStacks(_) => (1..=height).collect(),
+                TakeSize::Place
+                | TakeSize::SameFromTwoStacks
+                | TakeSize::UpToMultipleOfLast(_)
+                | TakeSize::TransferToNeighbour
+                | TakeSize::TransferToLower => vec![],
+            };
+
+            for take in takes.into_iter().filter(|&t| t <= height) {
+                let remainder = height - take;
+
+                if *split != Split::Always && take > 0 {
+                    options.push(nimbers[remainder]);
+                }
+
+                if *split != Split::Never {
+                    for a in 1..=remainder / 2 {
+                        options.push(nimbers[a] ^ nimbers[remainder - a]);
+                    }
+                }
+            }
+        }
+
+        let mut nimber = 0;
+        while options.contains(&nimber) {
+            nimber += 1;
+        }
+        nimbers.push(nimber);
+    }
+
+    nimbers
+}
+
+const SPARSE_SPACE: CalculationOptions = CalculationOptions {
+    algorithm: Algorithm::SparseSpace,
+    progress: None,
+    cancellation: None,
+};
+
+fn check_sparse_space(rules: &[NimRule], max_height: u64) {
+    let nimbers = calculate_nimbers_up_to_with(max_height, rules, &SPARSE_SPACE).unwrap();
+
+    assert_eq!(
+        nimbers.into_iter().map(|n| n.0).collect::<Vec<_>>(),
+        reference_nimbers(rules, max_height as usize),
+        "Nimbers differ for rules {rules:?}"
+    );
+}
+
+#[test]
+fn take_one_split_always() {
+    check_sparse_space(
+        &[NimRule {
+            take: TakeSize::List(vec![1]),
+            split: Split::Always,
+        }],
+        3000,
+    );
+}
+
+#[test]
+fn kayles() {
+    check_sparse_space(
+        &[NimRule {
+            take: TakeSize::List(vec![1, 2]),
+            split: Split::Optional,
+        }],
+        3000,
+    );
+}
+
+#[test]
+fn fancy_rules() {
+    check_sparse_space(
+        &[
+            NimRule {
+                take: TakeSize::List(vec![1, 3]),
+                split: Split::Never,
+            },
+            NimRule {
+                take: TakeSize::List(vec![6]),
+                split: Split::Always,
+            },
+            NimRule {
+                take: TakeSize::List(vec![9]),
+                split: Split::Optional,
+            },
+        ],
+        3000,
+    );
+}
+
+#[test]
+fn split_without_taking() {
+    check_sparse_space(
+        &[
+            NimRule {
+                take: TakeSize::List(vec![0]),
+                split: Split::Always,
+            },
+            NimRule {
+                take: TakeSize::List(vec![2]),
+                split: Split::Never,
+            },
+        ],
+        2500,
+    );
+}
+
+#[test]
+fn take_any_split_falls_back() {
+    check_sparse_space(
+        &[NimRule {
+            take: TakeSize::Any,
+            split: Split::Optional,
+        }],
+        150,
+    );
+}
+
+#[test]
+fn switch_algorithms() {
+    let rules = [NimRule {
+        take: TakeSize::List(vec![2, 3]),
+        split: Split::Optional,
+    }];
+
+    // Extend the same table with both algorithms alternately
+    let _ = calculate_nimbers_up_to_with(1500, &rules, &SPARSE_SPACE);
+    let _ = calculate_nimbers_up_to(2000, &rules).unwrap();
+    let nimbers = calculate_nimbers_up_to_with(2600, &rules, &SPARSE_SPACE).unwrap();
+
+    assert_eq!(
+        nimbers.into_iter().map(|n| n.0).collect::<Vec<_>>(),
+        reference_nimbers(&rules, 2600)
+    );
+}
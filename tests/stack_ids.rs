@@ -0,0 +1,175 @@
+use nimlib::{
+    moves::{self, MoveError},
+    NimAction, NimGame, NimRule, NimSplit, PlaceAction, Player, Split, Stack, StackId, TakeAction,
+    TakeSize,
+};
+
+fn splitting_game() -> NimGame {
+    NimGame::builder()
+        .rules([NimRule {
+            take: TakeSize::List(vec![1]),
+            split: Split::Optional,
+        }])
+        .stacks(vec![Stack(5), Stack(3), Stack(4)])
+        .stack_ids(true)
+        .build()
+        .unwrap()
+}
+
+fn take_from(stack_id: StackId, split: NimSplit) -> NimAction {
+    NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: 1,
+        split,
+        from: None,
+        stack_id: Some(stack_id),
+    })
+}
+
+#[test]
+fn ids_survive_splits() {
+    let mut game = splitting_game();
+    assert_eq!(
+        game.get_stack_ids(),
+        Some(&[StackId(0), StackId(1), StackId(2)][..])
+    );
+
+    // Split the first stack, shifting the other stacks
+    let changes = moves::apply_move(
+        &mut game,
+        &take_from(StackId(0), NimSplit::Yes(Stack(2), Stack(2))),
+    )
+    .unwrap();
+    assert_eq!(changes.removed, vec![StackId(0)]);
+    assert_eq!(changes.created, vec![StackId(3), StackId(4)]);
+    assert_eq!(
+        game.get_stacks(),
+        &vec![Stack(2), Stack(2), Stack(3), Stack(4)]
+    );
+    assert_eq!(game.find_stack(StackId(2)), Some(3));
+    assert_eq!(game.find_stack(StackId(0)), None);
+
+    // The last stack can still be found by its ID
+    let changes = moves::apply_move(&mut game, &take_from(StackId(2), NimSplit::No)).unwrap();
+    assert!(changes.removed.is_empty() && changes.created.is_empty());
+    assert_eq!(
+        game.get_stacks(),
+        &vec![Stack(2), Stack(2), Stack(3), Stack(3)]
+    );
+}
+
+#[test]
+fn unknown_ids_are_rejected() {
+    let mut game = splitting_game();
+    moves::apply_move(
+        &mut game,
+        &take_from(StackId(0), NimSplit::Yes(Stack(1), Stack(3))),
+    )
+    .unwrap();
+
+    assert_eq!(
+        moves::check_move(&game, &take_from(StackId(0), NimSplit::No)),
+        Err(MoveError::NoSuchStackId {
+            stack_id: StackId(0)
+        })
+    );
+
+    game.set_stack_ids(false);
+    assert_eq!(
+        moves::check_move(&game, &take_from(StackId(1), NimSplit::No)),
+        Err(MoveError::NoSuchStackId {
+            stack_id: StackId(1)
+        })
+    );
+}
+
+#[test]
+fn legal_moves_refer_to_ids() {
+    let mut game = splitting_game();
+    moves::apply_move(
+        &mut game,
+        &take_from(StackId(1), NimSplit::Yes(Stack(1), Stack(1))),
+    )
+    .unwrap();
+
+    for mov in game.calculate_legal_moves() {
+        let NimAction::Take(take) = &mov else {
+            panic!("Only take moves are possible");
+        };
+        assert_eq!(
+            take.stack_id,
+            game.get_stack_ids().unwrap().get(take.stack_index).copied()
+        );
+        assert!(moves::check_move(&game, &mov).is_ok());
+    }
+}
+
+#[test]
+fn validated_moves_check_ids() {
+    let mut game = splitting_game();
+    let mov = moves::check_move(&game, &take_from(StackId(0), NimSplit::No)).unwrap();
+
+    // A new stack with the same height is a different stack
+    game.set_stacks(vec![Stack(5), Stack(3), Stack(4)]).unwrap();
+    assert_eq!(
+        moves::apply_validated_move(&mut game, mov),
+        Err(MoveError::StaleMove)
+    );
+}
+
+#[test]
+fn ids_are_optional() {
+    let mut game = NimGame::builder()
+        .rules([
+            NimRule {
+                take: TakeSize::List(vec![1]),
+                split: Split::Never,
+            },
+            NimRule {
+                take: TakeSize::Place,
+                split: Split::Never,
+            },
+        ])
+        .stacks(vec![Stack(1)])
+        .pool_coins(Player::A, 1)
+        .build()
+        .unwrap();
+
+    assert_eq!(game.get_stack_ids(), None);
+
+    let place = NimAction::Place(PlaceAction {
+        stack_index: 0,
+        amount: 1,
+        from: Player::A,
+        stack_id: None,
+    });
+    let changes = moves::apply_move(&mut game, &place).unwrap();
+    assert!(changes.removed.is_empty() && changes.created.is_empty());
+
+    // Serialized games without IDs don't mention them
+    assert!(!serde_json::to_string(&game).unwrap().contains("stack_id"));
+}
+
+#[test]
+fn deserializing_rejects_duplicate_ids() {
+    let game = splitting_game();
+    let json = serde_json::to_string(&game).unwrap();
+    assert_eq!(serde_json::from_str::<NimGame>(&json).unwrap(), game);
+
+    let json = json.replace(r#""ids":[0,1,2]"#, r#""ids":[7,1,7]"#);
+    assert!(serde_json::from_str::<NimGame>(&json)
+        .unwrap_err()
+        .to_string()
+        .contains("Several stacks have the same ID #7"));
+}
+
+#[test]
+fn deserializing_rejects_missing_ids() {
+    let json = serde_json::to_string(&splitting_game())
+        .unwrap()
+        .replace(r#""ids":[0,1,2]"#, r#""ids":[0,1]"#);
+    assert!(serde_json::from_str::<NimGame>(&json)
+        .unwrap_err()
+        .to_string()
+        .contains("There are 2 stack IDs for 3 stack(s)"));
+}
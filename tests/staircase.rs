@@ -0,0 +1,259 @@
+mod common;
+
+use common::sequences;
+use nimlib::{
+    moves::{self, MoveError},
+    solver::{Outcome, SolveError, Solver},
+    staircase, NimAction, NimGame, NimRule, Nimber, PlayConvention, RuleProblem, RuleSet, Split,
+    Stack, StackId, TakeSize, TransferAction,
+};
+
+fn transfer_rule(take: TakeSize) -> NimRule {
+    NimRule {
+        take,
+        split: Split::Never,
+    }
+}
+
+fn staircase_nim() -> RuleSet {
+    RuleSet::new([transfer_rule(TakeSize::TransferToNeighbour)]).unwrap()
+}
+
+fn transfer(stack_index: usize, target_index: usize, amount: u64) -> NimAction {
+    NimAction::Transfer(TransferAction {
+        stack_index,
+        target_index,
+        amount,
+        stack_id: None,
+        target_id: None,
+    })
+}
+
+/// Calculate the nimber of a position from the nimbers of its options
+fn brute_force_nimber(game: &NimGame) -> u64 {
+    let options: Vec<u64> = game
+        .calculate_legal_moves()
+        .iter()
+        .map(|mov| {
+            let mut option = game.clone();
+            moves::apply_move(&mut option, mov).unwrap();
+            brute_force_nimber(&option)
+        })
+        .collect();
+
+    (0..).find(|nimber| !options.contains(nimber)).unwrap()
+}
+
+#[test]
+fn canonical_rules() {
+    // Moving coins onto any stack before covers moving them onto the stack right before
+    let rules = RuleSet::new([
+        transfer_rule(TakeSize::TransferToNeighbour),
+        transfer_rule(TakeSize::TransferToLower),
+    ])
+    .unwrap();
+    assert_eq!(rules.rules(), &[transfer_rule(TakeSize::TransferToLower)]);
+    assert_ne!(rules.fingerprint(), staircase_nim().fingerprint());
+    assert!(rules.depends_on_stack_order());
+    assert!(!NimGame::default().get_rules().depends_on_stack_order());
+
+    assert_eq!(
+        RuleSet::new([NimRule {
+            take: TakeSize::TransferToNeighbour,
+            split: Split::Optional,
+        }])
+        .unwrap_err()
+        .problems,
+        vec![RuleProblem::SplitAcrossStacks { rule: 0 }]
+    );
+}
+
+#[test]
+fn transfer_moves() {
+    let game = NimGame::new(staircase_nim(), vec![Stack(1), Stack(2), Stack(1)]);
+    let moves = game.calculate_legal_moves();
+
+    // No coins can be moved from the ground
+    assert_eq!(
+        moves,
+        vec![transfer(1, 0, 1), transfer(1, 0, 2), transfer(2, 1, 1)]
+    );
+    for mov in &moves {
+        assert!(moves::check_move(&game, mov).is_ok(), "{mov:?}");
+    }
+
+    // Coins may skip stacks with a rule for any stack before
+    let game = NimGame::new(
+        RuleSet::new([transfer_rule(TakeSize::TransferToLower)]).unwrap(),
+        vec![Stack(1), Stack(2), Stack(1)],
+    );
+    assert_eq!(game.calculate_legal_moves().len(), 2 + 2);
+    assert!(moves::check_move(&game, &transfer(2, 0, 1)).is_ok());
+}
+
+#[test]
+fn invalid_transfers() {
+    let game = NimGame::new(staircase_nim(), vec![Stack(2), Stack(3), Stack(4)]);
+
+    assert_eq!(
+        moves::check_move(&game, &transfer(2, 0, 1)),
+        Err(MoveError::NoSuchTransferRule {
+            stack_index: 2,
+            target_index: 0,
+        })
+    );
+    assert_eq!(
+        moves::check_move(&game, &transfer(0, 1, 1)),
+        Err(MoveError::NoSuchTransferRule {
+            stack_index: 0,
+            target_index: 1,
+        })
+    );
+    assert_eq!(
+        moves::check_move(&game, &transfer(1, 0, 0)),
+        Err(MoveError::NoSuchRule { amount: 0 })
+    );
+    assert_eq!(
+        moves::check_move(&game, &transfer(1, 0, 4)),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 1,
+            amount: 4,
+            available: 3,
+        })
+    );
+    assert_eq!(
+        moves::check_move(&game, &transfer(3, 2, 1)),
+        Err(MoveError::NoSuchStack {
+            stack_index: 3,
+            stack_count: 3,
+        })
+    );
+
+    // Ordinary Nim doesn't allow moving coins between stacks
+    let game = NimGame::new(
+        RuleSet::new([NimRule {
+            take: TakeSize::Any,
+            split: Split::Never,
+        }])
+        .unwrap(),
+        vec![Stack(2), Stack(3)],
+    );
+    assert_eq!(
+        moves::check_move(&game, &transfer(1, 0, 1)),
+        Err(MoveError::NoSuchTransferRule {
+            stack_index: 1,
+            target_index: 0,
+        })
+    );
+}
+
+#[test]
+fn apply_and_undo_transfers() {
+    let mut game = NimGame::builder()
+        .rules([transfer_rule(TakeSize::TransferToNeighbour)])
+        .stacks(vec![Stack(5), Stack(0), Stack(3), Stack(2), Stack(0)])
+        .stack_ids(true)
+        .auto_normalize(true)
+        .build()
+        .unwrap();
+
+    // Normalizing keeps the order of the stacks, and only drops the empty stacks at the end
+    assert_eq!(
+        game.get_stacks(),
+        &vec![Stack(5), Stack(0), Stack(3), Stack(2)]
+    );
+    let before = game.clone();
+
+    let mov = NimAction::Transfer(TransferAction {
+        stack_index: 0,
+        target_index: 0,
+        amount: 2,
+        stack_id: Some(StackId(3)),
+        target_id: Some(StackId(2)),
+    });
+    let changes = moves::apply_move(&mut game, &mov).unwrap();
+
+    // The emptied last stack is dropped, the other stacks keep their places and IDs
+    assert_eq!(changes.removed, vec![StackId(3)]);
+    assert!(changes.created.is_empty());
+    assert_eq!(game.get_stacks(), &vec![Stack(5), Stack(0), Stack(5)]);
+    assert_eq!(
+        game.get_stack_ids(),
+        Some(&[StackId(0), StackId(1), StackId(2)][..])
+    );
+    assert_eq!(game.get_last_take(), None);
+
+    moves::undo_move(&mut game, changes).unwrap();
+    assert_eq!(game, before);
+
+    // The order of the stacks is part of the canonical position
+    let a = NimGame::new(
+        staircase_nim(),
+        vec![Stack(0), Stack(1), Stack(2), Stack(0)],
+    );
+    let b = NimGame::new(staircase_nim(), vec![Stack(0), Stack(2), Stack(1)]);
+    let c = NimGame::new(staircase_nim(), vec![Stack(0), Stack(1), Stack(2)]);
+    assert_ne!(a.canonical_position(), b.canonical_position());
+    assert_eq!(a.canonical_position(), c.canonical_position());
+}
+
+#[test]
+fn odd_steps_agree_with_solver() {
+    for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+        let mut solver = Solver::new();
+
+        for stack_count in 0..=5 {
+            for stacks in sequences(stack_count, 3) {
+                let mut game = NimGame::new(staircase_nim(), stacks.clone());
+                game.set_convention(convention);
+
+                let outcome = solver.solve(&game);
+                assert_eq!(
+                    staircase::solve(&game),
+                    outcome,
+                    "{convention:?}: {stacks:?}"
+                );
+
+                // A player who wins by moving has a winning move, which leads to a lost position
+                let mov = staircase::winning_move(&game).unwrap();
+                let can_move = !game.calculate_legal_moves().is_empty();
+                assert_eq!(
+                    mov.is_some(),
+                    can_move && outcome == Ok(Outcome::Win),
+                    "{convention:?}: {stacks:?}"
+                );
+                if let Some(mov) = mov {
+                    let mut next = game.clone();
+                    moves::apply_move(&mut next, &mov).unwrap();
+                    assert_eq!(solver.solve(&next), Ok(Outcome::Loss), "{stacks:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn nimber_of_odd_steps() {
+    for stacks in sequences(4, 2) {
+        let game = NimGame::new(staircase_nim(), stacks.clone());
+        assert_eq!(
+            staircase::nimber(&game),
+            Ok(Nimber(brute_force_nimber(&game))),
+            "{stacks:?}"
+        );
+    }
+}
+
+#[test]
+fn only_staircase_nim() {
+    let game = NimGame::default();
+    assert_eq!(staircase::solve(&game), Err(SolveError::NotStaircaseNim));
+
+    // Games where coins may skip stacks are solved by brute force
+    let game = NimGame::new(
+        RuleSet::new([transfer_rule(TakeSize::TransferToLower)]).unwrap(),
+        vec![Stack(0), Stack(2), Stack(1), Stack(1)],
+    );
+    assert_eq!(staircase::solve(&game), Err(SolveError::NotStaircaseNim));
+    assert_eq!(Solver::new().solve(&game), Ok(Outcome::Win));
+}
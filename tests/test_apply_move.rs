@@ -1,4 +1,8 @@
-use nimlib::{moves, NimGame, NimRule, Split, Stack, TakeSize};
+use nimlib::{
+    moves::{self, MoveError},
+    NimAction, NimGame, NimRule, NimSplit, PlaceAction, Player, RuleSet, Split, Stack, TakeAction,
+    TakeSize,
+};
 
 #[test]
 fn test_apply_move_1() {
@@ -9,12 +13,12 @@ fn test_apply_move_1() {
 
     let stacks = vec![Stack(5)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
     // TODO maybe add more checks here
     assert_eq!(moves.len(), 3);
 
-    let mut game = NimGame::new(rules, stacks);
+    let mut game = NimGame::new(RuleSet::new(rules.clone()).unwrap(), stacks);
 
     // Get the values before application and check them
     let stacks = game.get_stacks();
@@ -37,12 +41,12 @@ fn test_apply_move_2() {
 
     let stacks = vec![Stack(5)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
     // TODO maybe add more checks here
     assert_eq!(moves.len(), 1);
 
-    let mut game = NimGame::new(rules, stacks);
+    let mut game = NimGame::new(RuleSet::new(rules.clone()).unwrap(), stacks);
 
     // Get the values before application and check them
     let stacks = game.get_stacks();
@@ -55,3 +59,146 @@ fn test_apply_move_2() {
 
     assert_eq!(stacks[0], Stack(2));
 }
+
+#[test]
+fn apply_validated_move() {
+    let rules = RuleSet::new(vec![NimRule {
+        take: TakeSize::List(vec![1]),
+        split: Split::Optional,
+    }])
+    .unwrap();
+
+    let mut game = NimGame::new(rules, vec![Stack(4), Stack(2)]);
+    let mov = NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: 1,
+        split: NimSplit::Yes(Stack(1), Stack(2)),
+        from: None,
+        stack_id: None,
+    });
+
+    let validated = moves::check_move(&game, &mov).unwrap();
+    moves::apply_validated_move(&mut game, validated.clone()).unwrap();
+
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(2), Stack(2)]);
+
+    // The first stack changed, so the move must be checked again
+    assert_eq!(
+        moves::apply_validated_move(&mut game, validated),
+        Err(MoveError::StaleMove)
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(1), Stack(2), Stack(2)]);
+}
+
+#[test]
+fn overflow_is_an_error() {
+    let mut game = NimGame::builder()
+        .rules([NimRule {
+            take: TakeSize::Place,
+            split: Split::Never,
+        }])
+        .stacks(vec![Stack(u64::MAX - 2)])
+        .pool_coins(Player::A, 5)
+        .build()
+        .unwrap();
+
+    let place = |amount| {
+        NimAction::Place(PlaceAction {
+            stack_index: 0,
+            amount,
+            from: Player::A,
+            stack_id: None,
+        })
+    };
+
+    assert_eq!(
+        moves::apply_move(&mut game, &place(3)),
+        Err(MoveError::StackOverflow { stack_index: 0 })
+    );
+    assert_eq!(
+        moves::apply_move(&mut game, &place(6)),
+        Err(MoveError::NotEnoughCoinsOnPlayer {
+            player: Player::A,
+            amount: 6,
+            available: 5
+        })
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(u64::MAX - 2)]);
+
+    moves::apply_move(&mut game, &place(2)).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(u64::MAX)]);
+}
+
+#[test]
+fn pools_of_the_player_to_move() {
+    let mut game = NimGame::builder()
+        .rules([
+            NimRule {
+                take: TakeSize::List(vec![1]),
+                split: Split::Never,
+            },
+            NimRule {
+                take: TakeSize::Place,
+                split: Split::Never,
+            },
+        ])
+        .stacks(vec![Stack(2)])
+        .pool_coins(Player::A, 1)
+        .pool_coins(Player::B, 2)
+        .build()
+        .unwrap();
+
+    assert_eq!(game.get_to_move(), Player::A);
+
+    // Only player A's pool is used, and taken coins go to player A
+    assert_eq!(
+        game.calculate_legal_moves(),
+        vec![
+            NimAction::Take(TakeAction {
+                stack_index: 0,
+                amount: 1,
+                split: NimSplit::No,
+                from: Some(Player::A),
+                stack_id: None,
+            }),
+            NimAction::Place(PlaceAction {
+                stack_index: 0,
+                amount: 1,
+                from: Player::A,
+                stack_id: None,
+            }),
+        ]
+    );
+
+    let place_b = NimAction::Place(PlaceAction {
+        stack_index: 0,
+        amount: 2,
+        from: Player::B,
+        stack_id: None,
+    });
+    assert_eq!(
+        moves::apply_move(&mut game, &place_b),
+        Err(MoveError::NotYourTurn {
+            player: Player::B,
+            to_move: Player::A
+        })
+    );
+
+    // The pool is filled in automatically
+    let take = NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: 1,
+        split: NimSplit::No,
+        from: None,
+        stack_id: None,
+    });
+    moves::apply_move(&mut game, &take).unwrap();
+    assert_eq!(game.get_to_move(), Player::B);
+
+    moves::apply_move(&mut game, &place_b).unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(3)]);
+    assert_eq!(game.get_to_move(), Player::A);
+
+    // Player A now has 2 coins, player B none
+    assert_eq!(game.calculate_legal_moves().len(), 3);
+}
@@ -1,4 +1,4 @@
-use nimlib::{moves, NimAction, NimRule, NimSplit, Split, Stack, TakeSize};
+use nimlib::{moves, NimAction, NimRule, NimSplit, Player, Split, Stack, TakeSize};
 
 #[test]
 fn move_any_one() {
@@ -10,7 +10,8 @@ fn move_any_one() {
 
         let stacks = vec![Stack(height)];
 
-        let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+        let moves =
+            moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
         assert_eq!(moves.len(), height as usize);
     }
@@ -26,7 +27,8 @@ fn move_any_two() {
 
         let stacks = vec![Stack(height), Stack(height)];
 
-        let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+        let moves =
+            moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
         assert_eq!(moves.len(), (height * 2) as usize);
     }
@@ -48,7 +50,8 @@ fn move_any_five() {
             Stack(height),
         ];
 
-        let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+        let moves =
+            moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
         assert_eq!(moves.len(), (height * 5) as usize);
     }
@@ -64,7 +67,8 @@ fn move_list_one() {
 
         let stacks = vec![Stack(height)];
 
-        let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+        let moves =
+            moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
         assert_eq!(moves.len(), height.clamp(0, 3) as usize);
     }
@@ -79,7 +83,8 @@ fn known_moves_simple() {
 
     let stacks = vec![Stack(10)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0))
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None)
+        .unwrap()
         .into_iter()
         .map(|mov| {
             if let NimAction::Take(take) = mov {
@@ -114,7 +119,8 @@ fn known_moves_split_always() {
 
     let stacks = vec![Stack(5)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0))
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None)
+        .unwrap()
         .into_iter()
         .map(|mov| {
             if let NimAction::Take(take) = mov {
@@ -153,7 +159,8 @@ fn known_moves_split_optional() {
 
     let stacks = vec![Stack(5)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0))
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None)
+        .unwrap()
         .into_iter()
         .map(|mov| {
             if let NimAction::Take(take) = mov {
@@ -217,7 +224,7 @@ fn empty_position_many_rules() {
 
     let stacks = vec![Stack(0)];
 
-    let moves = moves::calculate_legal_moves(&stacks, &rules, (0, 0));
+    let moves = moves::calculate_legal_moves(&stacks, &rules, &[0, 0], Player::A, None).unwrap();
 
     assert_eq!(moves.len(), 0);
 }
@@ -0,0 +1,283 @@
+use nimlib::{
+    moves::{self, MoveError},
+    solver::{self, Outcome},
+    GameError, NimAction, NimGame, NimGameBuilder, NimRule, NimSplit, Nimber, PlaceAction, Player,
+    Split, Stack, TakeAction, TakeSize,
+};
+
+fn take_any(split: Split) -> NimRule {
+    NimRule {
+        take: TakeSize::Any,
+        split,
+    }
+}
+
+fn place() -> NimRule {
+    NimRule {
+        take: TakeSize::Place,
+        split: Split::Never,
+    }
+}
+
+fn take(stack_index: usize, amount: u128, split: NimSplit<u128>) -> NimAction<u128> {
+    NimAction::Take(TakeAction {
+        stack_index,
+        amount,
+        split,
+        from: None,
+        stack_id: None,
+    })
+}
+
+#[test]
+fn stacks_beyond_u64() {
+    let mut game = NimGameBuilder::<u128>::new()
+        .rules([take_any(Split::Optional)])
+        .stacks(vec![Stack(u128::MAX), Stack(3)])
+        .stack_ids(true)
+        .build()
+        .unwrap();
+
+    let half = u128::MAX / 2;
+    moves::apply_move(
+        &mut game,
+        &take(0, 1, NimSplit::Yes(Stack(half), Stack(half))),
+    )
+    .unwrap();
+    assert_eq!(game.get_stacks(), &vec![Stack(half), Stack(half), Stack(3)]);
+
+    // Taking more coins than the stack holds is still an error
+    assert_eq!(
+        moves::apply_move(&mut game, &take(2, 4, NimSplit::No)),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 2,
+            amount: 4,
+            available: 3,
+        })
+    );
+}
+
+#[test]
+fn pools_beyond_u64() {
+    let mut game = NimGameBuilder::<u128>::new()
+        .rules([take_any(Split::Never), place()])
+        .stacks(vec![Stack(u128::from(u64::MAX))])
+        .pool_coins(Player::A, u128::from(u64::MAX))
+        .build()
+        .unwrap();
+
+    // The pool of player A can hold more than u64::MAX coins
+    let mov = NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: u128::from(u64::MAX),
+        split: NimSplit::No,
+        from: Some(Player::A),
+        stack_id: None,
+    });
+    moves::apply_move(&mut game, &mov).unwrap();
+    assert_eq!(game.get_pool_coins(Player::A), 2 * u128::from(u64::MAX));
+
+    // But not more than u128::MAX coins
+    game.set_pool_coins(Player::B, u128::MAX).unwrap();
+    game.set_stacks(vec![Stack(1)]).unwrap();
+    let place = NimAction::Place(PlaceAction {
+        stack_index: 0,
+        amount: u128::MAX,
+        from: Player::B,
+        stack_id: None,
+    });
+    assert_eq!(
+        moves::apply_move(&mut game, &place),
+        Err(MoveError::StackOverflow { stack_index: 0 })
+    );
+}
+
+#[test]
+fn nimbers_of_low_stacks() {
+    let rules = [NimRule {
+        take: TakeSize::List(vec![1, 2]),
+        split: Split::Optional,
+    }];
+    let stacks = [3, 5, 8];
+
+    let narrow = NimGame::builder()
+        .rules(rules.clone())
+        .stacks(stacks.map(Stack).to_vec())
+        .build()
+        .unwrap();
+    let wide = NimGameBuilder::<u128>::new()
+        .rules(rules.clone())
+        .stacks(stacks.map(|height| Stack(u128::from(height))).to_vec())
+        .track_nimber(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        wide.try_calculate_nimber(),
+        Ok(Nimber(u128::from(narrow.calculate_nimber().0)))
+    );
+    assert_eq!(wide.get_nimber(), wide.try_calculate_nimber().ok());
+    assert_eq!(solver::solve(&wide), solver::solve(&narrow));
+
+    // Only the nimbers of stacks up to u64::MAX can be calculated
+    assert_eq!(
+        Stack(5_u128)
+            .to_u64()
+            .unwrap()
+            .calculate_nimber(rules.to_vec(), 0),
+        Ok(narrow.get_stacks()[1]
+            .calculate_nimber(rules.to_vec(), 0)
+            .unwrap())
+    );
+    assert_eq!(Stack(1_u128 << 64).to_u64(), None);
+}
+
+#[test]
+fn solve_beyond_u64() {
+    // Nim with a single stack is won whenever the stack is not empty
+    let game = NimGameBuilder::<u128>::new()
+        .rules([take_any(Split::Never)])
+        .stacks(vec![Stack(1 << 100), Stack(0)])
+        .build()
+        .unwrap();
+    let mut moved = game.clone();
+    moves::apply_move(&mut moved, &take(0, 1 << 100, NimSplit::No)).unwrap();
+
+    assert_eq!(solver::solve(&moved), Ok(Outcome::Loss));
+}
+
+#[test]
+fn canonical_positions_agree_across_types() {
+    let narrow = NimGame::new(
+        NimGame::default().get_rules().clone(),
+        vec![Stack(7), Stack(0), Stack(2)],
+    );
+    let wide = NimGameBuilder::<u128>::new()
+        .rules([NimRule {
+            take: TakeSize::List(vec![1, 2, 3]),
+            split: Split::Never,
+        }])
+        .stacks(vec![Stack(2), Stack(7)])
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        narrow.canonical_position().fingerprint(),
+        wide.canonical_position().fingerprint()
+    );
+
+    let high = NimGameBuilder::<u128>::new()
+        .rules([NimRule {
+            take: TakeSize::List(vec![1, 2, 3]),
+            split: Split::Never,
+        }])
+        .stacks(vec![Stack(2), Stack(7 | 1 << 64)])
+        .build()
+        .unwrap();
+    assert_ne!(
+        high.canonical_position().fingerprint(),
+        wide.canonical_position().fingerprint()
+    );
+}
+
+#[test]
+fn serde_round_trip() {
+    let game = NimGameBuilder::<u128>::new()
+        .rules([take_any(Split::Never), place()])
+        .stacks(vec![Stack(u128::MAX), Stack(1)])
+        .pool_coins(Player::B, 1 << 80)
+        .build()
+        .unwrap();
+
+    let json = serde_json::to_string(&game).unwrap();
+    assert!(json.contains(&u128::MAX.to_string()));
+    assert_eq!(serde_json::from_str::<NimGame<u128>>(&json).unwrap(), game);
+
+    // Narrow games can't hold the wide stacks
+    assert!(serde_json::from_str::<NimGame>(&json).is_err());
+}
+
+#[test]
+fn nimbers_of_high_stacks_are_errors() {
+    let too_high = GameError::StackTooHigh { height: u128::MAX };
+    let builder = NimGameBuilder::<u128>::new()
+        .rules([take_any(Split::Never), place()])
+        .stacks(vec![Stack(u128::MAX)]);
+
+    assert_eq!(
+        builder.clone().track_nimber(true).build(),
+        Err(too_high.clone())
+    );
+
+    let mut game = builder.build().unwrap();
+    assert_eq!(game.try_calculate_nimber(), Err(too_high.clone()));
+    assert_eq!(game.set_track_nimber(true), Err(too_high.clone()));
+    assert_eq!(game.get_nimber(), None);
+
+    // Deserializing a game with a tracked nimber
+    let json = serde_json::to_string(&game).unwrap().replace(
+        "\"auto_normalize\":false",
+        "\"auto_normalize\":false,\"track_nimber\":true",
+    );
+    assert!(json.contains("track_nimber"));
+    assert!(serde_json::from_str::<NimGame<u128>>(&json)
+        .unwrap_err()
+        .to_string()
+        .contains("can't be calculated"));
+
+    // Tracked games keep their stacks up to u64::MAX
+    game.set_stacks(vec![Stack(1)]).unwrap();
+    game.set_track_nimber(true).unwrap();
+    assert_eq!(
+        game.set_stacks(vec![Stack(u128::MAX)]),
+        Err(too_high.clone())
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(1)]);
+
+    game.set_pool_coins(Player::A, u128::from(u64::MAX))
+        .unwrap();
+    let place = NimAction::Place(PlaceAction {
+        stack_index: 0,
+        amount: u128::from(u64::MAX),
+        from: Player::A,
+        stack_id: None,
+    });
+    assert_eq!(
+        moves::apply_move(&mut game, &place),
+        Err(MoveError::StackTooHigh { stack_index: 0 })
+    );
+    assert_eq!(game.get_stacks(), &vec![Stack(1)]);
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn big_stacks() {
+    use num_bigint::BigUint;
+
+    let height: BigUint = BigUint::from(1_u64) << 1000_u32;
+    let mut game = NimGameBuilder::<BigUint>::new()
+        .rules([take_any(Split::Always)])
+        .stacks(vec![Stack(height.clone())])
+        .build()
+        .unwrap();
+
+    let half: BigUint = &height >> 1_u32;
+    let mov = NimAction::Take(TakeAction {
+        stack_index: 0,
+        amount: BigUint::from(2_u64),
+        split: NimSplit::Yes(Stack(half.clone() - 1_u64), Stack(half.clone() - 1_u64)),
+        from: None,
+        stack_id: None,
+    });
+    moves::apply_move(&mut game, &mov).unwrap();
+    assert_eq!(
+        game.get_stacks(),
+        &vec![Stack(half.clone() - 1_u64), Stack(half - 1_u64)]
+    );
+
+    let json = serde_json::to_string(&game).unwrap();
+    assert_eq!(
+        serde_json::from_str::<NimGame<BigUint>>(&json).unwrap(),
+        game
+    );
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::sequences;
+use nimlib::{
+    moves::{self, MoveError},
+    solver::{Outcome, SolveError, Solver},
+    wythoff, NimAction, NimGame, NimRule, Nimber, PlayConvention, RuleProblem, RuleSet, Split,
+    Stack, StackTake, TakeManyAction, TakeSize,
+};
+
+fn any() -> NimRule {
+    NimRule {
+        take: TakeSize::Any,
+        split: Split::Never,
+    }
+}
+
+fn joint() -> NimRule {
+    NimRule {
+        take: TakeSize::SameFromTwoStacks,
+        split: Split::Never,
+    }
+}
+
+fn wythoff_rules() -> RuleSet {
+    RuleSet::new([joint(), any()]).unwrap()
+}
+
+fn board(a: u64, b: u64) -> NimGame {
+    NimGame::new(wythoff_rules(), vec![Stack(a), Stack(b)])
+}
+
+#[test]
+fn canonical_rules() {
+    assert!(wythoff::is_wythoff(&wythoff_rules()));
+
+    // Moves across up to 2 stacks include the joint takes
+    let rules = RuleSet::new([
+        joint(),
+        NimRule {
+            take: TakeSize::AnyFromStacks(2),
+            split: Split::Never,
+        },
+    ])
+    .unwrap();
+    assert_eq!(
+        rules.rules(),
+        &[NimRule {
+            take: TakeSize::AnyFromStacks(2),
+            split: Split::Never,
+        }]
+    );
+    assert!(!wythoff::is_wythoff(&rules));
+
+    assert_eq!(
+        RuleSet::new([NimRule {
+            take: TakeSize::SameFromTwoStacks,
+            split: Split::Always,
+        }])
+        .unwrap_err()
+        .problems,
+        vec![RuleProblem::SplitAcrossStacks { rule: 0 }]
+    );
+}
+
+#[test]
+fn joint_moves() {
+    let game = board(2, 3);
+    let moves = game.calculate_legal_moves();
+
+    // 2 + 3 moves from single stacks, and 2 joint moves
+    assert_eq!(moves.len(), 7);
+    assert!(moves.contains(&NimAction::TakeMany(TakeManyAction::joint(0, 1, 2))));
+    for mov in &moves {
+        assert!(moves::check_move(&game, mov).is_ok(), "{mov:?}");
+    }
+
+    let mut uneven = TakeManyAction::joint(0, 1, 1);
+    uneven.takes[1].amount = 2;
+    assert_eq!(
+        moves::check_move(&game, &NimAction::TakeMany(uneven)),
+        Err(MoveError::DifferentAmounts { amounts: (1, 2) })
+    );
+    assert_eq!(
+        moves::check_move(&game, &NimAction::TakeMany(TakeManyAction::joint(0, 1, 3))),
+        Err(MoveError::NotEnoughCoinsOnStack {
+            stack_index: 0,
+            amount: 3,
+            available: 2,
+        })
+    );
+
+    // Joint takes are allowed from any two stacks, but not from three
+    let game = NimGame::new(wythoff_rules(), vec![Stack(1), Stack(1), Stack(1)]);
+    assert_eq!(game.calculate_legal_moves().len(), 3 + 3);
+    let mut triple = TakeManyAction::joint(0, 1, 1);
+    triple.takes.push(StackTake {
+        stack_index: 2,
+        amount: 1,
+        stack_id: None,
+    });
+    assert_eq!(
+        moves::check_move(&game, &NimAction::TakeMany(triple)),
+        Err(MoveError::NoSuchStackRule { stack_count: 3 })
+    );
+
+    // Subtraction games may be combined with joint takes
+    let game = NimGame::new(
+        RuleSet::new([
+            joint(),
+            NimRule {
+                take: TakeSize::List(vec![1]),
+                split: Split::Never,
+            },
+        ])
+        .unwrap(),
+        vec![Stack(2), Stack(3)],
+    );
+    assert_eq!(game.calculate_legal_moves().len(), 2 + 2);
+}
+
+#[test]
+fn beatty_pairs() {
+    let golden_ratio = (1.0 + 5.0_f64.sqrt()) / 2.0;
+
+    for (n, (lower, upper)) in wythoff::p_positions().take(10_000).enumerate() {
+        let n = n as f64;
+        assert_eq!(lower, (n * golden_ratio).floor() as u64, "{n}");
+        assert_eq!(
+            upper,
+            (n * golden_ratio * golden_ratio).floor() as u64,
+            "{n}"
+        );
+    }
+}
+
+#[test]
+fn p_positions_agree() {
+    let pairs: HashSet<(u64, u64)> = wythoff::p_positions()
+        .take_while(|&(lower, _)| lower <= 500)
+        .collect();
+
+    for stacks in sequences(2, 500) {
+        let (a, b) = (stacks[0].0, stacks[1].0);
+        assert_eq!(
+            wythoff::is_p_position(&a, &b),
+            pairs.contains(&(a.min(b), a.max(b))),
+            "({a}, {b})"
+        );
+    }
+
+    // The heights may exceed u64::MAX
+    let (lower, upper) = wythoff::p_positions().nth(1_000_000).unwrap();
+    assert!(wythoff::is_p_position(
+        &u128::from(lower),
+        &u128::from(upper)
+    ));
+    assert!(!wythoff::is_p_position(&u128::MAX, &u128::MAX));
+}
+
+#[test]
+fn theorem_agrees_with_solver() {
+    for convention in [PlayConvention::Normal, PlayConvention::Misere] {
+        let mut solver = Solver::new();
+
+        for stacks in sequences(2, 12).into_iter().filter(|s| s.is_sorted()) {
+            let mut game = board(stacks[0].0, stacks[1].0);
+            game.set_convention(convention);
+
+            assert_eq!(
+                wythoff::solve(&game),
+                solver.solve(&game),
+                "{convention:?}: {stacks:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn nimbers_of_small_boards() {
+    let nimbers = wythoff::nimbers(12);
+
+    // A single stack is a heap of Nim
+    assert_eq!(nimbers[0][7], Nimber(7));
+    assert_eq!(nimbers[1][..6], [1, 2, 0, 4, 5, 3].map(Nimber));
+
+    for stacks in sequences(2, 12) {
+        let (a, b) = (stacks[0].0, stacks[1].0);
+        let game = board(a, b);
+        assert_eq!(wythoff::nimber(&game), Ok(nimbers[a as usize][b as usize]));
+        assert_eq!(
+            nimbers[a as usize][b as usize] == Nimber(0),
+            wythoff::is_p_position(&a, &b)
+        );
+
+        // The nimber is the MEX of the nimbers of the options
+        let options: HashSet<Nimber> = game
+            .calculate_legal_moves()
+            .iter()
+            .map(|mov| {
+                let mut option = game.clone();
+                moves::apply_move(&mut option, mov).unwrap();
+                wythoff::nimber(&option).unwrap()
+            })
+            .collect();
+        let mex = (0..).map(Nimber).find(|nimber| !options.contains(nimber));
+        assert_eq!(Some(nimbers[a as usize][b as usize]), mex, "({a}, {b})");
+    }
+}
+
+#[test]
+fn only_wythoff() {
+    let game = NimGame::default();
+    assert_eq!(wythoff::solve(&game), Err(SolveError::NotWythoff));
+    assert_eq!(wythoff::nimber(&game), Err(SolveError::NotWythoff));
+
+    let game = NimGame::new(
+        wythoff_rules(),
+        vec![Stack(1), Stack(0), Stack(2), Stack(3)],
+    );
+    assert_eq!(
+        wythoff::solve(&game),
+        Err(SolveError::TooManyStacks {
+            stack_count: 3,
+            max_stacks: 2,
+        })
+    );
+
+    // Empty stacks don't count
+    let game = NimGame::new(wythoff_rules(), vec![Stack(3), Stack(0), Stack(5)]);
+    assert_eq!(wythoff::solve(&game), Ok(Outcome::Loss));
+}